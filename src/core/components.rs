@@ -1,13 +1,11 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 // Bird компонент определён в bird.rs, но для использования в других модулях
 // реэкспортируем его здесь
 pub use crate::plugins::bird::Bird;
-
-#[derive(Component)]
-pub struct Collider {
-    pub size: Vec2,
-}
+// Хитбоксы описаны в hitboxes.rs вместе с их геометрией
+pub use crate::plugins::hitboxes::Collider;
 
 #[derive(Component)]
 pub struct Scrollable;
@@ -30,6 +28,9 @@ pub struct MainMenuButton;
 #[derive(Component)]
 pub struct SettingsButton;
 
+#[derive(Component)]
+pub struct WatchReplayButton;
+
 #[derive(Component)]
 pub struct GameModeSelectionButton;
 
@@ -58,7 +59,7 @@ pub struct SlowMotion {
 #[derive(Component)]
 pub struct PowerUpIndicator;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PowerUpType {
     Shield,
     DoubleScore,
@@ -81,6 +82,12 @@ pub struct ZenModeButton;
 #[derive(Component)]
 pub struct SurvivalModeButton;
 
+#[derive(Component)]
+pub struct DailyChallengeButton;
+
+#[derive(Component)]
+pub struct CoursesButton;
+
 #[derive(Component)]
 pub struct OnGameModeSelectionScreen;
 
@@ -96,5 +103,14 @@ pub struct GameModeInfoText;
 #[derive(Component)]
 pub struct StatisticsButton;
 
+#[derive(Component)]
+pub struct ProfilesButton;
+
+#[derive(Component)]
+pub struct AchievementsButton;
+
+#[derive(Component)]
+pub struct ShopButton;
+
 #[derive(Component)]
 pub struct OnStatisticsScreen;
@@ -0,0 +1,460 @@
+//! Headless-сборка игры для интеграционных тестов
+//!
+//! Поднимает `GamePlugin` поверх `MinimalPlugins` без окна и GPU:
+//! графика и звук заменяются пустыми хэндлами (с диска читаются только трассы),
+//! размер окна задаётся через `PlayArea`, время шагает фиксированной дельтой,
+//! а ввод подаётся синтетическими событиями клавиатуры, геймпадов и касаний
+//! через обычный `InputPlugin`.
+
+use crate::core::resources::{GameAssets, GameScore, PlayArea};
+use crate::core::save::SaveDir;
+use crate::core::GamePlugin;
+use crate::plugins::achievements::{
+    parse_achievements, AchievementCatalogue, AchievementCatalogueHandle,
+};
+use crate::plugins::rng::GameRng;
+use crate::plugins::shop::ShopCatalogueHandle;
+use crate::states::{app_state::AppState, game_state::GameState};
+use bevy::input::gamepad::{
+    GamepadConnection, GamepadConnectionEvent, RawGamepadButtonChangedEvent, RawGamepadEvent,
+};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey, NativeKeyCode};
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{WindowCloseRequested, WindowFocused};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Максимум кадров ожидания при переходах между состояниями
+const MAX_TRANSITION_FRAMES: usize = 10;
+
+/// Заглушка ассетов: пустые хэндлы вместо загрузки с диска
+pub fn stub_game_assets() -> GameAssets {
+    GameAssets {
+        skins: Handle::default(),
+        hitboxes: Handle::default(),
+        pipe_texture: Handle::default(),
+        font: Handle::default(),
+        jump_sound: Handle::default(),
+        score_sound: Handle::default(),
+        hit_sound: Handle::default(),
+        game_over_sound: Handle::default(),
+        powerup_collect_sound: Handle::default(),
+        powerup_spawn_sound: Handle::default(),
+        background_layers: vec![Handle::default(); 3],
+    }
+}
+
+/// Построитель headless-приложения
+pub struct HeadlessGameBuilder {
+    play_area: PlayArea,
+    frame_delta: Duration,
+    seed: Option<u64>,
+}
+
+impl Default for HeadlessGameBuilder {
+    fn default() -> Self {
+        Self {
+            play_area: PlayArea::default(),
+            frame_delta: Duration::from_secs_f64(1.0 / 60.0),
+            seed: None,
+        }
+    }
+}
+
+impl HeadlessGameBuilder {
+    /// Задаёт размер виртуального окна
+    pub fn with_window_size(mut self, width: f32, height: f32) -> Self {
+        self.play_area = PlayArea { width, height };
+        self
+    }
+
+    /// Задаёт фиксированную длительность одного кадра
+    pub fn with_frame_delta(mut self, frame_delta: Duration) -> Self {
+        self.frame_delta = frame_delta;
+        self
+    }
+
+    /// Фиксирует seed всех забегов
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Собирает приложение и прогоняет первый кадр (Startup + вход в меню)
+    pub fn build(self) -> HeadlessGame {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            StatesPlugin,
+            InputPlugin,
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(self.frame_delta))
+        .insert_resource(stub_game_assets())
+        .insert_resource(self.play_area)
+        .insert_resource(GameRng::new(self.seed))
+        // Без каталога сохранений: рекорды, повторы и настройки не читаются
+        // с диска и не пишутся на него
+        .insert_resource(SaveDir::new(None))
+        // Загрузка ассетов пропускается: сразу стартуем в Loaded
+        .insert_state(AppState::Loaded)
+        // Оконного плагина нет, а игра читает события фокуса и закрытия окна
+        .add_event::<WindowFocused>()
+        .add_event::<WindowCloseRequested>()
+        .add_plugins(GamePlugin);
+        app.finish();
+        app.cleanup();
+
+        let mut game = HeadlessGame { app };
+        game.step();
+        // Каталог магазина с диска не ждём: без него открыт только облик
+        // по умолчанию. Тесты магазина подставляют свой каталог
+        game.resource_mut::<ShopCatalogueHandle>().0 = Handle::default();
+        // Достижения тоже не ждём, а берём из файла игры, вшитого при сборке
+        let achievements = parse_achievements(include_bytes!(
+            "../../assets/achievements/catalogue.achievements.ron"
+        ))
+        .expect("файл достижений должен разбираться");
+        let achievements = game
+            .resource_mut::<Assets<AchievementCatalogue>>()
+            .add(achievements);
+        game.resource_mut::<AchievementCatalogueHandle>().0 = achievements;
+        game
+    }
+}
+
+/// Пустой временный каталог теста; удаляется вместе с guard'ом, даже если
+/// тест упал
+pub struct TempSaveDir {
+    root: PathBuf,
+}
+
+impl TempSaveDir {
+    /// Создаёт `<tmp>/<name>_<pid>`, удалив то, что осталось от прошлых запусков
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        fs::remove_dir_all(&root).ok();
+        fs::create_dir_all(&root).expect("временный каталог должен создаваться");
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Каталог сохранений в этом временном каталоге
+    pub fn save_dir(&self) -> SaveDir {
+        SaveDir::new(Some(self.root.clone()))
+    }
+}
+
+impl Drop for TempSaveDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.root).ok();
+    }
+}
+
+/// Игра, которой управляет тест
+pub struct HeadlessGame {
+    pub app: App,
+}
+
+impl HeadlessGame {
+    /// Прогоняет один кадр
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    /// Прогоняет заданное число кадров
+    pub fn step_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Нажимает клавишу на один кадр; отпускание обработается в следующем кадре
+    pub fn tap_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+        self.step();
+        self.send_key(key, ButtonState::Released);
+    }
+
+    /// Набирает текст по одному символу в кадр
+    pub fn type_text(&mut self, text: &str) {
+        for character in text.chars() {
+            let logical_key = Key::Character(character.to_string().into());
+            let key_code = KeyCode::Unidentified(NativeKeyCode::Unidentified);
+            self.send_keyboard_event(key_code, logical_key.clone(), ButtonState::Pressed);
+            self.step();
+            self.send_keyboard_event(key_code, logical_key, ButtonState::Released);
+        }
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        let logical_key = Key::Unidentified(NativeKey::Unidentified);
+        self.send_keyboard_event(key_code, logical_key, state);
+    }
+
+    fn send_keyboard_event(&mut self, key_code: KeyCode, logical_key: Key, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key,
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Подключает виртуальный геймпад и возвращает его сущность
+    pub fn connect_gamepad(&mut self) -> Entity {
+        let gamepad = self.app.world_mut().spawn_empty().id();
+        self.send_connection_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: "Тестовый геймпад".to_string(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        gamepad
+    }
+
+    /// Отключает виртуальный геймпад
+    pub fn disconnect_gamepad(&mut self, gamepad: Entity) {
+        self.send_connection_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Disconnected,
+        ));
+    }
+
+    /// Подключение и отключение приходят двумя событиями, как от gilrs
+    fn send_connection_event(&mut self, event: GamepadConnectionEvent) {
+        self.send_gamepad_event(RawGamepadEvent::Connection(event.clone()));
+        self.app.world_mut().send_event(event);
+        self.step();
+    }
+
+    /// Нажимает кнопку геймпада на один кадр
+    pub fn tap_gamepad_button(&mut self, gamepad: Entity, button: GamepadButton) {
+        self.send_gamepad_event(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+            gamepad, button, 1.0,
+        )));
+        self.step();
+        self.send_gamepad_event(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+            gamepad, button, 0.0,
+        )));
+    }
+
+    fn send_gamepad_event(&mut self, event: RawGamepadEvent) {
+        self.app.world_mut().send_event(event);
+    }
+
+    /// Касание экрана на один кадр
+    pub fn tap_screen(&mut self) {
+        self.send_touch(TouchPhase::Started);
+        self.step();
+        self.send_touch(TouchPhase::Ended);
+    }
+
+    fn send_touch(&mut self, phase: TouchPhase) {
+        self.app.world_mut().send_event(TouchInput {
+            phase,
+            position: Vec2::ZERO,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id: 0,
+        });
+    }
+
+    /// Имитирует нажатие на UI-кнопку с маркером `T`
+    pub fn click_button<T: Component>(&mut self) {
+        let world = self.app.world_mut();
+        let mut buttons = world.query_filtered::<&mut Interaction, With<T>>();
+        for mut interaction in buttons.iter_mut(world) {
+            *interaction = Interaction::Pressed;
+        }
+        self.step();
+
+        let world = self.app.world_mut();
+        for mut interaction in buttons.iter_mut(world) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    /// Крутит кадры, пока игра не перейдёт в нужное состояние
+    pub fn step_until_state(&mut self, state: GameState) -> bool {
+        for _ in 0..MAX_TRANSITION_FRAMES {
+            if self.game_state() == state {
+                return true;
+            }
+            self.step();
+        }
+        self.game_state() == state
+    }
+
+    /// Нажимает «Начать» в главном меню и сразу начинает забег, без стартового прыжка
+    /// и обратного отсчёта
+    pub fn start_run(&mut self) {
+        self.click_button::<crate::core::components::StartButton>();
+        assert!(self.step_until_state(GameState::PreGame));
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        assert!(
+            self.step_until_state(GameState::Playing),
+            "забег не начался, состояние: {:?}",
+            self.game_state()
+        );
+    }
+
+    pub fn game_state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.app.world().resource::<GameScore>().0
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.app.world_mut().resource_mut::<R>()
+    }
+
+    /// Количество сущностей с компонентом `T`
+    pub fn count<T: Component>(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world.query_filtered::<(), With<T>>().iter(world).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::resources::{GameMode, GameModeSettings};
+    use crate::plugins::{bird::Bird, pipes::Pipe};
+
+    #[test]
+    fn test_starts_in_main_menu() {
+        let game = HeadlessGameBuilder::default().build();
+        assert_eq!(game.game_state(), GameState::MainMenu);
+    }
+
+    #[test]
+    fn test_start_button_spawns_bird_and_pipes() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+
+        assert_eq!(game.count::<Bird>(), 1);
+        assert_eq!(game.count::<Pipe>(), 6);
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn test_bird_without_input_falls_out_of_bounds() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.step_frames(60);
+
+        assert_eq!(game.game_state(), GameState::GameOver);
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn test_virtual_window_size_moves_bounds() {
+        let mut game = HeadlessGameBuilder::default()
+            .with_window_size(800.0, 2000.0)
+            .build();
+        game.start_run();
+        game.step_frames(45);
+
+        assert_eq!(game.game_state(), GameState::Playing);
+    }
+
+    fn pipe_heights(game: &mut HeadlessGame) -> Vec<f32> {
+        let world = game.app.world_mut();
+        let mut heights: Vec<f32> = world
+            .query_filtered::<&Transform, With<Pipe>>()
+            .iter(world)
+            .map(|transform| transform.translation.y)
+            .collect();
+        heights.sort_by(f32::total_cmp);
+        heights
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_course() {
+        let mut first = HeadlessGameBuilder::default().with_seed(1234).build();
+        let mut second = HeadlessGameBuilder::default().with_seed(1234).build();
+        first.start_run();
+        second.start_run();
+
+        assert_eq!(first.resource::<GameRng>().seed(), 1234);
+        assert_eq!(pipe_heights(&mut first), pipe_heights(&mut second));
+    }
+
+    /// Прогоняет 3 секунды игры в дзен-режиме с прыжком каждые полсекунды
+    fn run_flapping_at_fps(fps: u32) -> (u32, f32, usize) {
+        let mut game = HeadlessGameBuilder::default()
+            .with_frame_delta(Duration::from_secs_f64(1.0 / fps as f64))
+            .with_seed(99)
+            .build();
+        game.resource_mut::<GameModeSettings>().current_mode = GameMode::Zen;
+        game.click_button::<crate::core::components::StartButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+
+        // Первый прыжок начинает забег со следующего кадра
+        game.tap_key(KeyCode::Space);
+        game.step();
+
+        let frames_per_flap = fps as usize / 2;
+        for _ in 0..5 {
+            game.step_frames(frames_per_flap - 1);
+            game.tap_key(KeyCode::Space);
+        }
+        game.step_frames(frames_per_flap - 1);
+        assert_eq!(game.game_state(), GameState::Playing);
+
+        let world = game.app.world_mut();
+        let bird_y = world
+            .query_filtered::<&Transform, With<Bird>>()
+            .single(world)
+            .translation
+            .y;
+        (game.score(), bird_y, game.count::<Pipe>())
+    }
+
+    #[test]
+    fn test_simulation_does_not_depend_on_frame_rate() {
+        let at_64 = run_flapping_at_fps(64);
+        assert!(at_64.0 > 0);
+        assert_eq!(run_flapping_at_fps(32), at_64);
+        assert_eq!(run_flapping_at_fps(256), at_64);
+    }
+
+    #[test]
+    fn test_flapping_in_zen_mode_scores_points() {
+        let mut game = HeadlessGameBuilder::default()
+            .with_frame_delta(Duration::from_secs_f64(1.0 / 30.0))
+            .build();
+        game.resource_mut::<GameModeSettings>().current_mode = GameMode::Zen;
+        game.start_run();
+
+        // Прыжок каждые полсекунды удерживает птицу около центра
+        for _ in 0..8 {
+            game.tap_key(KeyCode::Space);
+            game.step_frames(14);
+        }
+
+        assert_eq!(game.game_state(), GameState::Playing);
+        assert!(game.score() > 0);
+    }
+}
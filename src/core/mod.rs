@@ -1,48 +1,99 @@
 pub mod components;
 pub mod difficulty_types;
+#[cfg(test)]
+pub mod headless;
 pub mod resources;
+pub mod save;
 pub mod systems;
 pub mod utils;
 
 use self::components::{
-    ExitButton, MenuButton,  SettingsButton, StartButton, StatisticsButton,
+    AchievementsButton, ExitButton, MenuButton, ProfilesButton, SettingsButton, ShopButton,
+    StartButton, StatisticsButton,
 };
-use self::resources::{GameAssets, GameMode, GameModeSettings, GameOverUIState, GameScore};
+use self::resources::{GameAssets, GameOverUIState, GameScore, PlayArea};
 use self::systems::{
     handle_menu_button_clicks, menu_button_hover_effect, transition_to_game_state,
 };
+use self::save::SaveDir;
 use self::utils::despawn_entities;
 use crate::plugins::{
-    asset_loader::AssetLoaderPlugin, audio::AudioPlugin, background::BackgroundPlugin,
-    bird::BirdPlugin, difficulty::DifficultyPlugin, effects::EffectsPlugin,
-    game_modes::GameModesPlugin, game_over::GameOverPlugin, high_score::HighScorePlugin,
-    pipes::PipesPlugin, powerups::PowerUpsPlugin,
-    progressive_difficulty::ProgressiveDifficultyPlugin, settings_ui::SettingsUIPlugin,
+    achievements::AchievementsPlugin,
+    asset_loader::AssetLoaderPlugin,
+    audio::AudioPlugin,
+    background::BackgroundPlugin,
+    bird::BirdPlugin,
+    coins::CoinsPlugin,
+    controls_ui::ControlsUIPlugin,
+    courses::CoursesPlugin,
+    daily::DailyPlugin,
+    difficulty::DifficultyPlugin,
+    editor::EditorPlugin,
+    effects::EffectsPlugin,
+    game_modes::GameModesPlugin,
+    game_over::GameOverPlugin,
+    ghost::GhostPlugin,
+    high_score::HighScorePlugin,
+    hitboxes::HitboxesPlugin,
+    input_map::InputMapPlugin,
+    obstacles::ObstaclesPlugin,
+    pause::PausePlugin,
+    pipes::PipesPlugin,
+    powerups::PowerUpsPlugin,
+    preferences::PreferencesPlugin,
+    pregame::PreGamePlugin,
+    profiles::{Profiles, ProfilesPlugin},
+    progressive_difficulty::ProgressiveDifficultyPlugin,
+    replay::{is_replay_playing, ReplayPlugin},
+    rng::RngPlugin,
+    settings_ui::SettingsUIPlugin,
+    shop::ShopPlugin,
+    simulation::SimulationPlugin,
+    skins::SkinsPlugin,
     statistics::StatisticsPlugin,
 };
 use crate::states::app_state::AppState;
-use crate::states::game_state::{EffectsSet, GameState};
+use crate::states::game_state::{EffectsSet, GameState, PreGameSet};
 use bevy::prelude::*;
 use bevy::text::{TextColor, TextFont};
 use bevy::ui::{
     AlignItems, BorderRadius,  FlexDirection, JustifyContent, Node, Overflow, Val,
 };
+use bevy::window::PrimaryWindow;
 
 pub struct GamePlugin;
 
 #[derive(Component)]
 pub struct OnMainMenuScreen;
 
-#[derive(Component)]
-pub struct StateDisplayText;
-
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GameScore>()
             .init_resource::<GameOverUIState>()
+            .init_resource::<PlayArea>()
+            .init_resource::<SaveDir>()
             .init_state::<GameState>()
+            .configure_sets(
+                OnEnter(GameState::PreGame),
+                (PreGameSet::Cleanup, PreGameSet::Seed, PreGameSet::Spawn).chain(),
+            )
             .add_plugins((
                 AssetLoaderPlugin,
+                InputMapPlugin,
+                RngPlugin,
+                SimulationPlugin,
+                ReplayPlugin,
+                GhostPlugin,
+                PreGamePlugin,
+                PreferencesPlugin,
+                ProfilesPlugin,
+                AchievementsPlugin,
+                DailyPlugin,
+                CoursesPlugin,
+                EditorPlugin,
+                ObstaclesPlugin,
+            ))
+            .add_plugins((
                 AudioPlugin,
                 BackgroundPlugin,
                 BirdPlugin,
@@ -50,6 +101,8 @@ impl Plugin for GamePlugin {
                 DifficultyPlugin,
                 ProgressiveDifficultyPlugin,
                 SettingsUIPlugin,
+                ControlsUIPlugin,
+                PausePlugin,
                 EffectsPlugin,
                 HighScorePlugin,
                 PowerUpsPlugin,
@@ -57,20 +110,21 @@ impl Plugin for GamePlugin {
                 StatisticsPlugin,
                 GameOverPlugin,
             ))
+            .add_plugins((CoinsPlugin, ShopPlugin, SkinsPlugin, HitboxesPlugin))
             .add_systems(
                 Startup,
-                (setup.in_set(EffectsSet::SpawnCam), spawn_state_ui),
+                setup.in_set(EffectsSet::SpawnCam),
             )
             .add_systems(
                 OnEnter(AppState::Loaded),
-                (init_game_state, init_default_game_mode),
+                init_game_state,
             )
+            .add_systems(PreUpdate, sync_play_area_with_window)
             .add_systems(OnEnter(GameState::PreGame), reset_score)
             .add_systems(
                 Update,
                 (
-                    transition_to_game_state,
-                    pregame_to_playing.run_if(in_state(GameState::PreGame)),
+                    transition_to_game_state.run_if(not(is_replay_playing)),
                     handle_menu_button_clicks
                         .run_if(in_state(GameState::MainMenu).or(in_state(GameState::GameOver).or(in_state(GameState::Statistics)))),
                     menu_button_hover_effect
@@ -78,11 +132,6 @@ impl Plugin for GamePlugin {
                 )
                     .run_if(in_state(AppState::Loaded)),
             )
-            .add_systems(
-                Update,
-                update_state_display
-                    .run_if(state_changed::<AppState>.or(state_changed::<GameState>)),
-            )
             .add_systems(
                 OnEnter(GameState::MainMenu),
                 spawn_main_menu.run_if(in_state(AppState::Loaded)),
@@ -95,6 +144,12 @@ impl Plugin for GamePlugin {
                 OnExit(GameState::MainMenu),
                 despawn_entities::<OnMainMenuScreen>,
             );
+
+        #[cfg(feature = "dev")]
+        app.add_plugins((
+            crate::plugins::debug_overlay::DebugOverlayPlugin,
+            crate::plugins::console::ConsolePlugin,
+        ));
     }
 }
 
@@ -102,60 +157,31 @@ fn init_game_state(mut next_state: ResMut<NextState<GameState>>) {
     next_state.set(GameState::MainMenu);
 }
 
-fn init_default_game_mode(mut mode_settings: ResMut<GameModeSettings>) {
-    mode_settings.current_mode = GameMode::Classic;
-    mode_settings.time_limit = None;
-    mode_settings.target_score = None;
-    mode_settings.lives = None;
-    mode_settings.difficulty_multiplier = 1.0;
-}
-
 fn setup(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
-fn reset_score(mut score: ResMut<GameScore>) {
-    score.0 = 0;
-}
-
-fn pregame_to_playing(mut next_state: ResMut<NextState<GameState>>) {
-    next_state.set(GameState::Playing);
-}
-
-fn spawn_state_ui(mut commands: Commands, asset: Res<GameAssets>) {
-    commands
-        .spawn((Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(20.0),
-            flex_direction: FlexDirection::Column,
-            align_items: AlignItems::Center,
-            ..default()
-        },))
-        .with_children(|parent| {
-            parent.spawn((
-                Text::new("app state: Loading"), // начальный текст
-                TextFont {
-                    font: asset.font.clone(),
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-                StateDisplayText, // маркер для поиска
-            ));
-        });
-}
-
-fn update_state_display(
-    mut query: Query<&mut Text, With<StateDisplayText>>,
-    app_state: Res<State<AppState>>,
-    game_state: Res<State<GameState>>,
+/// Подгоняет размеры игрового поля под основное окно (если оно есть)
+fn sync_play_area_with_window(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut play_area: ResMut<PlayArea>,
 ) {
-    for mut text in &mut query {
-        **text = format!("app: {:?} | game: {:?}", app_state.get(), game_state.get());
+    if let Ok(window) = windows.get_single() {
+        let size = PlayArea {
+            width: window.width(),
+            height: window.height(),
+        };
+        if *play_area != size {
+            *play_area = size;
+        }
     }
 }
 
-fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
+fn reset_score(mut score: ResMut<GameScore>) {
+    score.0 = 0;
+}
+
+fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>, profiles: Res<Profiles>) {
     commands
         .spawn((
             Node {
@@ -178,7 +204,7 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                 },
                 TextColor(Color::WHITE),
                 Node {
-                    margin: UiRect::bottom(Val::Px(40.0)),
+                    margin: UiRect::bottom(Val::Px(8.0)),
                     ..default()
                 },
             ));
@@ -188,10 +214,39 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                     Button,
                     Node {
                         width: Val::Px(200.0),
-                        height: Val::Px(50.0),
+                        height: Val::Px(40.0),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(8.0)),
+                    BackgroundColor(Color::srgb(0.15, 0.35, 0.25)),
+                    MenuButton,
+                    ProfilesButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("👤 {}", profiles.active().name)),
+                        TextFont {
+                            font: asset.font.clone(),
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(200.0),
+                        height: Val::Px(46.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
-                        margin: UiRect::bottom(Val::Px(20.0)),
+                        margin: UiRect::bottom(Val::Px(8.0)),
                         overflow: Overflow::clip(),
                         ..default()
                     },
@@ -217,10 +272,10 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                     Button,
                     Node {
                         width: Val::Px(200.0),
-                        height: Val::Px(50.0),
+                        height: Val::Px(46.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
-                        margin: UiRect::bottom(Val::Px(20.0)),
+                        margin: UiRect::bottom(Val::Px(8.0)),
                         overflow: Overflow::clip(),
                         ..default()
                     },
@@ -246,10 +301,10 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                     Button,
                     Node {
                         width: Val::Px(200.0),
-                        height: Val::Px(50.0),
+                        height: Val::Px(46.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
-                        margin: UiRect::bottom(Val::Px(20.0)),
+                        margin: UiRect::bottom(Val::Px(8.0)),
                         overflow: Overflow::clip(),
                         ..default()
                     },
@@ -275,10 +330,68 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                     Button,
                     Node {
                         width: Val::Px(200.0),
-                        height: Val::Px(50.0),
+                        height: Val::Px(46.0),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(8.0)),
+                    BackgroundColor(Color::srgb(0.4, 0.3, 0.1)),
+                    MenuButton,
+                    AchievementsButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Достижения"),
+                        TextFont {
+                            font: asset.font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(200.0),
+                        height: Val::Px(46.0),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(8.0)),
+                    BackgroundColor(Color::srgb(0.45, 0.35, 0.05)),
+                    MenuButton,
+                    ShopButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Магазин"),
+                        TextFont {
+                            font: asset.font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(200.0),
+                        height: Val::Px(46.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
-                        margin: UiRect::bottom(Val::Px(20.0)),
+                        margin: UiRect::bottom(Val::Px(8.0)),
                         overflow: Overflow::clip(),
                         ..default()
                     },
@@ -304,7 +417,7 @@ fn spawn_main_menu(mut commands: Commands, asset: Res<GameAssets>) {
                     Button,
                     Node {
                         width: Val::Px(200.0),
-                        height: Val::Px(50.0),
+                        height: Val::Px(46.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
                         overflow: Overflow::clip(),
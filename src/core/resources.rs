@@ -1,9 +1,14 @@
+use crate::core::save::SaveData;
+use crate::plugins::hitboxes::Hitboxes;
+use crate::plugins::skins::SkinCatalogue;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Resource)]
 pub struct GameAssets {
-    pub bird_textures: Vec<Handle<Image>>,
+    /// Облики птицы с кадрами анимации
+    pub skins: Handle<SkinCatalogue>,
+    pub hitboxes: Handle<Hitboxes>,
     pub pipe_texture: Handle<Image>,
     pub font: Handle<Font>,
     // Аудио ассеты
@@ -20,6 +25,25 @@ pub struct GameAssets {
 #[derive(Resource, Default)]
 pub struct GameScore(pub u32);
 
+/// Размеры игрового поля.
+///
+/// В игре синхронизируются с основным окном, в headless-тестах задаются вручную,
+/// поэтому игровые системы не обращаются к `Window` напрямую.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PlayArea {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+}
+
 /// Рекорды игры с историей
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct HighScores {
@@ -36,12 +60,20 @@ impl Default for HighScores {
     }
 }
 
+impl SaveData for HighScores {
+    const FILE_NAME: &'static str = "high_scores.json";
+    const VERSION: u32 = 1;
+}
+
 /// Одна запись в таблице рекордов
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HighScoreEntry {
     pub score: u32,
     pub date: String,
     pub difficulty: String,
+    /// Имя файла повтора этого забега
+    #[serde(default)]
+    pub replay: Option<String>,
 }
 
 impl HighScoreEntry {
@@ -50,6 +82,7 @@ impl HighScoreEntry {
             score,
             date: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
             difficulty,
+            replay: None,
         }
     }
 }
@@ -84,28 +117,55 @@ pub struct ActivePowerUps {
 }
 
 /// Игровые режимы
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameMode {
     Classic,
     TimeAttack,
     Zen,
     Survival,
+    /// Трасса из файла
+    Course,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for GameMode {
     fn default() -> Self {
         GameMode::Classic
     }
 }
 
+impl GameMode {
+    /// Возвращает русское название режима
+    pub fn russian_name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Классика",
+            GameMode::TimeAttack => "Гонка на время",
+            GameMode::Zen => "Дзен",
+            GameMode::Survival => "Выживание",
+            GameMode::Course => "Трасса",
+        }
+    }
+}
+
+/// Как начинается забег после подготовки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartStyle {
+    /// Птица парит на месте, пока игрок не прыгнет
+    #[default]
+    TapToStart,
+    /// Обратный отсчёт на заданное число секунд
+    Countdown(u32),
+}
+
 /// Настройки игровых режимов
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct GameModeSettings {
     pub current_mode: GameMode,
     pub time_limit: Option<f32>,
     pub target_score: Option<u32>,
     pub lives: Option<u32>,
     pub difficulty_multiplier: f32,
+    pub start_style: StartStyle,
 }
 
 impl Default for GameModeSettings {
@@ -116,6 +176,7 @@ impl Default for GameModeSettings {
             target_score: None,
             lives: None,
             difficulty_multiplier: 1.0,
+            start_style: StartStyle::TapToStart,
         }
     }
 }
@@ -127,6 +188,16 @@ pub struct GameTimer {
     pub is_active: bool,
 }
 
+#[cfg(feature = "dev")]
+pub use crate::plugins::console::is_cheated_run;
+
+/// Условие запуска: текущий забег изменён из консоли. Без feature `dev`
+/// консоли нет, и каждый забег зачётный
+#[cfg(not(feature = "dev"))]
+pub fn is_cheated_run() -> bool {
+    false
+}
+
 /// Ресурс для отслеживания жизней в режиме выживания
 #[derive(Resource, Default)]
 pub struct SurvivalLives {
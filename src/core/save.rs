@@ -0,0 +1,331 @@
+//! Сохранённые данные игрока
+//!
+//! Все файлы лежат в одном каталоге (`SaveDir`), данные игрока — в подкаталоге
+//! его профиля. Файлы записываются в одном формате:
+//! `{"version": N, "data": ...}`. Файлы без обёртки считаются версией 0 — так
+//! сохраняли старые сборки. При чтении старые версии приводятся к текущей через
+//! `SaveData::migrate`. Запись атомарная: сначала временный файл, затем
+//! переименование. Файл, который не удалось прочитать, не затирается
+//! значениями по умолчанию, а переименовывается в резервную копию.
+
+use crate::core::utils::arg_value;
+use bevy::prelude::*;
+use bevy::window::WindowCloseRequested;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Каталог сохранений; `root: None` отключает чтение и запись на диск.
+///
+/// Данные игрока лежат в подкаталоге активного профиля `profiles/<id>`,
+/// общие файлы (список профилей) — в корне.
+#[derive(Resource, Debug, Clone)]
+pub struct SaveDir {
+    pub root: Option<PathBuf>,
+    /// Активный профиль; `None` — старая раскладка без профилей
+    pub profile: Option<String>,
+}
+
+impl Default for SaveDir {
+    fn default() -> Self {
+        Self::new(Some(
+            dirs::data_dir()
+                .map(|path| path.join("my_project"))
+                // Если системного каталога нет, сохраняем рядом с игрой
+                .unwrap_or_else(|| PathBuf::from(".")),
+        ))
+    }
+}
+
+impl SaveDir {
+    pub fn new(root: Option<PathBuf>) -> Self {
+        Self {
+            root,
+            profile: None,
+        }
+    }
+
+    /// Каталог из `--save-dir <путь>` или каталог по умолчанию
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        match arg_value(args, "--save-dir") {
+            Some(path) => Self::new(Some(PathBuf::from(path))),
+            None => Self::default(),
+        }
+    }
+
+    /// Путь к файлу или подкаталогу с данными активного профиля
+    pub fn path(&self, name: &str) -> Option<PathBuf> {
+        match &self.profile {
+            Some(profile) => self.profile_path(profile, name),
+            None => self.shared_path(name),
+        }
+    }
+
+    /// Путь к файлу или подкаталогу в данных профиля `profile`
+    pub fn profile_path(&self, profile: &str, name: &str) -> Option<PathBuf> {
+        self.shared_path("profiles")
+            .map(|profiles| profiles.join(profile).join(name))
+    }
+
+    /// Путь к общему файлу в корне каталога сохранений
+    pub fn shared_path(&self, name: &str) -> Option<PathBuf> {
+        self.root.as_ref().map(|root| root.join(name))
+    }
+}
+
+/// Условие для систем сохранения: игра закрывается (`AppExit`) или закрывают окно
+pub fn is_app_exiting(
+    mut exits: EventReader<AppExit>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+) -> bool {
+    let exiting = exits.read().count() > 0;
+    let closing = close_requests.read().count() > 0;
+    exiting || closing
+}
+
+/// Данные, которые хранятся в отдельном файле каталога сохранений
+pub trait SaveData: Serialize + DeserializeOwned {
+    /// Имя файла в каталоге сохранений
+    const FILE_NAME: &'static str;
+    /// Текущая версия формата
+    const VERSION: u32;
+
+    /// Переводит данные версии `version` на одну версию вперёд
+    fn migrate(version: u32, data: Value) -> Result<Value, String> {
+        // Версия 1 отличается от 0 только обёрткой с номером версии
+        match version {
+            0 => Ok(data),
+            _ => Err(format!("нет миграции с версии {}", version)),
+        }
+    }
+}
+
+/// Разворачивает обёртку с версией; файлы без обёртки считаются версией 0
+fn unwrap_versioned(value: Value) -> Result<(u32, Value), String> {
+    match value {
+        Value::Object(mut object)
+            if object.len() == 2
+                && object.contains_key("version")
+                && object.contains_key("data") =>
+        {
+            let version = object["version"]
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or("неверный номер версии")?;
+            Ok((version, object.remove("data").unwrap_or_default()))
+        }
+        other => Ok((0, other)),
+    }
+}
+
+/// Разбирает содержимое файла, применяя миграции до текущей версии
+pub fn decode<T: SaveData>(content: &str) -> Result<T, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let (mut version, mut data) = unwrap_versioned(value)?;
+    if version > T::VERSION {
+        return Err(format!(
+            "файл создан более новой версией игры ({} > {})",
+            version,
+            T::VERSION
+        ));
+    }
+    while version < T::VERSION {
+        data = T::migrate(version, data)?;
+        version += 1;
+    }
+    serde_json::from_value(data).map_err(|e| e.to_string())
+}
+
+/// Упаковывает данные в обёртку с текущей версией
+pub fn encode<T: SaveData>(data: &T) -> Result<String, String> {
+    let data = serde_json::to_value(data).map_err(|e| e.to_string())?;
+    let mut object = serde_json::Map::new();
+    object.insert("version".to_string(), Value::from(T::VERSION));
+    object.insert("data".to_string(), data);
+    serde_json::to_string_pretty(&Value::Object(object)).map_err(|e| e.to_string())
+}
+
+/// Читает файл `T` активного профиля. `None`, если файла нет, диск отключён или файл повреждён;
+/// повреждённый файл сохраняется рядом как резервная копия
+pub fn load<T: SaveData>(save_dir: &SaveDir) -> Option<T> {
+    let path = save_dir.path(T::FILE_NAME)?;
+    load_from(&path)
+}
+
+/// Читает файл по пути с теми же правилами, что и `load`
+pub fn load_from<T: SaveData>(path: &Path) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+
+    let decoded = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| decode::<T>(&content));
+    match decoded {
+        Ok(data) => Some(data),
+        Err(e) => {
+            eprintln!("Ошибка чтения {}: {}", path.display(), e);
+            match back_up_corrupt(path) {
+                Ok(backup) => eprintln!("Файл сохранён как {}", backup.display()),
+                Err(e) => eprintln!("Не удалось сохранить резервную копию: {}", e),
+            }
+            None
+        }
+    }
+}
+
+/// Записывает файл `T` в каталог активного профиля
+pub fn save<T: SaveData>(save_dir: &SaveDir, data: &T) {
+    if let Some(path) = save_dir.path(T::FILE_NAME) {
+        save_to(&path, data);
+    }
+}
+
+/// Записывает файл `T` по пути
+pub fn save_to<T: SaveData>(path: &Path, data: &T) {
+    if let Err(e) = encode(data).and_then(|json| write_atomic(path, json.as_bytes())) {
+        eprintln!("Ошибка сохранения {}: {}", path.display(), e);
+    }
+}
+
+/// Записывает файл целиком или не трогает его: пишет во временный файл и
+/// переименовывает его поверх старого
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if result.is_err() {
+        fs::remove_file(&tmp_path).ok();
+    }
+    result.map_err(|e| e.to_string())
+}
+
+/// Переименовывает нечитаемый файл в `<имя>.corrupt-<время>`
+fn back_up_corrupt(path: &Path) -> std::io::Result<PathBuf> {
+    let mut backup_name = path.file_name().unwrap_or_default().to_os_string();
+    backup_name.push(format!(
+        ".corrupt-{}",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+    ));
+    let backup = path.with_file_name(backup_name);
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::TempSaveDir;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        count: u32,
+        label: String,
+    }
+
+    impl SaveData for Counter {
+        const FILE_NAME: &'static str = "counter.json";
+        const VERSION: u32 = 2;
+
+        fn migrate(version: u32, mut data: Value) -> Result<Value, String> {
+            match version {
+                0 => Ok(data),
+                // В версии 2 появилась подпись
+                1 => {
+                    data["label"] = Value::from("без подписи");
+                    Ok(data)
+                }
+                _ => Err(format!("нет миграции с версии {}", version)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_writes_version_and_leaves_no_temp_file() {
+        let temp = TempSaveDir::new("save_test_roundtrip");
+        let save_dir = temp.save_dir();
+        let counter = Counter {
+            count: 3,
+            label: "три".to_string(),
+        };
+        save(&save_dir, &counter);
+
+        let files: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        let content = fs::read_to_string(temp.path().join("counter.json")).unwrap();
+        let loaded = load::<Counter>(&save_dir);
+
+        assert_eq!(files, vec!["counter.json"]);
+        assert!(content.contains("\"version\": 2"));
+        assert_eq!(loaded, Some(counter));
+    }
+
+    #[test]
+    fn test_unversioned_file_is_migrated() {
+        let loaded: Counter = decode(r#"{"count": 7}"#).unwrap();
+        assert_eq!(loaded.count, 7);
+        assert_eq!(loaded.label, "без подписи");
+
+        let newer = r#"{"version": 3, "data": {"count": 1, "label": ""}}"#;
+        assert!(decode::<Counter>(newer).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_file_is_kept_as_backup() {
+        let temp = TempSaveDir::new("save_test_corrupt");
+        let root = temp.path();
+        fs::write(root.join("counter.json"), "{ не json").unwrap();
+
+        let loaded = load::<Counter>(&temp.save_dir());
+        let backups: Vec<String> = fs::read_dir(root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        let backup_content = backups
+            .first()
+            .map(|name| fs::read_to_string(root.join(name)).unwrap());
+
+        assert_eq!(loaded, None);
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("counter.json.corrupt-"));
+        assert_eq!(backup_content.as_deref(), Some("{ не json"));
+    }
+
+    #[test]
+    fn test_save_dir_from_args() {
+        let args = ["game", "--save-dir", "/tmp/flappy"].map(String::from);
+        assert_eq!(
+            SaveDir::from_args(args).path("a.json"),
+            Some(PathBuf::from("/tmp/flappy/a.json"))
+        );
+        assert!(SaveDir::new(None).path("a.json").is_none());
+
+        let mut save_dir = SaveDir::new(Some(PathBuf::from("/saves")));
+        save_dir.profile = Some("anna".to_string());
+        assert_eq!(
+            save_dir.path("a.json"),
+            Some(PathBuf::from("/saves/profiles/anna/a.json"))
+        );
+        assert_eq!(
+            save_dir.shared_path("profiles.json"),
+            Some(PathBuf::from("/saves/profiles.json"))
+        );
+    }
+}
@@ -1,24 +1,39 @@
 use crate::core::components::{
-    ExitButton, GameModeSelectionButton, MainMenuButton, MenuButton, RestartButton, SettingsButton,
-    StartButton, StatisticsButton,
+    AchievementsButton, ExitButton, GameModeSelectionButton, MainMenuButton, MenuButton,
+    ProfilesButton, RestartButton, SettingsButton, ShopButton, StartButton, StatisticsButton,
 };
+use crate::plugins::input_map::{ActionState, GameAction};
 use crate::states::game_state::GameState;
 use bevy::prelude::*;
 
 pub fn transition_to_game_state(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
     mut exit: EventWriter<AppExit>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space) && current_state.get() == &GameState::GameOver {
-        next_state.set(GameState::MainMenu);
+    // Касание или клик по кнопке меню — это нажатие кнопки, а не подтверждение
+    let button_pressed = buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    let confirmed = actions.just_pressed(GameAction::Confirm) && !button_pressed;
+
+    if confirmed && current_state.get() == &GameState::MainMenu {
+        next_state.set(GameState::PreGame);
     }
 
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if current_state.get() == &GameState::GameOver {
+        if confirmed {
+            next_state.set(GameState::MainMenu);
+        }
+        if actions.just_pressed(GameAction::Restart) {
+            next_state.set(GameState::PreGame);
+        }
+    }
+
+    if actions.just_pressed(GameAction::Back) {
         match current_state.get() {
-            GameState::Playing => next_state.set(GameState::Paused),
-            GameState::Paused => next_state.set(GameState::Playing),
             GameState::GameOver => {
                 exit.send(AppExit::Success);
             }
@@ -27,16 +42,29 @@ pub fn transition_to_game_state(
             }
             GameState::Settings => next_state.set(GameState::MainMenu),
             GameState::GameModeSelection => next_state.set(GameState::MainMenu),
+            GameState::CourseSelection => next_state.set(GameState::GameModeSelection),
+            GameState::Editor => next_state.set(GameState::CourseSelection),
             GameState::Statistics => next_state.set(GameState::MainMenu),
+            GameState::Profiles => next_state.set(GameState::MainMenu),
+            GameState::Achievements => next_state.set(GameState::MainMenu),
+            GameState::Shop => next_state.set(GameState::MainMenu),
+            GameState::Customization => next_state.set(GameState::Shop),
             _ => {}
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_menu_button_clicks(
     start_button_query: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
     settings_button_query: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
     statistics_button_query: Query<&Interaction, (Changed<Interaction>, With<StatisticsButton>)>,
+    profiles_button_query: Query<&Interaction, (Changed<Interaction>, With<ProfilesButton>)>,
+    achievements_button_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<AchievementsButton>),
+    >,
+    shop_button_query: Query<&Interaction, (Changed<Interaction>, With<ShopButton>)>,
     game_mode_button_query: Query<
         &Interaction,
         (Changed<Interaction>, With<GameModeSelectionButton>),
@@ -67,6 +95,24 @@ pub fn handle_menu_button_clicks(
         }
     }
 
+    for interaction in &profiles_button_query {
+        if *interaction == Interaction::Pressed && current_state.get() == &GameState::MainMenu {
+            next_state.set(GameState::Profiles);
+        }
+    }
+
+    for interaction in &achievements_button_query {
+        if *interaction == Interaction::Pressed && current_state.get() == &GameState::MainMenu {
+            next_state.set(GameState::Achievements);
+        }
+    }
+
+    for interaction in &shop_button_query {
+        if *interaction == Interaction::Pressed && current_state.get() == &GameState::MainMenu {
+            next_state.set(GameState::Shop);
+        }
+    }
+
     for interaction in &game_mode_button_query {
         if *interaction == Interaction::Pressed && current_state.get() == &GameState::MainMenu {
             next_state.set(GameState::GameModeSelection);
@@ -98,6 +144,7 @@ pub fn handle_menu_button_clicks(
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn menu_button_hover_effect(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
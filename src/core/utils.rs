@@ -1,5 +1,27 @@
 use bevy::prelude::*;
 
+/// Достаёт значение параметра командной строки: `--name value` или `--name=value`
+pub fn arg_value(args: impl IntoIterator<Item = String>, name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Хэш FNV-1a: стабилен между запусками и версиями, в отличие от `DefaultHasher`
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 pub fn despawn_entities<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
     for entity in &query {
         commands.entity(entity).despawn_recursive();
@@ -1,4 +1,7 @@
+use crate::core::save::SaveDir;
 use crate::core::GamePlugin;
+use crate::plugins::replay::{replay_from_args, ReplayPlayback};
+use crate::plugins::rng::{seed_from_args, GameRng};
 use crate::states::app_state::AppState;
 use bevy::prelude::*;
 
@@ -7,6 +10,8 @@ mod plugins;
 mod states;
 
 fn main() {
+    let save_dir = SaveDir::from_args(std::env::args());
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -17,6 +22,12 @@ fn main() {
             ..default()
         }))
         .init_state::<AppState>()
+        .insert_resource(GameRng::new(seed_from_args(std::env::args())))
+        .insert_resource(ReplayPlayback::with_queued(replay_from_args(
+            std::env::args(),
+            &save_dir,
+        )))
+        .insert_resource(save_dir)
         .add_plugins(GamePlugin)
         .run();
 }
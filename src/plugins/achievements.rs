@@ -0,0 +1,767 @@
+//! Достижения
+//!
+//! Достижения описаны в `assets/achievements/catalogue.achievements.ron` и
+//! загружаются как ассет: у каждого есть цель и порог.
+//! Прогресс копится из игровых событий и хранится в `achievements.json` профиля.
+//! Открытое во время забега достижение показывается всплывающим уведомлением.
+
+use crate::{
+    core::{
+        components::PowerUpType,
+        resources::{is_cheated_run, GameAssets, GameMode, GameModeSettings, GameScore},
+        save::{self, is_app_exiting, SaveData, SaveDir},
+        utils::despawn_entities,
+    },
+    plugins::{
+        audio::ScoreEvent, editor::is_editor_playtest, powerups::PowerUpCollectedEvent,
+        profiles::ProfileSwitched, replay::is_replay_playing, settings_ui::spawn_menu_button,
+        simulation::RunClock,
+    },
+    states::game_state::GameState,
+};
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+use bevy::ui::{
+    AlignItems, FlexDirection, JustifyContent, Node, Overflow, PositionType, UiRect, Val,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+/// Файл достижений внутри `assets`
+const CATALOGUE_PATH: &str = "achievements/catalogue.achievements.ron";
+
+/// Сколько секунд висит уведомление
+const TOAST_DURATION: f32 = 3.0;
+
+/// Что нужно сделать для достижения
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum AchievementGoal {
+    /// Набрать очки за один забег; `None` — в любом режиме
+    Score { points: u32, mode: Option<GameMode> },
+    /// Подобрать power-up
+    CollectPowerUp(PowerUpType),
+    /// Продержаться в режиме за один забег
+    Survive { mode: GameMode, seconds: u32 },
+    /// Набрать очки в режиме, не подобрав ни одного щита
+    ScoreWithoutShield { mode: GameMode, points: u32 },
+    /// Сыграть забегов за всё время
+    GamesPlayed(u32),
+    /// Пройти труб за всё время
+    PipesPassed(u32),
+}
+
+impl AchievementGoal {
+    /// Значение прогресса, при котором достижение открывается
+    pub fn target(&self) -> u32 {
+        match *self {
+            AchievementGoal::Score { points, .. } => points,
+            AchievementGoal::CollectPowerUp(_) => 1,
+            AchievementGoal::Survive { seconds, .. } => seconds,
+            AchievementGoal::ScoreWithoutShield { points, .. } => points,
+            AchievementGoal::GamesPlayed(games) => games,
+            AchievementGoal::PipesPassed(pipes) => pipes,
+        }
+    }
+}
+
+/// Описание достижения
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Achievement {
+    /// Ключ в файле сохранения; не меняется между версиями
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub goal: AchievementGoal,
+}
+
+/// Все достижения игры из файла
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Deserialize)]
+pub struct AchievementCatalogue {
+    pub achievements: Vec<Achievement>,
+}
+
+impl AchievementCatalogue {
+    /// Проверяет, что ключи уникальны, а пороги не нулевые
+    pub fn validate(&self) -> Result<(), AchievementsLoadError> {
+        let mut ids = HashSet::new();
+        for achievement in &self.achievements {
+            if achievement.id.is_empty() || !ids.insert(achievement.id.as_str()) {
+                return Err(AchievementsLoadError::Invalid(format!(
+                    "ключ достижения «{}» пустой или повторяется",
+                    achievement.id
+                )));
+            }
+            if achievement.goal.target() == 0 {
+                return Err(AchievementsLoadError::Invalid(format!(
+                    "достижение «{}» открывается без усилий: порог равен нулю",
+                    achievement.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ошибка загрузки списка достижений
+#[derive(Debug)]
+pub enum AchievementsLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for AchievementsLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AchievementsLoadError::Io(error) => {
+                write!(f, "не удалось прочитать достижения: {}", error)
+            }
+            AchievementsLoadError::Parse(error) => write!(f, "ошибка в достижениях: {}", error),
+            AchievementsLoadError::Invalid(error) => {
+                write!(f, "некорректные достижения: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AchievementsLoadError {}
+
+impl From<std::io::Error> for AchievementsLoadError {
+    fn from(error: std::io::Error) -> Self {
+        AchievementsLoadError::Io(error)
+    }
+}
+
+/// Разбирает список достижений из RON
+pub fn parse_achievements(bytes: &[u8]) -> Result<AchievementCatalogue, AchievementsLoadError> {
+    let catalogue: AchievementCatalogue = ron::de::from_bytes(bytes)
+        .map_err(|error| AchievementsLoadError::Parse(error.to_string()))?;
+    catalogue.validate()?;
+    Ok(catalogue)
+}
+
+/// Загрузчик `*.achievements.ron`
+#[derive(Default)]
+pub struct AchievementCatalogueLoader;
+
+impl AssetLoader for AchievementCatalogueLoader {
+    type Asset = AchievementCatalogue;
+    type Settings = ();
+    type Error = AchievementsLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<AchievementCatalogue, AchievementsLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        parse_achievements(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+/// Загруженный список достижений
+#[derive(Resource, Default)]
+pub struct AchievementCatalogueHandle(pub Handle<AchievementCatalogue>);
+
+/// Прогресс достижений игрока
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AchievementProgress {
+    progress: BTreeMap<String, u32>,
+    unlocked: BTreeSet<String>,
+}
+
+impl SaveData for AchievementProgress {
+    const FILE_NAME: &'static str = "achievements.json";
+    const VERSION: u32 = 1;
+}
+
+impl AchievementProgress {
+    pub fn progress(&self, achievement: &Achievement) -> u32 {
+        self.progress.get(&achievement.id).copied().unwrap_or(0)
+    }
+
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains(&achievement.id)
+    }
+
+    /// Доля выполнения от 0 до 1
+    pub fn fraction(&self, achievement: &Achievement) -> f32 {
+        if self.is_unlocked(achievement) {
+            return 1.0;
+        }
+        let target = achievement.goal.target().max(1);
+        (self.progress(achievement) as f32 / target as f32).min(1.0)
+    }
+
+    /// Обновляет прогресс. `value` получает цель и текущий прогресс и возвращает
+    /// новый прогресс, если событие относится к цели. Прогресс не убывает.
+    /// Возвращает только что открытые достижения
+    pub fn advance<'a>(
+        &mut self,
+        catalogue: &'a AchievementCatalogue,
+        value: impl Fn(&AchievementGoal, u32) -> Option<u32>,
+    ) -> Vec<&'a Achievement> {
+        let mut unlocked = Vec::new();
+        for achievement in &catalogue.achievements {
+            if self.is_unlocked(achievement) {
+                continue;
+            }
+            let current = self.progress(achievement);
+            let Some(new) = value(&achievement.goal, current) else {
+                continue;
+            };
+            if new > current {
+                self.progress.insert(achievement.id.clone(), new);
+            }
+            if new >= achievement.goal.target() {
+                self.unlocked.insert(achievement.id.clone());
+                unlocked.push(achievement);
+            }
+        }
+        unlocked
+    }
+}
+
+/// Достижение открыто
+#[derive(Event)]
+pub struct AchievementUnlocked(pub Achievement);
+
+/// Что произошло в текущем забеге
+#[derive(Resource, Default)]
+pub struct RunAchievementState {
+    shield_collected: bool,
+    /// Подобранные power-ups и пройденные трубы, ещё не учтённые в прогрессе:
+    /// пока список достижений не загружен, они копятся здесь
+    pending_power_ups: Vec<PowerUpType>,
+    pending_pipes: u32,
+}
+
+/// Плагин достижений
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AchievementCatalogue>()
+            .init_asset_loader::<AchievementCatalogueLoader>()
+            .init_resource::<AchievementCatalogueHandle>()
+            .init_resource::<AchievementProgress>()
+            .init_resource::<RunAchievementState>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(Startup, (load_catalogue, load_achievements))
+            .add_systems(
+                Update,
+                load_achievements.run_if(on_event::<ProfileSwitched>),
+            )
+            .add_systems(OnEnter(GameState::PreGame), reset_run_state)
+            .add_systems(
+                Update,
+                track_run_progress.run_if(
+                    in_state(GameState::Playing)
+                        .and(not(is_replay_playing))
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (record_finished_run, save_achievements).chain().run_if(
+                    not(is_replay_playing)
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), save_achievements)
+            .add_systems(Last, save_achievements.run_if(is_app_exiting))
+            .add_systems(
+                Update,
+                (spawn_unlock_toasts, expire_toasts)
+                    .chain()
+                    .after(track_run_progress),
+            )
+            .add_systems(OnEnter(GameState::Achievements), spawn_achievements_screen)
+            .add_systems(
+                Update,
+                handle_achievements_back.run_if(in_state(GameState::Achievements)),
+            )
+            .add_systems(
+                OnExit(GameState::Achievements),
+                despawn_entities::<OnAchievementsScreen>,
+            );
+    }
+}
+
+fn load_catalogue(mut handle: ResMut<AchievementCatalogueHandle>, asset_server: Res<AssetServer>) {
+    handle.0 = asset_server.load(CATALOGUE_PATH);
+}
+
+fn load_achievements(save_dir: Res<SaveDir>, mut achievements: ResMut<AchievementProgress>) {
+    *achievements = save::load::<AchievementProgress>(&save_dir).unwrap_or_default();
+}
+
+fn save_achievements(save_dir: Res<SaveDir>, achievements: Res<AchievementProgress>) {
+    save::save(&save_dir, &*achievements);
+}
+
+/// Неучтённый прогресс прошлых забегов остаётся ждать списка достижений
+fn reset_run_state(mut run_state: ResMut<RunAchievementState>) {
+    run_state.shield_collected = false;
+}
+
+/// Прогресс, который копится по ходу забега: очки, время, подобранные power-ups
+#[allow(clippy::too_many_arguments)]
+fn track_run_progress(
+    score: Res<GameScore>,
+    clock: Res<RunClock>,
+    mode_settings: Res<GameModeSettings>,
+    mut run_state: ResMut<RunAchievementState>,
+    mut powerup_events: EventReader<PowerUpCollectedEvent>,
+    mut score_events: EventReader<ScoreEvent>,
+    catalogues: Res<Assets<AchievementCatalogue>>,
+    handle: Res<AchievementCatalogueHandle>,
+    mut achievements: ResMut<AchievementProgress>,
+    mut unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    for event in powerup_events.read() {
+        run_state.shield_collected |= event.power_type == PowerUpType::Shield;
+        run_state.pending_power_ups.push(event.power_type);
+    }
+    run_state.pending_pipes += score_events.read().count() as u32;
+
+    let Some(catalogue) = catalogues.get(&handle.0) else {
+        return;
+    };
+    let mode = mode_settings.current_mode;
+    let score = score.0;
+    let seconds = clock.elapsed_secs() as u32;
+    let shield_collected = run_state.shield_collected;
+    let collected = std::mem::take(&mut run_state.pending_power_ups);
+    let pipes = std::mem::take(&mut run_state.pending_pipes);
+
+    let unlocked = achievements.advance(catalogue, |goal, current| match *goal {
+        AchievementGoal::Score {
+            mode: goal_mode, ..
+        } if goal_mode.is_none_or(|m| m == mode) => Some(score),
+        AchievementGoal::CollectPowerUp(power_type) if collected.contains(&power_type) => Some(1),
+        AchievementGoal::Survive {
+            mode: goal_mode, ..
+        } if goal_mode == mode => Some(seconds),
+        AchievementGoal::ScoreWithoutShield {
+            mode: goal_mode, ..
+        } if goal_mode == mode && !shield_collected => Some(score),
+        AchievementGoal::PipesPassed(_) if pipes > 0 => Some(current + pipes),
+        _ => None,
+    });
+    for achievement in unlocked {
+        unlocked_events.send(AchievementUnlocked(achievement.clone()));
+    }
+}
+
+/// Прогресс, который засчитывается по окончании забега
+fn record_finished_run(
+    catalogues: Res<Assets<AchievementCatalogue>>,
+    handle: Res<AchievementCatalogueHandle>,
+    mut achievements: ResMut<AchievementProgress>,
+    mut unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    let Some(catalogue) = catalogues.get(&handle.0) else {
+        return;
+    };
+    let unlocked = achievements.advance(catalogue, |goal, current| match goal {
+        AchievementGoal::GamesPlayed(_) => Some(current + 1),
+        _ => None,
+    });
+    for achievement in unlocked {
+        unlocked_events.send(AchievementUnlocked(achievement.clone()));
+    }
+}
+
+/// Всплывающее уведомление об открытом достижении
+#[derive(Component)]
+pub struct AchievementToast {
+    timer: Timer,
+}
+
+fn spawn_unlock_toasts(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut unlocked_events: EventReader<AchievementUnlocked>,
+    toasts: Query<(), With<AchievementToast>>,
+) {
+    let shown = toasts.iter().count();
+    for (index, AchievementUnlocked(achievement)) in (shown..).zip(unlocked_events.read()) {
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0 + index as f32 * 70.0),
+                    right: Val::Px(20.0),
+                    width: Val::Px(280.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(8.0)),
+                BackgroundColor(Color::srgba(0.1, 0.1, 0.2, 0.9)),
+                GlobalZIndex(10),
+                AchievementToast {
+                    timer: Timer::from_seconds(TOAST_DURATION, TimerMode::Once),
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text::new("🏆 Достижение открыто"),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                ));
+                parent.spawn((
+                    Text::new(achievement.title.clone()),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+    }
+}
+
+fn expire_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toasts: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in &mut toasts {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Маркер-компонент для экрана достижений
+#[derive(Component)]
+pub struct OnAchievementsScreen;
+
+/// Строка достижения на экране
+#[derive(Component)]
+pub struct AchievementRow;
+
+/// Кнопка возврата в главное меню
+#[derive(Component)]
+pub struct AchievementsBackButton;
+
+fn spawn_achievements_screen(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    catalogues: Res<Assets<AchievementCatalogue>>,
+    handle: Res<AchievementCatalogueHandle>,
+    achievements: Res<AchievementProgress>,
+) {
+    let catalogue = catalogues.get(&handle.0);
+    let all = catalogue.map_or(&[][..], |catalogue| &catalogue.achievements[..]);
+    let unlocked = all
+        .iter()
+        .filter(|achievement| achievements.is_unlocked(achievement))
+        .count();
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnAchievementsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("🏆 Достижения {}/{}", unlocked, all.len())),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(Node {
+                    width: Val::Px(520.0),
+                    height: Val::Px(400.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                })
+                .with_children(|list| {
+                    if catalogue.is_none() {
+                        list.spawn((
+                            Text::new(format!(
+                                "Достижения не загружены: assets/{}",
+                                CATALOGUE_PATH
+                            )),
+                            TextFont {
+                                font: assets.font.clone(),
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    }
+                    for achievement in all {
+                        spawn_achievement_row(list, &assets, &achievements, achievement);
+                    }
+                });
+
+            spawn_menu_button(parent, &assets, "🔙 Назад в меню", AchievementsBackButton);
+        });
+}
+
+fn spawn_achievement_row(
+    parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    achievements: &AchievementProgress,
+    achievement: &Achievement,
+) {
+    let unlocked = achievements.is_unlocked(achievement);
+    let (title_color, bar_color) = if unlocked {
+        (Color::srgb(1.0, 0.8, 0.3), Color::srgb(1.0, 0.8, 0.3))
+    } else {
+        (Color::srgb(0.7, 0.7, 0.7), Color::srgb(0.3, 0.6, 1.0))
+    };
+    let target = achievement.goal.target();
+    let progress = if unlocked {
+        target
+    } else {
+        achievements.progress(achievement).min(target)
+    };
+
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(500.0),
+                flex_direction: FlexDirection::Column,
+                margin: UiRect::bottom(Val::Px(8.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(8.0)),
+            BackgroundColor(Color::srgb(0.1, 0.1, 0.2)),
+            AchievementRow,
+        ))
+        .with_children(|row| {
+            row.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                ..default()
+            })
+            .with_children(|header| {
+                header.spawn((
+                    Text::new(achievement.title.clone()),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(title_color),
+                ));
+                header.spawn((
+                    Text::new(format!("{}/{}", progress, target)),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+
+            row.spawn((
+                Text::new(achievement.description.clone()),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                Node {
+                    margin: UiRect::vertical(Val::Px(4.0)),
+                    ..default()
+                },
+            ));
+
+            // Полоса прогресса
+            row.spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(8.0),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(4.0)),
+                BackgroundColor(Color::srgb(0.25, 0.25, 0.3)),
+            ))
+            .with_children(|bar| {
+                bar.spawn((
+                    Node {
+                        width: Val::Percent(achievements.fraction(achievement) * 100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(4.0)),
+                    BackgroundColor(bar_color),
+                ));
+            });
+        });
+}
+
+fn handle_achievements_back(
+    query: Query<&Interaction, (Changed<Interaction>, With<AchievementsBackButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::HeadlessGameBuilder;
+
+    fn catalogue() -> AchievementCatalogue {
+        parse_achievements(include_bytes!(
+            "../../assets/achievements/catalogue.achievements.ron"
+        ))
+        .unwrap()
+    }
+
+    fn achievement(id: &str) -> Achievement {
+        catalogue()
+            .achievements
+            .into_iter()
+            .find(|a| a.id == id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_progress_keeps_best_value_and_unlocks_once() {
+        let catalogue = catalogue();
+        let mut progress = AchievementProgress::default();
+        let score_in = |score: u32| {
+            move |goal: &AchievementGoal, _| match goal {
+                AchievementGoal::Score { mode: None, .. } => Some(score),
+                _ => None,
+            }
+        };
+
+        let unlocked = progress.advance(&catalogue, score_in(12));
+        let ids: Vec<_> = unlocked.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["first_point", "score_10"]);
+
+        // Худший забег не откатывает прогресс, открытые не открываются снова
+        assert!(progress.advance(&catalogue, score_in(5)).is_empty());
+        assert_eq!(progress.progress(&achievement("score_25")), 12);
+        assert!((progress.fraction(&achievement("score_25")) - 0.48).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_catalogue_rejects_duplicate_ids_and_zero_targets() {
+        assert_eq!(catalogue().achievements.len(), 13);
+        let duplicate = br#"(achievements: [
+            (id: "a", title: "A", goal: GamesPlayed(1)),
+            (id: "a", title: "B", goal: PipesPassed(1)),
+        ])"#;
+        assert!(matches!(
+            parse_achievements(duplicate),
+            Err(AchievementsLoadError::Invalid(_))
+        ));
+        let zero =
+            br#"(achievements: [(id: "a", title: "A", goal: Score(points: 0, mode: None))])"#;
+        assert!(parse_achievements(zero).is_err());
+        let unknown_mode =
+            br#"(achievements: [(id: "a", title: "A", goal: Survive(mode: Moon, seconds: 5))])"#;
+        assert!(matches!(
+            parse_achievements(unknown_mode),
+            Err(AchievementsLoadError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_unlock_during_run_shows_toast() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.resource_mut::<GameScore>().0 = 10;
+        game.step();
+
+        let achievements = game.resource::<AchievementProgress>();
+        assert!(achievements.is_unlocked(&achievement("score_10")));
+        assert!(!achievements.is_unlocked(&achievement("score_25")));
+        assert_eq!(game.count::<AchievementToast>(), 2);
+
+        game.step_frames((TOAST_DURATION * 60.0) as usize + 2);
+        assert_eq!(game.count::<AchievementToast>(), 0);
+    }
+
+    #[test]
+    fn test_progress_waits_for_catalogue() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let shipped = game.resource::<AchievementCatalogueHandle>().0.clone();
+        game.resource_mut::<AchievementCatalogueHandle>().0 = Handle::default();
+        game.start_run();
+
+        let world = game.app.world_mut();
+        world.send_event(PowerUpCollectedEvent {
+            power_type: PowerUpType::Shield,
+        });
+        world.send_event(ScoreEvent);
+        game.step_frames(3);
+        assert!(!game
+            .resource::<AchievementProgress>()
+            .is_unlocked(&achievement("collect_shield")));
+
+        // Список загрузился позже событий, но они не потерялись
+        game.resource_mut::<AchievementCatalogueHandle>().0 = shipped;
+        game.step();
+        let achievements = game.resource::<AchievementProgress>();
+        assert!(achievements.is_unlocked(&achievement("collect_shield")));
+        assert_eq!(achievements.progress(&achievement("pipes_1000")), 1);
+    }
+
+    #[test]
+    fn test_achievements_screen_lists_all() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.click_button::<crate::core::components::AchievementsButton>();
+        assert!(game.step_until_state(GameState::Achievements));
+        assert_eq!(
+            game.count::<AchievementRow>(),
+            catalogue().achievements.len()
+        );
+
+        game.click_button::<AchievementsBackButton>();
+        assert!(game.step_until_state(GameState::MainMenu));
+        assert_eq!(game.count::<OnAchievementsScreen>(), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Анимация спрайтов по клипам
+//!
+//! Клип — это последовательность кадров листа спрайтов, у каждого кадра своя
+//! длительность. Что делать после последнего кадра, тоже задаётся в данных:
+//! начать сначала, замереть или перейти к другому клипу. `ClipPlayer` только
+//! считает время и номер кадра, поэтому его можно проверять без ассетов.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Сколько кадров клипа можно пролистать за один вызов `tick`
+const MAX_STEPS_PER_TICK: usize = 16;
+
+/// Кадр клипа: номер ячейки в листе и сколько он держится на экране
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ClipFrame {
+    pub index: usize,
+    pub seconds: f32,
+}
+
+/// Что происходит после последнего кадра
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub enum ClipEnd {
+    /// Начать клип сначала
+    #[default]
+    Loop,
+    /// Остаться на последнем кадре
+    Hold,
+    /// Перейти к клипу с этим именем
+    Next(String),
+}
+
+/// Именованный клип анимации
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AnimationClip {
+    pub frames: Vec<ClipFrame>,
+    #[serde(default)]
+    pub end: ClipEnd,
+}
+
+/// Клипы листа по именам
+pub type AnimationClips = HashMap<String, AnimationClip>;
+
+/// Проверяет, что кадры клипов есть в листе и переходы ведут к существующим клипам
+pub fn validate_clips(clips: &AnimationClips, cells: usize) -> Result<(), String> {
+    for (name, clip) in clips {
+        if clip.frames.is_empty() {
+            return Err(format!("в клипе «{}» нет кадров", name));
+        }
+        for frame in &clip.frames {
+            if frame.index >= cells {
+                return Err(format!(
+                    "клип «{}»: кадр {} вне листа из {} ячеек",
+                    name, frame.index, cells
+                ));
+            }
+            if frame.seconds <= 0.0 || !frame.seconds.is_finite() {
+                return Err(format!(
+                    "клип «{}»: длительность кадра должна быть больше нуля",
+                    name
+                ));
+            }
+        }
+        if let ClipEnd::Next(next) = &clip.end {
+            if !clips.contains_key(next) {
+                return Err(format!(
+                    "клип «{}» переходит к неизвестному «{}»",
+                    name, next
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Проигрыватель клипов: текущий клип, кадр и время на нём
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClipPlayer {
+    clip: String,
+    frame: usize,
+    elapsed: f32,
+}
+
+impl ClipPlayer {
+    pub fn clip(&self) -> &str {
+        &self.clip
+    }
+
+    /// Запускает клип с первого кадра, даже если он уже играет
+    pub fn play(&mut self, clip: &str) {
+        self.clip = clip.to_string();
+        self.frame = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// Продвигает время и возвращает ячейку листа для текущего кадра
+    pub fn tick(&mut self, clips: &AnimationClips, delta: f32) -> Option<usize> {
+        self.elapsed += delta;
+        // После долгой паузы кадры догоняются за несколько вызовов, а не разом
+        for _ in 0..MAX_STEPS_PER_TICK {
+            let clip = clips.get(&self.clip)?;
+            let frame = clip.frames.get(self.frame)?;
+            if self.elapsed < frame.seconds {
+                break;
+            }
+            if self.frame + 1 < clip.frames.len() {
+                self.elapsed -= frame.seconds;
+                self.frame += 1;
+                continue;
+            }
+            match &clip.end {
+                ClipEnd::Loop => {
+                    self.elapsed -= frame.seconds;
+                    self.frame = 0;
+                }
+                ClipEnd::Hold => {
+                    self.elapsed = frame.seconds;
+                    break;
+                }
+                ClipEnd::Next(next) => {
+                    let elapsed = self.elapsed - frame.seconds;
+                    self.play(next);
+                    self.elapsed = elapsed;
+                }
+            }
+        }
+        self.current(clips)
+    }
+
+    /// Ячейка листа для текущего кадра
+    pub fn current(&self, clips: &AnimationClips) -> Option<usize> {
+        let clip = clips.get(&self.clip)?;
+        clip.frames.get(self.frame).map(|frame| frame.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clips() -> AnimationClips {
+        ron::de::from_str(
+            r#"{
+                "flap": (frames: [(index: 0, seconds: 0.1), (index: 1, seconds: 0.1)],
+                    end: Next("glide")),
+                "glide": (frames: [(index: 2, seconds: 0.2), (index: 1, seconds: 0.2)]),
+                "tumble": (frames: [(index: 0, seconds: 0.1), (index: 2, seconds: 0.1)],
+                    end: Hold),
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_one_shot_clip_moves_to_next_and_loops() {
+        let clips = clips();
+        let mut player = ClipPlayer::default();
+        player.play("flap");
+
+        assert_eq!(player.tick(&clips, 0.05), Some(0));
+        assert_eq!(player.tick(&clips, 0.1), Some(1));
+        assert_eq!(player.tick(&clips, 0.1), Some(2));
+        assert_eq!(player.clip(), "glide");
+        assert_eq!(player.tick(&clips, 0.2), Some(1));
+        // Клип без перехода начинается сначала
+        assert_eq!(player.tick(&clips, 0.2), Some(2));
+        assert_eq!(player.clip(), "glide");
+    }
+
+    #[test]
+    fn test_hold_stays_on_last_frame() {
+        let clips = clips();
+        let mut player = ClipPlayer::default();
+        player.play("tumble");
+
+        assert_eq!(player.tick(&clips, 1.0), Some(2));
+        assert_eq!(player.tick(&clips, 1.0), Some(2));
+        assert_eq!(player.clip(), "tumble");
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_clips() {
+        assert!(validate_clips(&clips(), 3).is_ok());
+        assert!(validate_clips(&clips(), 2).is_err());
+
+        let mut broken = clips();
+        broken.get_mut("flap").unwrap().end = ClipEnd::Next("dive".to_string());
+        assert!(validate_clips(&broken, 3).is_err());
+    }
+}
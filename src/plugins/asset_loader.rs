@@ -1,4 +1,5 @@
 use crate::core::resources::GameAssets;
+use crate::plugins::{hitboxes, skins};
 use crate::states::app_state::AppState;
 use bevy::prelude::*;
 
@@ -16,11 +17,8 @@ impl Plugin for AssetLoaderPlugin {
 
 fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(GameAssets {
-        bird_textures: vec![
-            asset_server.load("textures/bird_up.png"),
-            asset_server.load("textures/bird_mid.png"),
-            asset_server.load("textures/bird_down.png"),
-        ],
+        skins: asset_server.load(skins::CATALOGUE_PATH),
+        hitboxes: asset_server.load(hitboxes::HITBOXES_PATH),
         pipe_texture: asset_server.load("textures/pipe.png"),
         font: asset_server.load("fonts/Roboto-Regular.ttf"),
         // Загрузка аудио ассетов
@@ -44,10 +42,11 @@ fn check_assets_loaded(
     asset_server: Res<AssetServer>,
     game_assets: Res<GameAssets>,
 ) {
-    let bird_textures_loaded = game_assets
-        .bird_textures
-        .iter()
-        .all(|texture| asset_server.load_state(texture.id()).is_loaded());
+    // Каталог обликов считается загруженным вместе с кадрами
+    let skins_loaded = asset_server.is_loaded_with_dependencies(game_assets.skins.id());
+    let hitboxes_loaded = asset_server
+        .load_state(game_assets.hitboxes.id())
+        .is_loaded();
     let pipe_loaded = asset_server
         .load_state(game_assets.pipe_texture.id())
         .is_loaded();
@@ -75,7 +74,8 @@ fn check_assets_loaded(
         .iter()
         .all(|handle| asset_server.load_state(handle.id()).is_loaded());
 
-    if bird_textures_loaded
+    if skins_loaded
+        && hitboxes_loaded
         && pipe_loaded
         && font_loaded
         && jump_sound_loaded
@@ -1,8 +1,43 @@
 use crate::core::resources::GameAssets;
 use crate::core::utils::despawn_entities;
 use crate::states::app_state::AppState;
+use bevy::audio::Volume;
 use bevy::prelude::*;
 
+/// Громкость звука, от 0 до 1
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct AudioSettings {
+    /// Общая громкость
+    pub master_volume: f32,
+    /// Громкость звуковых эффектов
+    pub effects_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            effects_volume: 1.0,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Параметры воспроизведения одиночного эффекта
+    pub fn effect_playback(&self) -> PlaybackSettings {
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(self.effects_volume))
+    }
+}
+
+/// Следующее деление громкости по кругу: 0%, 25%, ..., 100%, 0%
+pub fn next_volume_step(volume: f32) -> f32 {
+    if volume >= 1.0 {
+        0.0
+    } else {
+        ((volume * 4.0).floor() + 1.0) / 4.0
+    }
+}
+
 /// Маркер для аудио сущностей
 #[derive(Component)]
 pub struct AudioEntity;
@@ -12,7 +47,8 @@ pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<JumpEvent>()
+        app.init_resource::<AudioSettings>()
+            .add_event::<JumpEvent>()
             .add_event::<ScoreEvent>()
             .add_event::<CollisionEvent>()
             .add_event::<GameOverEvent>()
@@ -30,6 +66,10 @@ impl Plugin for AudioPlugin {
                 )
                     .run_if(in_state(AppState::Loaded)),
             )
+            .add_systems(
+                Update,
+                apply_master_volume.run_if(resource_changed::<AudioSettings>),
+            )
             .add_systems(
                 OnExit(crate::states::game_state::GameState::GameOver),
                 despawn_entities::<AudioEntity>,
@@ -60,16 +100,24 @@ pub struct PowerUpCollectEvent;
 #[derive(Event)]
 pub struct PowerUpSpawnSoundEvent;
 
+/// Общая громкость действует на все новые звуки
+fn apply_master_volume(audio: Res<AudioSettings>, global_volume: Option<ResMut<GlobalVolume>>) {
+    if let Some(mut global_volume) = global_volume {
+        *global_volume = GlobalVolume::new(audio.master_volume);
+    }
+}
+
 /// Воспроизведение звука прыжка
 pub fn play_jump_sounds(
     mut commands: Commands,
     mut jump_events: EventReader<JumpEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in jump_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.jump_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
@@ -80,11 +128,12 @@ pub fn play_score_sounds(
     mut commands: Commands,
     mut score_events: EventReader<ScoreEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in score_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.score_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
@@ -95,11 +144,12 @@ pub fn play_collision_sounds(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in collision_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.hit_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
@@ -110,11 +160,12 @@ pub fn play_game_over_sounds(
     mut commands: Commands,
     mut game_over_events: EventReader<GameOverEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in game_over_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.game_over_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
@@ -125,11 +176,12 @@ pub fn play_powerup_collect_sounds(
     mut commands: Commands,
     mut collect_events: EventReader<PowerUpCollectEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in collect_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.powerup_collect_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
@@ -140,11 +192,12 @@ pub fn play_powerup_spawn_sounds(
     mut commands: Commands,
     mut spawn_events: EventReader<PowerUpSpawnSoundEvent>,
     assets: Res<GameAssets>,
+    audio: Res<AudioSettings>,
 ) {
     for _event in spawn_events.read() {
         commands.spawn((
             AudioPlayer::new(assets.powerup_spawn_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            audio.effect_playback(),
             AudioEntity,
         ));
     }
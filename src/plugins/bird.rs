@@ -1,183 +1,253 @@
 use crate::{
-    core::{resources::GameAssets, utils::despawn_entities},
+    core::{components::Collider, resources::PlayArea, utils::despawn_entities},
+    plugins::animation::ClipPlayer,
     plugins::audio::{CollisionEvent, GameOverEvent, JumpEvent},
-    states::game_state::GameState,
+    plugins::hitboxes::Hitboxes,
+    plugins::input_map::{ActionState, GameAction},
+    plugins::replay::is_replay_playing,
+    plugins::simulation::InterpolatedTransform,
+    plugins::skins::{SkinAnimation, Skins},
+    states::game_state::{GameState, PauseState, PreGameSet, SimulationSet},
 };
+use bevy::app::{RunFixedMainLoop, RunFixedMainLoopSystem};
 use bevy::prelude::*;
-use bevy::time::Timer;
 
 #[derive(Component)]
 pub struct Bird {
     pub velocity: f32,
 }
 
+/// Нажатия прыжка, ожидающие ближайшего шага симуляции
+#[derive(Resource, Default)]
+pub struct FlapBuffer {
+    pub pending: bool,
+}
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 pub enum BirdAnimationState {
     #[default]
     Idle,
     Flapping,
     Falling,
+    Dead,
 }
 
-#[derive(Component)]
+impl BirdAnimationState {
+    fn from_motion(velocity: f32, dead: bool) -> Self {
+        if dead {
+            BirdAnimationState::Dead
+        } else if velocity > 100.0 {
+            BirdAnimationState::Flapping
+        } else if velocity < -100.0 {
+            BirdAnimationState::Falling
+        } else {
+            BirdAnimationState::Idle
+        }
+    }
+}
+
+/// Клип облика, который сейчас играет птица
+#[derive(Component, Default)]
 pub struct BirdAnimation {
-    pub timer: Timer,
-    pub current_frame: usize,
     pub state: BirdAnimationState,
+    pub player: ClipPlayer,
+    /// Скорость в прошлом кадре: по её скачку вверх узнаём о новом взмахе
+    last_velocity: f32,
 }
 
 const VELOCITY_TO_ROTATION_RATIO: f32 = 7.5;
-const BIRD_SIZE: f32 = 50.0;
-
-// Скорости анимации для разных состояний
-const FLAPPING_ANIMATION_SPEED: f32 = 0.1;
-const IDLE_ANIMATION_SPEED: f32 = 0.2;
-const FALLING_ANIMATION_SPEED: f32 = 0.3;
+pub const BIRD_SIZE: f32 = 50.0;
+const JUMP_VELOCITY: f32 = 500.0;
+const GRAVITY: f32 = 2000.0;
 
 pub struct BirdPlugin;
 
 impl Plugin for BirdPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::PreGame), spawn_bird)
+        app.init_resource::<FlapBuffer>()
             .add_systems(
-                Update,
-                (bird_movement, bird_jump, animate_bird, check_bird_bounds)
+                OnEnter(GameState::PreGame),
+                (
+                    (despawn_entities::<Bird>, clear_flap_buffer).in_set(PreGameSet::Cleanup),
+                    spawn_bird.in_set(PreGameSet::Spawn),
+                ),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                bird_jump
+                    .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop)
+                    .run_if(in_state(PauseState::Running).and(not(is_replay_playing))),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    bird_movement.in_set(SimulationSet::Movement),
+                    check_bird_bounds.in_set(SimulationSet::Collision),
+                )
                     .run_if(in_state(GameState::Playing)),
             )
-            .add_systems(OnExit(GameState::GameOver), despawn_entities::<Bird>);
+            // После гибели птица доигрывает клип падения на экране конца игры
+            .add_systems(
+                Update,
+                animate_bird.run_if(in_state(GameState::Playing).or(in_state(GameState::GameOver))),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_entities::<Bird>)
+            // Выход в меню прямо из забега (меню паузы)
+            .add_systems(OnEnter(GameState::MainMenu), despawn_entities::<Bird>);
     }
 }
 
-fn spawn_bird(mut commands: Commands, assets: Res<GameAssets>) {
-    let initial_texture = assets.bird_textures.first().cloned().unwrap_or_default();
+fn spawn_bird(mut commands: Commands, skins: Skins, hitboxes: Res<Hitboxes>) {
+    let sprite = skins
+        .selected()
+        .map(|skin| skin.sprite())
+        .unwrap_or_default();
 
     commands.spawn((
         Sprite {
-            image: initial_texture,
             custom_size: Some(Vec2::new(BIRD_SIZE, BIRD_SIZE)),
-            ..default()
+            ..sprite
         },
         Transform::from_xyz(0.0, 0.0, 0.0),
         Bird { velocity: 0.0 },
-        BirdAnimation {
-            timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            current_frame: 0,
-            state: BirdAnimationState::Idle,
-        },
+        InterpolatedTransform::default(),
+        BirdAnimation::default(),
+        Collider::new(hitboxes.bird),
         Name::new("Bird"),
     ));
 }
 
-fn bird_jump(
-    keys: Res<ButtonInput<KeyCode>>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
-    mut jump_events: EventWriter<JumpEvent>,
-) {
-    if keys.just_pressed(KeyCode::Space) || mouse_buttons.just_pressed(MouseButton::Left) {
-        // Отправляем событие прыжка для воспроизведения звука
-        jump_events.send(JumpEvent);
+fn clear_flap_buffer(mut flap: ResMut<FlapBuffer>) {
+    flap.pending = false;
+}
+
+/// Читает ввод каждый кадр и откладывает прыжок до шага симуляции
+fn bird_jump(actions: Res<ActionState>, mut flap: ResMut<FlapBuffer>) {
+    if actions.just_pressed(GameAction::Flap) {
+        flap.pending = true;
     }
 }
 
 fn bird_movement(
     time: Res<Time>,
-    mut reader: EventReader<JumpEvent>,
+    mut flap: ResMut<FlapBuffer>,
+    mut jump_events: EventWriter<JumpEvent>,
     mut query: Query<(&mut Bird, &mut Transform)>,
 ) {
-    let jumped = reader.read().next().is_some();
+    let jumped = std::mem::take(&mut flap.pending);
 
     if let Ok((mut bird, mut transform)) = query.get_single_mut() {
         if jumped {
-            bird.velocity = 500.0;
+            // Отправляем событие прыжка для воспроизведения звука
+            jump_events.send(JumpEvent);
         }
 
-        bird.velocity -= 2000.0 * time.delta_secs();
-        transform.translation.y += bird.velocity * time.delta_secs();
-
-        transform.rotation = Quat::from_axis_angle(
-            Vec3::Z,
-            f32::clamp(bird.velocity / VELOCITY_TO_ROTATION_RATIO, -90., 90.).to_radians(),
+        step_bird_physics(
+            &mut bird.velocity,
+            &mut transform,
+            jumped,
+            time.delta_secs(),
         );
     }
 }
 
+/// Один шаг физики птицы. Общий для игрока и призрака, чтобы их траектории совпадали
+pub fn step_bird_physics(velocity: &mut f32, transform: &mut Transform, jumped: bool, dt: f32) {
+    if jumped {
+        *velocity = JUMP_VELOCITY;
+    }
+
+    *velocity -= GRAVITY * dt;
+    transform.translation.y += *velocity * dt;
+
+    transform.rotation = Quat::from_axis_angle(
+        Vec3::Z,
+        f32::clamp(*velocity / VELOCITY_TO_ROTATION_RATIO, -90., 90.).to_radians(),
+    );
+}
+
 fn animate_bird(
     time: Res<Time>,
+    state: Res<State<GameState>>,
     mut query: Query<(&Bird, &mut BirdAnimation, &mut Sprite)>,
-    assets: Res<GameAssets>,
+    skins: Skins,
 ) {
+    let Some(skin) = skins.selected() else {
+        return;
+    };
+    let dead = *state.get() == GameState::GameOver;
     for (bird, mut animation, mut sprite) in &mut query {
-        // Обновляем таймер анимации
-        animation.timer.tick(time.delta());
+        advance_bird_animation(
+            &mut animation,
+            &mut sprite,
+            bird.velocity,
+            dead,
+            &time,
+            &skin.animation,
+        );
+    }
+}
 
-        // Определяем состояние анимации на основе скорости
-        let new_state = if bird.velocity > 100.0 {
-            BirdAnimationState::Flapping
-        } else if bird.velocity < -100.0 {
-            BirdAnimationState::Falling
+/// Запускает клип облика для состояния птицы и продвигает его кадры
+pub fn advance_bird_animation(
+    animation: &mut BirdAnimation,
+    sprite: &mut Sprite,
+    velocity: f32,
+    dead: bool,
+    time: &Time,
+    skin: &SkinAnimation,
+) {
+    let state = BirdAnimationState::from_motion(velocity, dead);
+    // Новый взмах, пока не кончился прошлый, начинает клип взмаха заново
+    let flapped = state == BirdAnimationState::Flapping && velocity > animation.last_velocity;
+    animation.last_velocity = velocity;
+
+    // Клипа может не быть, если облик сменился или каталог перезагрузился
+    let index =
+        if state != animation.state || flapped || !skin.clips.contains_key(animation.player.clip())
+        {
+            animation.state = state;
+            animation.player.play(skin.states.clip(state));
+            animation.player.current(&skin.clips)
         } else {
-            BirdAnimationState::Idle
+            animation.player.tick(&skin.clips, time.delta_secs())
         };
 
-        // Если состояние изменилось, обновляем таймер и сбрасываем кадр
-        if new_state != animation.state {
-            animation.state = new_state;
-            animation.current_frame = 0;
-
-            // Устанавливаем скорость таймера для нового состояния
-            match animation.state {
-                BirdAnimationState::Flapping => {
-                    animation.timer =
-                        Timer::from_seconds(FLAPPING_ANIMATION_SPEED, TimerMode::Repeating);
-                }
-                BirdAnimationState::Idle => {
-                    animation.timer =
-                        Timer::from_seconds(IDLE_ANIMATION_SPEED, TimerMode::Repeating);
-                }
-                BirdAnimationState::Falling => {
-                    animation.timer =
-                        Timer::from_seconds(FALLING_ANIMATION_SPEED, TimerMode::Repeating);
-                }
-            }
-        }
-
-        // Переключаем кадры на основе таймера
-        if animation.timer.just_finished() {
-            animation.current_frame = (animation.current_frame + 1) % assets.bird_textures.len();
-
-            // Меняем текстуру спрайта на соответствующий кадр
-            if let Some(new_texture) = assets.bird_textures.get(animation.current_frame) {
-                sprite.image = new_texture.clone();
-            }
+    if let (Some(index), Some(atlas)) = (index, sprite.texture_atlas.as_mut()) {
+        // Пишем только при смене, чтобы не трогать отслеживание изменений
+        if atlas.index != index {
+            atlas.index = index;
         }
     }
 }
 
+/// Нижняя и верхняя границы центра птицы с учётом её размера
+pub fn bird_bounds(play_area: &PlayArea) -> (f32, f32) {
+    let half_height = BIRD_SIZE / 2.0;
+    (
+        -play_area.height / 2.0 + half_height,
+        play_area.height / 2.0 - half_height,
+    )
+}
+
 fn check_bird_bounds(
     query: Query<&Transform, With<Bird>>,
-    windows: Query<&Window>,
+    play_area: Res<PlayArea>,
     mut next_state: ResMut<NextState<GameState>>,
     mut collision_events: EventWriter<CollisionEvent>,
     mut game_over_events: EventWriter<GameOverEvent>,
 ) {
-    if let Ok(window) = windows.get_single() {
-        let window_height = window.height();
-        let bird_height = BIRD_SIZE;
-
-        // Границы с учетом размера птицы
-        let top_bound = window_height / 2.0 - bird_height / 2.0;
-        let bottom_bound = -window_height / 2.0 + bird_height / 2.0;
+    let (bottom_bound, top_bound) = bird_bounds(&play_area);
 
-        if let Ok(bird_transform) = query.get_single() {
-            let bird_y = bird_transform.translation.y;
+    if let Ok(bird_transform) = query.get_single() {
+        let bird_y = bird_transform.translation.y;
 
-            if bird_y > top_bound || bird_y < bottom_bound {
-                // Отправляем звуковые события
-                collision_events.send(CollisionEvent);
-                game_over_events.send(GameOverEvent);
+        if bird_y > top_bound || bird_y < bottom_bound {
+            // Отправляем звуковые события
+            collision_events.send(CollisionEvent);
+            game_over_events.send(GameOverEvent);
 
-                next_state.set(GameState::GameOver);
-            }
+            next_state.set(GameState::GameOver);
         }
     }
 }
@@ -0,0 +1,227 @@
+//! Монеты
+//!
+//! Монеты расставляются в просветах труб и дугой перед ними: раскладка зависит
+//! только от номера пары, поэтому не трогает генератор случайных чисел и
+//! одинакова в повторе. Подобранные монеты копятся на счёте игрока (`shop`).
+
+use crate::{
+    core::{
+        components::{Bird, Collider},
+        difficulty_types::DifficultySettings,
+        resources::ActivePowerUps,
+        utils::despawn_entities,
+    },
+    plugins::hitboxes::Hitboxes,
+    plugins::pipes::{OscillatingGap, PipeGap, TopPipe},
+    plugins::simulation::InterpolatedTransform,
+    states::game_state::{GameState, PreGameSet, SimulationSet},
+};
+use bevy::prelude::*;
+
+pub const COIN_SIZE: f32 = 24.0;
+/// Дуга из монет перед парой труб: смещение по x от трубы и по y от центра просвета
+const COIN_ARC: [(f32, f32); 3] = [(-150.0, 25.0), (-110.0, 40.0), (-70.0, 25.0)];
+const OFFSCREEN_THRESHOLD: f32 = -500.0;
+
+#[derive(Component)]
+pub struct Coin;
+
+/// Подобрана монета; `value` уже учитывает двойные очки
+#[derive(Event)]
+pub struct CoinCollectedEvent {
+    pub value: u32,
+}
+
+/// Сколько пар труб уже получили монеты в этом забеге
+#[derive(Resource, Default)]
+pub struct CoinSpawner {
+    pairs: u32,
+}
+
+/// Монеты, подобранные в текущем забеге
+#[derive(Resource, Default)]
+pub struct RunCoins(pub u32);
+
+pub struct CoinsPlugin;
+
+impl Plugin for CoinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoinSpawner>()
+            .init_resource::<RunCoins>()
+            .add_event::<CoinCollectedEvent>()
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (despawn_entities::<Coin>, reset_coins).in_set(PreGameSet::Cleanup),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (place_coins, move_coins).in_set(SimulationSet::Movement),
+                    collect_coins.in_set(SimulationSet::Collision),
+                    cleanup_offscreen_coins.in_set(SimulationSet::Scoring),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_entities::<Coin>)
+            .add_systems(OnEnter(GameState::MainMenu), despawn_entities::<Coin>);
+    }
+}
+
+fn reset_coins(mut spawner: ResMut<CoinSpawner>, mut run_coins: ResMut<RunCoins>) {
+    *spawner = CoinSpawner::default();
+    run_coins.0 = 0;
+}
+
+/// Смещения монет относительно центра просвета для пары с номером `pair`
+fn coin_pattern(pair: u32, moving_gap: bool) -> Vec<Vec2> {
+    match pair % 4 {
+        // В подвижный просвет монету не кладём: она оказалась бы внутри трубы
+        0 | 2 if !moving_gap => vec![Vec2::ZERO],
+        1 => COIN_ARC.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Ставит монеты у каждой новой пары труб
+fn place_coins(
+    mut commands: Commands,
+    hitboxes: Res<Hitboxes>,
+    mut spawner: ResMut<CoinSpawner>,
+    query: Query<(&Transform, &PipeGap, Has<OscillatingGap>), Added<TopPipe>>,
+) {
+    let mut pairs: Vec<_> = query.iter().collect();
+    // Несколько пар за шаг появляются только в начале забега
+    pairs.sort_by(|(a, ..), (b, ..)| a.translation.x.total_cmp(&b.translation.x));
+
+    for (transform, gap, moving_gap) in pairs {
+        let center = Vec2::new(transform.translation.x, gap.center);
+        for offset in coin_pattern(spawner.pairs, moving_gap) {
+            spawn_coin_at(&mut commands, &hitboxes, center + offset);
+        }
+        spawner.pairs += 1;
+    }
+}
+
+pub fn spawn_coin_at(commands: &mut Commands, hitboxes: &Hitboxes, position: Vec2) -> Entity {
+    commands
+        .spawn((
+            Coin,
+            Sprite {
+                color: Color::srgb(1.0, 0.84, 0.0),
+                custom_size: Some(Vec2::splat(COIN_SIZE)),
+                ..default()
+            },
+            Transform::from_translation(position.extend(3.0)),
+            InterpolatedTransform::default(),
+            Collider::new(hitboxes.coin),
+        ))
+        .id()
+}
+
+fn move_coins(
+    mut query: Query<&mut Transform, With<Coin>>,
+    time: Res<Time>,
+    difficulty: Res<DifficultySettings>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let speed_multiplier = if active_effects.slow_motion_active {
+        0.3 // Монеты летят вместе с трубами
+    } else {
+        1.0
+    };
+
+    for mut transform in &mut query {
+        transform.translation.x -=
+            difficulty.current_params.pipe_speed * speed_multiplier * time.delta_secs();
+    }
+}
+
+fn collect_coins(
+    mut commands: Commands,
+    bird_query: Query<(&Transform, &Collider), With<Bird>>,
+    coin_query: Query<(Entity, &Transform, &Collider), With<Coin>>,
+    active_effects: Res<ActivePowerUps>,
+    mut run_coins: ResMut<RunCoins>,
+    mut coin_events: EventWriter<CoinCollectedEvent>,
+) {
+    let Ok((bird_transform, bird_collider)) = bird_query.get_single() else {
+        return;
+    };
+
+    for (entity, transform, collider) in &coin_query {
+        if bird_collider.intersects(bird_transform, collider, transform) {
+            let value = if active_effects.double_score_active {
+                2
+            } else {
+                1
+            };
+            commands.entity(entity).despawn();
+            run_coins.0 += value;
+            coin_events.send(CoinCollectedEvent { value });
+        }
+    }
+}
+
+fn cleanup_offscreen_coins(mut commands: Commands, query: Query<(Entity, &Transform), With<Coin>>) {
+    for (entity, transform) in &query {
+        if transform.translation.x < OFFSCREEN_THRESHOLD {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::DoubleScore;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+
+    fn bird(game: &mut HeadlessGame) -> (Entity, Vec2) {
+        let world = game.app.world_mut();
+        let (entity, transform) = world
+            .query_filtered::<(Entity, &Transform), With<Bird>>()
+            .single(world);
+        (entity, transform.translation.truncate())
+    }
+
+    fn spawn_coin(game: &mut HeadlessGame, position: Vec2) {
+        let world = game.app.world_mut();
+        let hitboxes = Hitboxes::default();
+        spawn_coin_at(&mut world.commands(), &hitboxes, position);
+        world.flush();
+    }
+
+    #[test]
+    fn test_coins_placed_in_and_before_gaps() {
+        assert_eq!(coin_pattern(0, false), vec![Vec2::ZERO]);
+        assert!(coin_pattern(0, true).is_empty());
+        assert_eq!(coin_pattern(1, true).len(), COIN_ARC.len());
+        assert!(coin_pattern(3, false).is_empty());
+
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.step();
+        assert!(game.count::<Coin>() > 0);
+    }
+
+    #[test]
+    fn test_double_score_doubles_coins() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+
+        let (_, position) = bird(&mut game);
+        spawn_coin(&mut game, position);
+        game.step();
+        assert_eq!(game.resource::<RunCoins>().0, 1);
+
+        let (entity, _) = bird(&mut game);
+        game.app.world_mut().entity_mut(entity).insert(DoubleScore {
+            timer: Timer::from_seconds(10.0, TimerMode::Once),
+        });
+        game.step();
+        let (_, position) = bird(&mut game);
+        spawn_coin(&mut game, position);
+        game.step();
+        assert_eq!(game.resource::<RunCoins>().0, 3);
+    }
+}
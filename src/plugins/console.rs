@@ -0,0 +1,586 @@
+//! Консоль разработчика
+//!
+//! Выезжает сверху по клавише ` и принимает команды, которые меняют состояние
+//! игры напрямую: счёт, состояние, power-ups, сложность, режим, бессмертие,
+//! скорость времени и seed. Так до поздней стадии забега не нужно доигрывать
+//! руками. Консоль собирается только с feature `dev`.
+
+use crate::{
+    core::{
+        components::{Bird, PowerUpType},
+        difficulty_types::{DifficultyLevel, DifficultySettings},
+        resources::{ActivePowerUps, GameAssets, GameMode, GameModeSettings, GameScore, PlayArea},
+    },
+    plugins::bird::bird_bounds,
+    plugins::game_modes::apply_game_mode,
+    plugins::input_map::ActionState,
+    plugins::powerups::{activate_powerup_effect, power_up_duration},
+    plugins::replay::ReplayPlayback,
+    plugins::rng::GameRng,
+    states::game_state::{GameState, SimulationSet},
+};
+use bevy::ecs::system::SystemParam;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+/// Клавиша, открывающая и закрывающая консоль
+const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+/// Сколько последних строк журнала видно
+const MAX_LOG_LINES: usize = 12;
+/// Допустимая скорость времени
+const TIME_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.05..=4.0;
+
+const HELP: &str = "команды: score N, state <состояние>, powerup shield|double|slow, \
+difficulty easy|normal|hard|progressive, mode classic|timeattack|zen|survival, \
+god on|off, timescale X, seed N";
+
+/// Состояния, в которые можно перейти командой `state`
+const STATES: [GameState; 13] = [
+    GameState::MainMenu,
+    GameState::GameModeSelection,
+    GameState::CourseSelection,
+    GameState::Editor,
+    GameState::Settings,
+    GameState::Statistics,
+    GameState::Profiles,
+    GameState::Achievements,
+    GameState::Shop,
+    GameState::Customization,
+    GameState::PreGame,
+    GameState::Playing,
+    GameState::GameOver,
+];
+
+/// Разобранная команда консоли
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    Score(u32),
+    State(GameState),
+    PowerUp(PowerUpType),
+    Difficulty(DifficultyLevel),
+    Mode(GameMode),
+    God(bool),
+    TimeScale(f32),
+    Seed(u64),
+    Help,
+}
+
+/// Ищет вариант по имени без учёта регистра
+fn find_named<T: Copy + std::fmt::Debug>(variants: &[T], name: &str) -> Option<T> {
+    variants
+        .iter()
+        .copied()
+        .find(|variant| format!("{:?}", variant).eq_ignore_ascii_case(name))
+}
+
+/// Разбирает строку консоли
+pub fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Err("пустая команда".to_string());
+    };
+    let argument = words.next();
+    if words.next().is_some() {
+        return Err(format!("«{}»: слишком много аргументов", name));
+    }
+    let need = |what: &str| argument.ok_or_else(|| format!("{}: нужен аргумент — {}", name, what));
+
+    match name.to_lowercase().as_str() {
+        "help" => Ok(ConsoleCommand::Help),
+        "score" => need("число")?
+            .parse()
+            .map(ConsoleCommand::Score)
+            .map_err(|_| "score: нужно неотрицательное целое".to_string()),
+        "state" => {
+            let state = need("состояние")?;
+            find_named(&STATES, state)
+                .map(ConsoleCommand::State)
+                .ok_or_else(|| format!("неизвестное состояние «{}»", state))
+        }
+        "powerup" => match need("shield, double или slow")?.to_lowercase().as_str() {
+            "shield" => Ok(ConsoleCommand::PowerUp(PowerUpType::Shield)),
+            "double" | "doublescore" => Ok(ConsoleCommand::PowerUp(PowerUpType::DoubleScore)),
+            "slow" | "slowmotion" => Ok(ConsoleCommand::PowerUp(PowerUpType::SlowMotion)),
+            other => Err(format!("неизвестный power-up «{}»", other)),
+        },
+        "difficulty" => {
+            let level = need("уровень")?;
+            let levels = [
+                DifficultyLevel::Easy,
+                DifficultyLevel::Normal,
+                DifficultyLevel::Hard,
+                DifficultyLevel::Progressive,
+            ];
+            find_named(&levels, level)
+                .map(ConsoleCommand::Difficulty)
+                .ok_or_else(|| format!("неизвестная сложность «{}»", level))
+        }
+        "mode" => {
+            let mode = need("режим")?;
+            // Трассе нужен выбранный файл, поэтому её включают только из меню
+            let modes = [
+                GameMode::Classic,
+                GameMode::TimeAttack,
+                GameMode::Zen,
+                GameMode::Survival,
+            ];
+            find_named(&modes, mode)
+                .map(ConsoleCommand::Mode)
+                .ok_or_else(|| format!("неизвестный режим «{}»", mode))
+        }
+        "god" => match need("on или off")? {
+            "on" => Ok(ConsoleCommand::God(true)),
+            "off" => Ok(ConsoleCommand::God(false)),
+            other => Err(format!("god: ожидалось on или off, а не «{}»", other)),
+        },
+        "timescale" => match need("множитель")?.parse::<f32>() {
+            Ok(scale) if TIME_SCALE_RANGE.contains(&scale) => Ok(ConsoleCommand::TimeScale(scale)),
+            _ => Err(format!(
+                "timescale: нужно число от {} до {}",
+                TIME_SCALE_RANGE.start(),
+                TIME_SCALE_RANGE.end()
+            )),
+        },
+        "seed" => need("число")?
+            .parse()
+            .map(ConsoleCommand::Seed)
+            .map_err(|_| "seed: нужно неотрицательное целое".to_string()),
+        _ => Err(format!("неизвестная команда «{}»; help — список", name)),
+    }
+}
+
+/// Строка ввода и журнал консоли
+#[derive(Resource, Default)]
+pub struct DevConsole {
+    pub open: bool,
+    pub input: String,
+    pub log: Vec<String>,
+}
+
+impl DevConsole {
+    fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        if self.log.len() > MAX_LOG_LINES {
+            let extra = self.log.len() - MAX_LOG_LINES;
+            self.log.drain(..extra);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct DevConsoleRoot;
+
+#[derive(Component)]
+pub struct DevConsoleText;
+
+/// Бессмертие из консоли: удары и вылет за край не заканчивают забег
+#[derive(Resource, Default)]
+pub struct GodMode(pub bool);
+
+/// Забег изменён командами консоли: его итог не попадает в рекорды, повторы,
+/// статистику, достижения, кошелёк и ежедневное испытание
+#[derive(Resource, Default)]
+pub struct CheatedRun(pub bool);
+
+/// Условие запуска: текущий забег изменён из консоли
+pub fn is_cheated_run(cheated: Res<CheatedRun>) -> bool {
+    cheated.0
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevConsole>()
+            .init_resource::<GodMode>()
+            .init_resource::<CheatedRun>()
+            .add_event::<ConsoleCommand>()
+            .add_systems(Startup, spawn_console)
+            .add_systems(OnEnter(GameState::PreGame), reset_cheated_run)
+            // Между движением и столкновениями, чтобы проверка границ не увидела вылета
+            .add_systems(
+                FixedUpdate,
+                hold_immortal_bird
+                    .after(SimulationSet::Movement)
+                    .before(SimulationSet::Collision)
+                    .run_if(in_state(GameState::Playing).and(is_god_mode)),
+            )
+            .add_systems(
+                Update,
+                (
+                    console_input,
+                    run_console_commands,
+                    update_console_ui.run_if(resource_changed::<DevConsole>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Бессмертие и скорость времени из консоли переживают забег, поэтому
+/// следующий забег с ними тоже не засчитывается
+fn reset_cheated_run(
+    mut cheated: ResMut<CheatedRun>,
+    god_mode: Res<GodMode>,
+    time: Res<Time<Virtual>>,
+    playback: Res<ReplayPlayback>,
+) {
+    cheated.0 = !playback.is_active() && (god_mode.0 || time.relative_speed() != 1.0);
+}
+
+fn is_god_mode(god_mode: Res<GodMode>) -> bool {
+    god_mode.0
+}
+
+/// Бессмертную птицу край поля просто останавливает
+fn hold_immortal_bird(mut query: Query<(&mut Transform, &mut Bird)>, play_area: Res<PlayArea>) {
+    let (bottom_bound, top_bound) = bird_bounds(&play_area);
+    for (mut transform, mut bird) in &mut query {
+        let y = transform.translation.y;
+        if y > top_bound || y < bottom_bound {
+            transform.translation.y = y.clamp(bottom_bound, top_bound);
+            bird.velocity = 0.0;
+        }
+    }
+}
+
+fn spawn_console(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::FlexEnd,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.85)),
+            GlobalZIndex(200),
+            Visibility::Hidden,
+            DevConsoleRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                DevConsoleText,
+            ));
+        });
+}
+
+/// Открытие консоли и набор команды: Enter — выполнить, Esc — закрыть.
+/// Пока консоль открыта, игровые действия не срабатывают
+fn console_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut console: ResMut<DevConsole>,
+    mut actions: ResMut<ActionState>,
+    mut commands: EventWriter<ConsoleCommand>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        if event.key_code == TOGGLE_KEY {
+            console.open = !console.open;
+            actions.suspended = console.open;
+            continue;
+        }
+        if !console.open {
+            continue;
+        }
+        match (&event.key_code, &event.logical_key) {
+            (KeyCode::Enter | KeyCode::NumpadEnter, _) => {
+                let line = std::mem::take(&mut console.input);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                console.print(format!("> {}", line));
+                match parse_command(&line) {
+                    Ok(command) => {
+                        commands.send(command);
+                    }
+                    Err(error) => console.print(error),
+                }
+            }
+            (KeyCode::Escape, _) => {
+                console.open = false;
+                actions.suspended = false;
+            }
+            (KeyCode::Backspace, _) => {
+                console.input.pop();
+            }
+            (_, Key::Character(text)) => {
+                console.input.push_str(text);
+            }
+            (_, Key::Space) => {
+                console.input.push(' ');
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Ресурсы, которые меняют команды консоли
+#[derive(SystemParam)]
+pub struct ConsoleTargets<'w, 's> {
+    commands: Commands<'w, 's>,
+    assets: Res<'w, GameAssets>,
+    score: ResMut<'w, GameScore>,
+    next_state: ResMut<'w, NextState<GameState>>,
+    active_effects: ResMut<'w, ActivePowerUps>,
+    difficulty: ResMut<'w, DifficultySettings>,
+    mode_settings: ResMut<'w, GameModeSettings>,
+    god_mode: ResMut<'w, GodMode>,
+    cheated: ResMut<'w, CheatedRun>,
+    time: ResMut<'w, Time<Virtual>>,
+    rng: ResMut<'w, GameRng>,
+    bird_query: Query<'w, 's, Entity, With<Bird>>,
+}
+
+impl ConsoleTargets<'_, '_> {
+    /// Выполняет команду и возвращает строку для журнала
+    fn apply(&mut self, command: &ConsoleCommand) -> String {
+        // Команды, меняющие ход текущего забега, лишают его зачёта
+        let changes_run = match *command {
+            ConsoleCommand::Score(_)
+            | ConsoleCommand::PowerUp(_)
+            | ConsoleCommand::Difficulty(_)
+            | ConsoleCommand::Mode(_) => true,
+            ConsoleCommand::God(enabled) => enabled,
+            ConsoleCommand::TimeScale(scale) => scale != 1.0,
+            ConsoleCommand::State(_) | ConsoleCommand::Seed(_) | ConsoleCommand::Help => false,
+        };
+        if changes_run {
+            self.cheated.0 = true;
+        }
+
+        match *command {
+            ConsoleCommand::Help => HELP.to_string(),
+            ConsoleCommand::Score(score) => {
+                self.score.0 = score;
+                self.difficulty.update_progressive_params(score);
+                format!("счёт: {}", score)
+            }
+            ConsoleCommand::State(state) => {
+                self.next_state.set(state);
+                format!("состояние: {:?}", state)
+            }
+            ConsoleCommand::PowerUp(power_type) => {
+                let Ok(bird) = self.bird_query.get_single() else {
+                    return "power-up выдаётся только во время забега".to_string();
+                };
+                activate_powerup_effect(
+                    &mut self.commands,
+                    &self.assets,
+                    power_type,
+                    power_up_duration(power_type),
+                    bird,
+                );
+                match power_type {
+                    PowerUpType::Shield => self.active_effects.shield_active = true,
+                    PowerUpType::DoubleScore => self.active_effects.double_score_active = true,
+                    PowerUpType::SlowMotion => self.active_effects.slow_motion_active = true,
+                }
+                format!("power-up: {:?}", power_type)
+            }
+            ConsoleCommand::Difficulty(level) => {
+                self.difficulty.set_level(level);
+                self.difficulty.update_progressive_params(self.score.0);
+                format!("сложность: {}", level.russian_name())
+            }
+            ConsoleCommand::Mode(mode) => {
+                apply_game_mode(&mut self.mode_settings, mode);
+                format!("режим: {:?} (таймеры и жизни — со следующего забега)", mode)
+            }
+            ConsoleCommand::God(enabled) => {
+                self.god_mode.0 = enabled;
+                format!("бессмертие: {}", if enabled { "вкл" } else { "выкл" })
+            }
+            ConsoleCommand::TimeScale(scale) => {
+                self.time.set_relative_speed(scale);
+                format!("скорость времени: {}", scale)
+            }
+            ConsoleCommand::Seed(seed) => {
+                self.rng.fixed_seed = Some(seed);
+                format!("seed: {} (со следующего забега)", seed)
+            }
+        }
+    }
+}
+
+fn run_console_commands(
+    mut events: EventReader<ConsoleCommand>,
+    mut targets: ConsoleTargets,
+    mut console: ResMut<DevConsole>,
+) {
+    for command in events.read() {
+        let result = targets.apply(command);
+        console.print(result);
+    }
+}
+
+fn update_console_ui(
+    console: Res<DevConsole>,
+    mut root_query: Query<&mut Visibility, With<DevConsoleRoot>>,
+    mut text_query: Query<&mut Text, With<DevConsoleText>>,
+) {
+    for mut visibility in &mut root_query {
+        *visibility = if console.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut text in &mut text_query {
+        let mut lines = console.log.clone();
+        lines.push(format!("> {}_", console.input));
+        **text = lines.join("\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::ActiveShield;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::core::resources::HighScores;
+    use crate::plugins::replay::ReplayRecorder;
+    use crate::plugins::statistics::GameStatistics;
+
+    fn run(game: &mut HeadlessGame, line: &str) {
+        game.type_text(line);
+        game.tap_key(KeyCode::Enter);
+        game.step();
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_command("score 50"), Ok(ConsoleCommand::Score(50)));
+        assert_eq!(
+            parse_command("state GameOver"),
+            Ok(ConsoleCommand::State(GameState::GameOver))
+        );
+        assert_eq!(
+            parse_command("powerup shield"),
+            Ok(ConsoleCommand::PowerUp(PowerUpType::Shield))
+        );
+        assert_eq!(
+            parse_command("difficulty progressive"),
+            Ok(ConsoleCommand::Difficulty(DifficultyLevel::Progressive))
+        );
+        assert_eq!(
+            parse_command("mode survival"),
+            Ok(ConsoleCommand::Mode(GameMode::Survival))
+        );
+        assert_eq!(parse_command("god on"), Ok(ConsoleCommand::God(true)));
+        assert_eq!(
+            parse_command("timescale 0.25"),
+            Ok(ConsoleCommand::TimeScale(0.25))
+        );
+        assert_eq!(parse_command("seed 1234"), Ok(ConsoleCommand::Seed(1234)));
+
+        for bad in [
+            "",
+            "fly",
+            "score",
+            "score -3",
+            "state Nowhere",
+            "god maybe",
+            "timescale 100",
+            "mode course",
+        ] {
+            assert!(parse_command(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_console_commands_change_game() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.tap_key(TOGGLE_KEY);
+        assert!(game.resource::<DevConsole>().open);
+        assert!(game.resource::<ActionState>().suspended);
+
+        // Пока набираются команды, птица не должна разбиться
+        run(&mut game, "god on");
+        run(&mut game, "score 50");
+        assert_eq!(game.resource::<GameScore>().0, 50);
+
+        run(&mut game, "powerup shield");
+        assert!(game.resource::<ActivePowerUps>().shield_active);
+        assert_eq!(game.count::<ActiveShield>(), 1);
+
+        run(&mut game, "timescale 0.25");
+        assert_eq!(game.resource::<Time<Virtual>>().relative_speed(), 0.25);
+
+        run(&mut game, "seed 1234");
+        assert_eq!(game.resource::<GameRng>().fixed_seed, Some(1234));
+
+        run(&mut game, "nonsense");
+        let log = &game.resource::<DevConsole>().log;
+        assert!(log.last().unwrap().contains("неизвестная команда"));
+
+        game.tap_key(KeyCode::Escape);
+        game.step();
+        assert!(!game.resource::<DevConsole>().open);
+        assert!(!game.resource::<ActionState>().suspended);
+        assert_eq!(
+            game.resource::<State<GameState>>().get(),
+            &GameState::Playing
+        );
+    }
+
+    #[test]
+    fn test_god_mode_survives_falling() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.tap_key(TOGGLE_KEY);
+        run(&mut game, "god on");
+        assert!(game.resource::<GodMode>().0);
+
+        // Без прыжков птица падает на дно, но забег продолжается
+        game.step_frames(300);
+        assert_eq!(
+            game.resource::<State<GameState>>().get(),
+            &GameState::Playing
+        );
+
+        run(&mut game, "state GameOver");
+        game.step();
+        assert_eq!(
+            game.resource::<State<GameState>>().get(),
+            &GameState::GameOver
+        );
+    }
+
+    #[test]
+    fn test_cheated_run_is_not_recorded() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        game.tap_key(TOGGLE_KEY);
+        run(&mut game, "score 50");
+        assert!(game.resource::<CheatedRun>().0);
+        run(&mut game, "state GameOver");
+        assert!(game.step_until_state(GameState::GameOver));
+
+        assert!(game.resource::<HighScores>().scores.is_empty());
+        assert!(game.resource::<ReplayRecorder>().last_replay.is_none());
+        assert_eq!(game.resource::<GameStatistics>().total_games, 0);
+
+        // Следующий забег без бессмертия снова засчитывается
+        run(&mut game, "state MainMenu");
+        assert!(game.step_until_state(GameState::MainMenu));
+        game.tap_key(TOGGLE_KEY);
+        game.start_run();
+        assert!(!game.resource::<CheatedRun>().0);
+    }
+}
@@ -0,0 +1,360 @@
+//! Экран управления: назначение клавиш игровым действиям
+
+use crate::{
+    core::{
+        resources::GameAssets,
+        save::{self, SaveDir},
+        utils::despawn_entities,
+    },
+    plugins::pause::PAUSE_BACKDROP,
+    plugins::{
+        input_map::{ActionState, GameAction, InputBinding, InputBindings},
+        settings_ui::spawn_menu_button,
+    },
+    states::game_state::{PauseState, SettingsPage},
+};
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, UiRect, Val};
+
+/// Плагин экрана управления
+pub struct ControlsUIPlugin;
+
+impl Plugin for ControlsUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BindingEditor>()
+            .add_systems(OnEnter(SettingsPage::Controls), spawn_controls_ui)
+            .add_systems(
+                Update,
+                (
+                    capture_binding,
+                    handle_controls_buttons,
+                    update_controls_text,
+                )
+                    .chain()
+                    .run_if(in_state(SettingsPage::Controls)),
+            )
+            .add_systems(
+                OnExit(SettingsPage::Controls),
+                (despawn_entities::<OnControlsScreen>, stop_capture),
+            );
+    }
+}
+
+/// Состояние редактора привязок
+#[derive(Resource, Default)]
+pub struct BindingEditor {
+    /// Действие, для которого ждём нажатие клавиши
+    capturing: Option<GameAction>,
+    /// Сообщение под списком действий
+    message: Option<String>,
+}
+
+/// Маркер-компонент для экрана управления
+#[derive(Component)]
+pub struct OnControlsScreen;
+
+/// Текст с привязками действия
+#[derive(Component)]
+pub struct BindingsText(GameAction);
+
+/// Кнопка добавления привязки
+#[derive(Component)]
+pub struct AddBindingButton(GameAction);
+
+/// Кнопка удаления всех привязок действия
+#[derive(Component)]
+pub struct ClearBindingsButton(GameAction);
+
+/// Кнопка сброса привязок по умолчанию
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+/// Кнопка возврата к основным настройкам
+#[derive(Component)]
+pub struct ControlsBackButton;
+
+/// Строка подсказок и конфликтов
+#[derive(Component)]
+pub struct ControlsStatusText;
+
+fn spawn_controls_ui(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    pause_state: Option<Res<State<PauseState>>>,
+) {
+    let background = if pause_state.is_some() {
+        PAUSE_BACKDROP
+    } else {
+        Color::NONE
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(background),
+            OnControlsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("🎮 Управление"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+
+            for action in GameAction::ALL {
+                spawn_action_row(parent, &assets, action);
+            }
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                Node {
+                    margin: UiRect::vertical(Val::Px(15.0)),
+                    ..default()
+                },
+                ControlsStatusText,
+            ));
+
+            spawn_menu_button(parent, &assets, "↺ По умолчанию", ResetBindingsButton);
+            spawn_menu_button(parent, &assets, "🔙 Назад", ControlsBackButton);
+        });
+}
+
+/// Создает строку действия: название, привязки и кнопки «+» и «✕»
+fn spawn_action_row(parent: &mut ChildBuilder, assets: &GameAssets, action: GameAction) {
+    let text_font = TextFont {
+        font: assets.font.clone(),
+        font_size: 22.0,
+        ..default()
+    };
+
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            margin: UiRect::vertical(Val::Px(4.0)),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Text::new(action.russian_name()),
+                text_font.clone(),
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                Node {
+                    width: Val::Px(180.0),
+                    ..default()
+                },
+            ));
+            row.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+                Node {
+                    width: Val::Px(260.0),
+                    ..default()
+                },
+                BindingsText(action),
+            ));
+            spawn_small_button(row, text_font.clone(), "+", AddBindingButton(action));
+            spawn_small_button(row, text_font, "✕", ClearBindingsButton(action));
+        });
+}
+
+fn spawn_small_button(
+    parent: &mut ChildBuilder,
+    text_font: TextFont,
+    text: &str,
+    button_component: impl Component,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(40.0),
+                height: Val::Px(36.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                margin: UiRect::left(Val::Px(8.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            button_component,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(text), text_font, TextColor(Color::WHITE)));
+        });
+}
+
+/// Ждёт нажатия клавиши, кнопки мыши или геймпада и назначает его действию. Esc отменяет
+#[allow(clippy::too_many_arguments)]
+fn capture_binding(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<&Gamepad>,
+    mut editor: ResMut<BindingEditor>,
+    mut actions: ResMut<ActionState>,
+    mut bindings: ResMut<InputBindings>,
+    save_dir: Res<SaveDir>,
+) {
+    let Some(action) = editor.capturing else {
+        return;
+    };
+
+    let binding = match keys.get_just_pressed().next() {
+        Some(KeyCode::Escape) => {
+            editor.capturing = None;
+            editor.message = None;
+            actions.suspended = false;
+            return;
+        }
+        Some(&key) => InputBinding::Key(key),
+        None => {
+            let gamepad_button = gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next().copied());
+            if let Some(&button) = mouse_buttons.get_just_pressed().next() {
+                InputBinding::Mouse(button)
+            } else if let Some(button) = gamepad_button {
+                InputBinding::Gamepad(button)
+            } else if touches.any_just_pressed() {
+                InputBinding::Touch
+            } else {
+                return;
+            }
+        }
+    };
+
+    editor.capturing = None;
+    actions.suspended = false;
+    editor.message = match bindings.bind(action, binding) {
+        Ok(()) => {
+            save::save(&save_dir, &*bindings);
+            None
+        }
+        Err(conflict) => Some(format!(
+            "{} уже назначена на «{}»",
+            conflict.binding.label(),
+            conflict.first.russian_name()
+        )),
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_controls_buttons(
+    add_query: Query<(&Interaction, &AddBindingButton), Changed<Interaction>>,
+    clear_query: Query<(&Interaction, &ClearBindingsButton), Changed<Interaction>>,
+    reset_query: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<ControlsBackButton>)>,
+    mut editor: ResMut<BindingEditor>,
+    mut actions: ResMut<ActionState>,
+    mut bindings: ResMut<InputBindings>,
+    save_dir: Res<SaveDir>,
+    mut next_page: ResMut<NextState<SettingsPage>>,
+) {
+    for (interaction, button) in &add_query {
+        if *interaction == Interaction::Pressed {
+            editor.capturing = Some(button.0);
+            editor.message = None;
+            actions.suspended = true;
+        }
+    }
+
+    for (interaction, button) in &clear_query {
+        if *interaction == Interaction::Pressed {
+            bindings.clear(button.0);
+            save::save(&save_dir, &*bindings);
+        }
+    }
+
+    for interaction in &reset_query {
+        if *interaction == Interaction::Pressed {
+            *bindings = InputBindings::default();
+            editor.message = None;
+            save::save(&save_dir, &*bindings);
+        }
+    }
+
+    for interaction in &back_query {
+        if *interaction == Interaction::Pressed {
+            next_page.set(SettingsPage::General);
+        }
+    }
+}
+
+fn update_controls_text(
+    editor: Res<BindingEditor>,
+    bindings: Res<InputBindings>,
+    mut bindings_text: Query<(&mut Text, &BindingsText), Without<ControlsStatusText>>,
+    mut status_text: Query<&mut Text, With<ControlsStatusText>>,
+) {
+    for (mut text, BindingsText(action)) in &mut bindings_text {
+        let labels: Vec<String> = bindings
+            .get(*action)
+            .iter()
+            .map(|binding| binding.label())
+            .collect();
+        let label = if labels.is_empty() {
+            "—".to_string()
+        } else {
+            labels.join(", ")
+        };
+        if **text != label {
+            **text = label;
+        }
+    }
+
+    let status = if let Some(action) = editor.capturing {
+        format!(
+            "Нажмите клавишу для «{}» (Esc — отмена)",
+            action.russian_name()
+        )
+    } else if let Some(message) = &editor.message {
+        message.clone()
+    } else {
+        bindings
+            .conflicts()
+            .iter()
+            .map(|conflict| {
+                format!(
+                    "Конфликт: {} — «{}» и «{}»",
+                    conflict.binding.label(),
+                    conflict.first.russian_name(),
+                    conflict.second.russian_name()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    for mut text in &mut status_text {
+        if **text != status {
+            **text = status.clone();
+        }
+    }
+}
+
+fn stop_capture(mut editor: ResMut<BindingEditor>, mut actions: ResMut<ActionState>) {
+    *editor = BindingEditor::default();
+    actions.suspended = false;
+}
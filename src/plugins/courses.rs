@@ -0,0 +1,850 @@
+//! Трассы из файлов
+//!
+//! Трасса — заранее расставленная последовательность пар труб с необязательными
+//! power-ups. Файлы `*.course.ron` и `*.course.json` лежат в `assets/courses`
+//! и загружаются как обычные ассеты, поэтому правка файла подхватывается
+//! без перезапуска игры. В режиме «Трасса» трубы не генерируются случайно:
+//! забег заканчивается, когда пройдена последняя пара труб.
+//! Трассы можно собирать и во встроенном редакторе (`editor`).
+
+use crate::{
+    core::{
+        components::{CoursesButton, MenuButton, PowerUpType, Scrollable},
+        difficulty_types::DifficultySettings,
+        resources::{GameAssets, GameMode, GameModeSettings, GameScore, PlayArea},
+        utils::{despawn_entities, fnv1a},
+    },
+    plugins::{
+        game_modes::apply_game_mode,
+        hitboxes::Hitboxes,
+        pipes::spawn_pipes_at,
+        powerups::{spawn_powerup_at, POWERUP_SCROLL_SPEED},
+        settings_ui::spawn_menu_button,
+        simulation::RunClock,
+    },
+    states::game_state::{GameOverSet, GameState, PreGameSet, SimulationSet},
+};
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, LoadState, LoadedFolder};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, Overflow, UiRect, Val};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Папка с трассами внутри `assets`
+const COURSES_FOLDER: &str = "courses";
+
+/// Трасса из файла
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Course {
+    pub name: String,
+    pub pipes: Vec<CoursePipe>,
+    /// Power-ups вне просветов труб
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub power_ups: Vec<CoursePowerUp>,
+}
+
+/// Пара труб трассы
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoursePipe {
+    /// Расстояние от предыдущей пары труб; у первой — от птицы
+    pub spacing: f32,
+    /// Высота центра просвета
+    pub gap_y: f32,
+    /// Высота просвета; по умолчанию берётся из текущей сложности
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap: Option<f32>,
+    /// Power-up в центре просвета
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_up: Option<PowerUpType>,
+}
+
+/// Power-up в произвольной точке трассы
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoursePowerUp {
+    /// Расстояние от птицы по той же оси, что и трубы
+    pub x: f32,
+    pub y: f32,
+    pub kind: PowerUpType,
+}
+
+impl Course {
+    /// Проверяет, что по трассе можно пролететь
+    pub fn validate(&self) -> Result<(), CourseLoadError> {
+        if self.pipes.is_empty() {
+            return Err(CourseLoadError::Invalid("в трассе нет труб".to_string()));
+        }
+        for (index, pipe) in self.pipes.iter().enumerate() {
+            if !pipe.spacing.is_finite() || pipe.spacing <= 0.0 {
+                return Err(CourseLoadError::Invalid(format!(
+                    "труба {}: spacing должен быть больше нуля",
+                    index + 1
+                )));
+            }
+            if pipe.gap.is_some_and(|gap| !gap.is_finite() || gap <= 0.0) {
+                return Err(CourseLoadError::Invalid(format!(
+                    "труба {}: gap должен быть больше нуля",
+                    index + 1
+                )));
+            }
+        }
+        for (index, power_up) in self.power_ups.iter().enumerate() {
+            if !power_up.x.is_finite() || power_up.x <= 0.0 || !power_up.y.is_finite() {
+                return Err(CourseLoadError::Invalid(format!(
+                    "power-up {}: x должен быть больше нуля",
+                    index + 1
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Хэш раскладки трассы (FNV-1a). Им помечаются повторы и призраки:
+    /// имя трассы не влияет на забег, поэтому в хэш не входит
+    pub fn content_hash(&self) -> u64 {
+        fnv1a(&serde_json::to_vec(&(&self.pipes, &self.power_ups)).unwrap_or_default())
+    }
+
+    /// Расстояния от птицы до каждой пары труб
+    pub fn pipe_positions(&self) -> Vec<f32> {
+        self.pipes
+            .iter()
+            .scan(0.0, |x, pipe| {
+                *x += pipe.spacing;
+                Some(*x)
+            })
+            .collect()
+    }
+}
+
+/// Сериализует трассу в RON или JSON, как при загрузке
+pub fn serialize_course(course: &Course, json: bool) -> Result<String, String> {
+    if json {
+        serde_json::to_string_pretty(course).map_err(|error| error.to_string())
+    } else {
+        let config = ron::ser::PrettyConfig::new()
+            .extensions(ron::extensions::Extensions::IMPLICIT_SOME)
+            .struct_names(false);
+        ron::ser::to_string_pretty(course, config).map_err(|error| error.to_string())
+    }
+}
+
+/// Ошибка загрузки файла трассы
+#[derive(Debug)]
+pub enum CourseLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for CourseLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CourseLoadError::Io(error) => write!(f, "не удалось прочитать трассу: {}", error),
+            CourseLoadError::Parse(error) => write!(f, "ошибка в файле трассы: {}", error),
+            CourseLoadError::Invalid(error) => write!(f, "некорректная трасса: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for CourseLoadError {}
+
+impl From<std::io::Error> for CourseLoadError {
+    fn from(error: std::io::Error) -> Self {
+        CourseLoadError::Io(error)
+    }
+}
+
+/// Разбирает трассу из RON или JSON
+pub fn parse_course(bytes: &[u8], json: bool) -> Result<Course, CourseLoadError> {
+    let course: Course = if json {
+        serde_json::from_slice(bytes).map_err(|error| CourseLoadError::Parse(error.to_string()))?
+    } else {
+        ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|error| CourseLoadError::Parse(error.to_string()))?
+    };
+    course.validate()?;
+    Ok(course)
+}
+
+/// Загрузчик `*.course.ron` и `*.course.json`
+#[derive(Default)]
+pub struct CourseLoader;
+
+impl AssetLoader for CourseLoader {
+    type Asset = Course;
+    type Settings = ();
+    type Error = CourseLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Course, CourseLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let json = load_context
+            .path()
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        parse_course(&bytes, json)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["course.ron", "course.json"]
+    }
+}
+
+/// Все трассы из папки `assets/courses`
+#[derive(Resource)]
+pub struct CourseLibrary {
+    folder: Handle<LoadedFolder>,
+}
+
+impl CourseLibrary {
+    /// Папка трасс ещё загружается
+    pub fn is_loading(&self, asset_server: &AssetServer) -> bool {
+        matches!(
+            asset_server.get_load_state(&self.folder),
+            Some(LoadState::NotLoaded | LoadState::Loading)
+        )
+    }
+}
+
+/// Ищет загруженную трассу по хэшу раскладки
+pub fn find_course(courses: &mut Assets<Course>, hash: u64) -> Option<Handle<Course>> {
+    let id = courses
+        .iter()
+        .find(|(_, course)| course.content_hash() == hash)
+        .map(|(id, _)| id)?;
+    courses.get_strong_handle(id)
+}
+
+/// Трасса, выбранная для режима «Трасса»
+#[derive(Resource, Default)]
+pub struct SelectedCourse(pub Option<Handle<Course>>);
+
+/// Сколько пар труб в текущем забеге по трассе
+#[derive(Resource, Default)]
+pub struct CourseRun {
+    pub total: u32,
+}
+
+/// Итог последнего забега по трассе
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseResult {
+    pub name: String,
+    pub completed: bool,
+    pub passed: u32,
+    pub total: u32,
+    pub time: f32,
+    pub score: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct CourseResults {
+    pub last: Option<CourseResult>,
+}
+
+/// Трасса текущего забега, если выбран режим «Трасса»
+#[derive(SystemParam)]
+pub struct ActiveCourse<'w> {
+    mode_settings: Res<'w, GameModeSettings>,
+    selected: Res<'w, SelectedCourse>,
+    courses: Res<'w, Assets<Course>>,
+}
+
+impl ActiveCourse<'_> {
+    pub fn get(&self) -> Option<&Course> {
+        if self.mode_settings.current_mode != GameMode::Course {
+            return None;
+        }
+        self.selected
+            .0
+            .as_ref()
+            .and_then(|handle| self.courses.get(handle))
+    }
+
+    pub fn content_hash(&self) -> Option<u64> {
+        self.get().map(Course::content_hash)
+    }
+}
+
+/// Идёт забег по выбранной трассе
+pub fn is_course_run(mode_settings: Res<GameModeSettings>, selected: Res<SelectedCourse>) -> bool {
+    mode_settings.current_mode == GameMode::Course && selected.0.is_some()
+}
+
+#[derive(Component)]
+pub struct OnCourseSelectionScreen;
+
+/// Список трасс на экране выбора
+#[derive(Component)]
+struct CourseList;
+
+/// Кнопка выбора трассы
+#[derive(Component)]
+pub struct CourseButton(pub AssetId<Course>);
+
+/// Открыть трассу в редакторе
+#[derive(Component)]
+pub struct EditCourseButton(pub AssetId<Course>);
+
+#[derive(Component)]
+pub struct NewCourseButton;
+
+#[derive(Component)]
+pub struct CoursesBackButton;
+
+/// Плагин трасс
+pub struct CoursesPlugin;
+
+impl Plugin for CoursesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Course>()
+            .init_asset_loader::<CourseLoader>()
+            .init_resource::<SelectedCourse>()
+            .init_resource::<CourseRun>()
+            .init_resource::<CourseResults>()
+            .add_systems(Startup, load_course_library)
+            .add_systems(
+                Update,
+                open_course_selection.run_if(in_state(GameState::GameModeSelection)),
+            )
+            .add_systems(OnEnter(GameState::CourseSelection), spawn_course_selection)
+            .add_systems(
+                Update,
+                (
+                    refresh_course_list.run_if(on_event::<AssetEvent<Course>>),
+                    handle_course_selection,
+                )
+                    .run_if(in_state(GameState::CourseSelection)),
+            )
+            .add_systems(
+                OnExit(GameState::CourseSelection),
+                despawn_entities::<OnCourseSelectionScreen>,
+            )
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (
+                    reset_course_run.in_set(PreGameSet::Cleanup),
+                    spawn_course.in_set(PreGameSet::Spawn).run_if(is_course_run),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                check_course_finished
+                    .in_set(SimulationSet::Timers)
+                    .run_if(in_state(GameState::Playing).and(is_course_run)),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_course_result
+                    .before(GameOverSet::SpawnUi)
+                    .run_if(is_course_run),
+            );
+    }
+}
+
+fn load_course_library(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CourseLibrary {
+        folder: asset_server.load_folder(COURSES_FOLDER),
+    });
+}
+
+fn open_course_selection(
+    query: Query<&Interaction, (Changed<Interaction>, With<CoursesButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::CourseSelection);
+    }
+}
+
+fn spawn_course_selection(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    courses: Res<Assets<Course>>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnCourseSelectionScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("🏁 Трассы"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(520.0),
+                        height: Val::Px(330.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    CourseList,
+                ))
+                .with_children(|list| spawn_course_rows(list, &assets, &courses));
+
+            spawn_menu_button(parent, &assets, "➕ Новая трасса", NewCourseButton);
+            spawn_menu_button(parent, &assets, "🔙 Назад", CoursesBackButton);
+        });
+}
+
+/// Пересобирает список, когда трасса загрузилась или файл изменился
+fn refresh_course_list(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    courses: Res<Assets<Course>>,
+    list_query: Query<Entity, With<CourseList>>,
+) {
+    for list in &list_query {
+        commands
+            .entity(list)
+            .despawn_descendants()
+            .with_children(|list| spawn_course_rows(list, &assets, &courses));
+    }
+}
+
+fn spawn_course_rows(parent: &mut ChildBuilder, assets: &GameAssets, courses: &Assets<Course>) {
+    let mut sorted: Vec<_> = courses.iter().collect();
+    sorted.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    if sorted.is_empty() {
+        parent.spawn((
+            Text::new(format!("Трассы не найдены в assets/{}", COURSES_FOLDER)),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        ));
+        return;
+    }
+
+    for (id, course) in sorted {
+        parent
+            .spawn(Node {
+                flex_direction: FlexDirection::Row,
+                margin: UiRect::bottom(Val::Px(8.0)),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_course_button(row, assets, course, id);
+                row.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(50.0),
+                        height: Val::Px(50.0),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::left(Val::Px(8.0)),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(8.0)),
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    MenuButton,
+                    EditCourseButton(id),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new("✎"),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 22.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            });
+    }
+}
+
+fn spawn_course_button(
+    parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    course: &Course,
+    id: AssetId<Course>,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(442.0),
+                height: Val::Px(50.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::horizontal(Val::Px(16.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(8.0)),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            MenuButton,
+            CourseButton(id),
+        ))
+        .with_children(|row| {
+            row.spawn((
+                Text::new(course.name.clone()),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            row.spawn((
+                Text::new(format!("{} труб", course.pipes.len())),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+        });
+}
+
+fn handle_course_selection(
+    course_query: Query<(&Interaction, &CourseButton), Changed<Interaction>>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<CoursesBackButton>)>,
+    mut courses: ResMut<Assets<Course>>,
+    mut selected: ResMut<SelectedCourse>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, CourseButton(id)) in &course_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // Сильный хэндл держит трассу загруженной, пока она выбрана
+        if let Some(handle) = courses.get_strong_handle(*id) {
+            selected.0 = Some(handle);
+            apply_game_mode(&mut mode_settings, GameMode::Course);
+            next_state.set(GameState::PreGame);
+            return;
+        }
+    }
+
+    if back_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::GameModeSelection);
+    }
+}
+
+fn reset_course_run(mut course_run: ResMut<CourseRun>, mut results: ResMut<CourseResults>) {
+    course_run.total = 0;
+    results.last = None;
+}
+
+/// Расставляет все трубы и power-ups трассы сразу
+#[allow(clippy::too_many_arguments)]
+fn spawn_course(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    hitboxes: Res<Hitboxes>,
+    play_area: Res<PlayArea>,
+    difficulty: Res<DifficultySettings>,
+    selected: Res<SelectedCourse>,
+    courses: Res<Assets<Course>>,
+    mut course_run: ResMut<CourseRun>,
+) {
+    let Some(course) = selected.0.as_ref().and_then(|handle| courses.get(handle)) else {
+        return;
+    };
+    let params = &difficulty.current_params;
+
+    // Power-ups летят медленнее труб: ставим так, чтобы они долетели
+    // до птицы одновременно с трубами на том же расстоянии
+    let power_up_x = |x: f32| x * POWERUP_SCROLL_SPEED.abs() / params.pipe_speed;
+
+    for (pipe, pipe_x) in course.pipes.iter().zip(course.pipe_positions()) {
+        spawn_pipes_at(
+            &mut commands,
+            &assets,
+            &hitboxes,
+            play_area.height,
+            pipe_x,
+            pipe.gap_y,
+            pipe.gap.unwrap_or(params.pipe_gap),
+        );
+
+        if let Some(power_type) = pipe.power_up {
+            let position = Vec2::new(power_up_x(pipe_x), pipe.gap_y);
+            spawn_powerup_at(&mut commands, &hitboxes, power_type, position);
+        }
+    }
+    for power_up in &course.power_ups {
+        let position = Vec2::new(power_up_x(power_up.x), power_up.y);
+        spawn_powerup_at(&mut commands, &hitboxes, power_up.kind, position);
+    }
+    course_run.total = course.pipes.len() as u32;
+}
+
+/// Трасса пройдена, когда за птицей остались все трубы
+fn check_course_finished(
+    course_run: Res<CourseRun>,
+    remaining: Query<(), With<Scrollable>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if course_run.total > 0 && remaining.is_empty() {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn record_course_result(
+    course_run: Res<CourseRun>,
+    selected: Res<SelectedCourse>,
+    courses: Res<Assets<Course>>,
+    remaining: Query<(), With<Scrollable>>,
+    clock: Res<RunClock>,
+    score: Res<GameScore>,
+    mut results: ResMut<CourseResults>,
+) {
+    let Some(course) = selected.0.as_ref().and_then(|handle| courses.get(handle)) else {
+        return;
+    };
+    let passed = course_run
+        .total
+        .saturating_sub(remaining.iter().count() as u32);
+    results.last = Some(CourseResult {
+        name: course.name.clone(),
+        completed: course_run.total > 0 && passed == course_run.total,
+        passed,
+        total: course_run.total,
+        time: clock.elapsed_secs(),
+        score: score.0,
+    });
+}
+
+/// Итог трассы на экране Game Over
+pub fn spawn_course_results(
+    parent: &mut ChildBuilder,
+    results: &CourseResults,
+    assets: &GameAssets,
+) {
+    let Some(result) = results.last.as_ref() else {
+        return;
+    };
+
+    let (status, color) = if result.completed {
+        (
+            format!("пройдена за {:.1} с", result.time),
+            Color::srgb(0.3, 1.0, 0.3),
+        )
+    } else {
+        (
+            format!(
+                "{}/{} труб за {:.1} с",
+                result.passed, result.total, result.time
+            ),
+            Color::srgb(1.0, 0.6, 0.3),
+        )
+    };
+
+    parent.spawn((
+        Text::new(format!("🏁 {}: {}", result.name, status)),
+        TextFont {
+            font: assets.font.clone(),
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(color),
+        Node {
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::GameModeSelectionButton;
+    use crate::core::components::PowerUp;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::plugins::pipes::Pipe;
+    use crate::plugins::replay::{ReplayPlayback, ReplayRecorder};
+
+    const WARMUP_RON: &str = r#"(
+        name: "Тест",
+        pipes: [
+            (spacing: 400, gap_y: 0),
+            (spacing: 250, gap_y: 50, gap: 220, power_up: Shield),
+        ],
+    )"#;
+
+    #[test]
+    fn test_parse_ron_and_json() {
+        let course = parse_course(WARMUP_RON.as_bytes(), false).unwrap();
+        assert_eq!(course.name, "Тест");
+        assert_eq!(course.pipes.len(), 2);
+        assert_eq!(course.pipes[0].gap, None);
+        assert_eq!(course.pipes[1].gap, Some(220.0));
+        assert_eq!(course.pipes[1].power_up, Some(PowerUpType::Shield));
+
+        let json = serde_json::to_vec(&course).unwrap();
+        assert_eq!(parse_course(&json, true).unwrap(), course);
+    }
+
+    #[test]
+    fn test_rejects_invalid_courses() {
+        let empty = br#"(name: "Empty", pipes: [])"#;
+        assert!(matches!(
+            parse_course(empty, false),
+            Err(CourseLoadError::Invalid(_))
+        ));
+
+        let backwards = br#"(name: "Backwards", pipes: [(spacing: -10, gap_y: 0)])"#;
+        assert!(matches!(
+            parse_course(backwards, false),
+            Err(CourseLoadError::Invalid(_))
+        ));
+
+        assert!(matches!(
+            parse_course(b"{ not json", true),
+            Err(CourseLoadError::Parse(_))
+        ));
+    }
+
+    /// Открывает экран трасс и выбирает трассу по её id
+    fn select_course(game: &mut HeadlessGame, course: Course) -> Handle<Course> {
+        let handle = game.resource_mut::<Assets<Course>>().add(course);
+        game.click_button::<GameModeSelectionButton>();
+        assert!(game.step_until_state(GameState::GameModeSelection));
+        game.click_button::<CoursesButton>();
+        assert!(game.step_until_state(GameState::CourseSelection));
+        // Список пересобирается, когда доходит событие о новой трассе
+        game.step();
+
+        let world = game.app.world_mut();
+        let mut buttons = world.query::<(&mut Interaction, &CourseButton)>();
+        for (mut interaction, button) in buttons.iter_mut(world) {
+            if button.0 == handle.id() {
+                *interaction = Interaction::Pressed;
+            }
+        }
+        assert!(game.step_until_state(GameState::PreGame));
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        assert!(game.step_until_state(GameState::Playing));
+        handle
+    }
+
+    #[test]
+    fn test_course_spawns_authored_pipes_and_finishes() {
+        let mut game = HeadlessGameBuilder::default().build();
+        select_course(
+            &mut game,
+            parse_course(WARMUP_RON.as_bytes(), false).unwrap(),
+        );
+
+        assert_eq!(game.count::<Pipe>(), 4);
+        assert_eq!(game.count::<PowerUp>(), 1);
+        assert_eq!(game.resource::<CourseRun>().total, 2);
+
+        // Вместо полёта отмечаем все трубы пройденными
+        let world = game.app.world_mut();
+        let pipes: Vec<Entity> = world
+            .query_filtered::<Entity, With<Scrollable>>()
+            .iter(world)
+            .collect();
+        for pipe in pipes {
+            world.entity_mut(pipe).remove::<Scrollable>();
+        }
+        assert!(game.step_until_state(GameState::GameOver));
+
+        let result = game.resource::<CourseResults>().last.clone().unwrap();
+        assert_eq!(result.name, "Тест");
+        assert!(result.completed);
+        assert_eq!((result.passed, result.total), (2, 2));
+    }
+
+    #[test]
+    fn test_crash_records_unfinished_course() {
+        let mut game = HeadlessGameBuilder::default().build();
+        select_course(
+            &mut game,
+            parse_course(WARMUP_RON.as_bytes(), false).unwrap(),
+        );
+
+        // Без прыжков птица падает раньше первой трубы
+        game.step_frames(60);
+        assert_eq!(game.game_state(), GameState::GameOver);
+
+        let result = game.resource::<CourseResults>().last.clone().unwrap();
+        assert!(!result.completed);
+        assert_eq!((result.passed, result.total), (0, 2));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_name() {
+        let course = parse_course(WARMUP_RON.as_bytes(), false).unwrap();
+        let mut renamed = course.clone();
+        renamed.name = "Другое имя".to_string();
+        assert_eq!(course.content_hash(), renamed.content_hash());
+
+        let mut moved = course.clone();
+        moved.pipes[0].gap_y += 10.0;
+        assert_ne!(course.content_hash(), moved.content_hash());
+    }
+
+    #[test]
+    fn test_replay_restores_its_course() {
+        let course = parse_course(WARMUP_RON.as_bytes(), false).unwrap();
+        let mut game = HeadlessGameBuilder::default().build();
+        select_course(&mut game, course.clone());
+        game.step_frames(60);
+        assert_eq!(game.game_state(), GameState::GameOver);
+        let replay = game
+            .resource::<ReplayRecorder>()
+            .last_replay
+            .clone()
+            .unwrap();
+        assert_eq!(replay.course, Some(course.content_hash()));
+
+        // Трасса не выбрана: повтор сам находит её по хэшу и возвращает выбор после просмотра
+        let mut viewer = HeadlessGameBuilder::default().build();
+        let _course = viewer.resource_mut::<Assets<Course>>().add(course);
+        viewer.resource_mut::<ReplayPlayback>().queued = Some(replay);
+        assert!(viewer.step_until_state(GameState::Playing));
+        assert_eq!(viewer.resource::<CourseRun>().total, 2);
+        assert!(viewer.resource::<SelectedCourse>().0.is_some());
+
+        viewer
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::MainMenu);
+        assert!(viewer.step_until_state(GameState::MainMenu));
+        assert!(viewer.resource::<SelectedCourse>().0.is_none());
+    }
+}
@@ -0,0 +1,550 @@
+//! Ежедневное испытание
+//!
+//! Режим, сложность и seed испытания выводятся из текущей даты UTC, поэтому
+//! в один день у всех одинаковые трубы и power-ups. Первая попытка за день
+//! засчитывается в историю профиля (`daily.json`), остальные — тренировка.
+//! На время испытания режим и сложность игрока подменяются и возвращаются
+//! при выходе в главное меню, как при просмотре повтора.
+
+use crate::{
+    core::{
+        components::DailyChallengeButton,
+        difficulty_types::{DifficultyLevel, DifficultySettings},
+        resources::{is_cheated_run, GameAssets, GameMode, GameModeSettings, GameScore},
+        save::{self, is_app_exiting, SaveData, SaveDir},
+    },
+    plugins::{
+        game_modes::apply_game_mode,
+        profiles::ProfileSwitched,
+        replay::{is_replay_playing, stop_replay},
+        rng::GameRng,
+    },
+    states::game_state::{GameState, PreGameSet},
+};
+use bevy::prelude::*;
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Режимы, из которых выбирается испытание дня
+const DAILY_MODES: [GameMode; 3] = [GameMode::Classic, GameMode::TimeAttack, GameMode::Survival];
+/// Сложности, из которых выбирается испытание дня
+const DAILY_DIFFICULTIES: [DifficultyLevel; 2] = [DifficultyLevel::Normal, DifficultyLevel::Hard];
+
+/// Испытание конкретного дня
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyChallenge {
+    pub date: NaiveDate,
+    pub seed: u64,
+    pub mode: GameMode,
+    pub difficulty: DifficultyLevel,
+}
+
+impl DailyChallenge {
+    /// Испытание на дату; одно и то же на любой машине
+    pub fn for_date(date: NaiveDate) -> Self {
+        let seed = mix_seed(date.num_days_from_ce() as u64);
+        Self {
+            date,
+            seed,
+            mode: DAILY_MODES[(seed % DAILY_MODES.len() as u64) as usize],
+            difficulty: DAILY_DIFFICULTIES
+                [((seed >> 8) % DAILY_DIFFICULTIES.len() as u64) as usize],
+        }
+    }
+
+    /// Испытание на сегодня по UTC
+    pub fn today() -> Self {
+        Self::for_date(Utc::now().date_naive())
+    }
+
+    /// Подпись для кнопки и экрана результатов
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {}, {}",
+            self.date.format("%d.%m.%Y"),
+            self.mode.russian_name(),
+            self.difficulty.russian_name()
+        )
+    }
+}
+
+/// Перемешивает номер дня (SplitMix64), чтобы соседние дни не давали похожие seed
+fn mix_seed(day: u64) -> u64 {
+    let mut z = day.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Засчитанные попытки по дням
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyHistory {
+    results: BTreeMap<NaiveDate, u32>,
+}
+
+impl SaveData for DailyHistory {
+    const FILE_NAME: &'static str = "daily.json";
+    const VERSION: u32 = 1;
+}
+
+impl DailyHistory {
+    /// Счёт засчитанной попытки за день
+    pub fn result(&self, date: NaiveDate) -> Option<u32> {
+        self.results.get(&date).copied()
+    }
+
+    pub fn record(&mut self, date: NaiveDate, score: u32) {
+        self.results.insert(date, score);
+    }
+
+    /// Лучший день за всю историю
+    pub fn best(&self) -> Option<(NaiveDate, u32)> {
+        self.results
+            .iter()
+            .max_by_key(|(date, score)| (**score, std::cmp::Reverse(**date)))
+            .map(|(date, score)| (*date, *score))
+    }
+
+    /// Сколько дней подряд, заканчивая `date`, сыграна засчитанная попытка
+    pub fn streak(&self, date: NaiveDate) -> u32 {
+        let mut streak = 0;
+        let mut day = Some(date);
+        while let Some(current) = day.filter(|d| self.results.contains_key(d)) {
+            streak += 1;
+            day = current.pred_opt();
+        }
+        streak
+    }
+}
+
+/// Идёт ли сейчас испытание дня
+#[derive(Resource, Default)]
+pub struct DailyRun {
+    active: Option<ActiveDaily>,
+}
+
+struct ActiveDaily {
+    challenge: DailyChallenge,
+    /// Попытка за этот день уже засчитана, дальше только тренировка
+    practice: bool,
+    /// Текущий забег засчитывается
+    scoring: bool,
+    /// Последний завершённый забег был засчитанной попыткой
+    last_scored: bool,
+    // Настройки игрока, которые вернутся после испытания
+    restore_mode: GameModeSettings,
+    restore_difficulty: DifficultyLevel,
+}
+
+impl DailyRun {
+    pub fn challenge(&self) -> Option<&DailyChallenge> {
+        self.active.as_ref().map(|active| &active.challenge)
+    }
+}
+
+/// Условие запуска: сейчас идёт испытание дня
+pub fn is_daily_run(daily: Res<DailyRun>) -> bool {
+    daily.active.is_some()
+}
+
+/// Плагин ежедневного испытания
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DailyRun>()
+            .init_resource::<DailyHistory>()
+            .add_systems(Startup, load_daily_history)
+            .add_systems(
+                Update,
+                load_daily_history.run_if(on_event::<ProfileSwitched>),
+            )
+            .add_systems(
+                Update,
+                start_daily_challenge.run_if(in_state(GameState::GameModeSelection)),
+            )
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                prepare_daily_run
+                    .in_set(PreGameSet::Cleanup)
+                    .run_if(is_daily_run.and(not(is_replay_playing))),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                begin_daily_attempt.run_if(is_daily_run.and(not(is_replay_playing))),
+            )
+            // Выход из забега через меню паузы тоже расходует попытку
+            .add_systems(
+                OnExit(GameState::Playing),
+                is_cheated_run
+                    .pipe(finish_daily_attempt)
+                    .run_if(is_daily_run.and(not(is_replay_playing))),
+            )
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                // Повтор возвращает настройки испытания, а испытание — настройки игрока
+                stop_daily_challenge.after(stop_replay),
+            )
+            .add_systems(
+                Last,
+                is_cheated_run
+                    .pipe(save_daily_history)
+                    .run_if(is_app_exiting),
+            );
+    }
+}
+
+fn load_daily_history(save_dir: Res<SaveDir>, mut history: ResMut<DailyHistory>) {
+    *history = save::load::<DailyHistory>(&save_dir).unwrap_or_default();
+}
+
+fn start_daily_challenge(
+    query: Query<&Interaction, (Changed<Interaction>, With<DailyChallengeButton>)>,
+    history: Res<DailyHistory>,
+    mut daily: ResMut<DailyRun>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut difficulty: ResMut<DifficultySettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+
+    let challenge = DailyChallenge::today();
+    let (restore_mode, restore_difficulty) = match daily.active.take() {
+        Some(active) => (active.restore_mode, active.restore_difficulty),
+        None => (mode_settings.clone(), difficulty.current_level),
+    };
+    daily.active = Some(ActiveDaily {
+        challenge,
+        practice: history.result(challenge.date).is_some(),
+        scoring: false,
+        last_scored: false,
+        restore_mode,
+        restore_difficulty,
+    });
+
+    apply_game_mode(&mut mode_settings, challenge.mode);
+    difficulty.set_level(challenge.difficulty);
+    info!("Испытание дня {}", challenge.summary());
+    next_state.set(GameState::PreGame);
+}
+
+/// Перед каждым забегом испытания подставляет seed дня
+fn prepare_daily_run(daily: Res<DailyRun>, mut rng: ResMut<GameRng>) {
+    if let Some(challenge) = daily.challenge() {
+        rng.next_run_seed = Some(challenge.seed);
+    }
+}
+
+/// Первый начатый забег дня становится засчитанной попыткой
+/// Попытка записывается на диск сразу: закрытие игры посреди забега
+/// не должно возвращать зачётную попытку
+fn begin_daily_attempt(
+    save_dir: Res<SaveDir>,
+    mut daily: ResMut<DailyRun>,
+    mut history: ResMut<DailyHistory>,
+) {
+    let Some(active) = daily.active.as_mut() else {
+        return;
+    };
+    if !active.practice {
+        history.record(active.challenge.date, 0);
+        active.practice = true;
+        active.scoring = true;
+        save::save(&save_dir, &*history);
+    }
+}
+
+/// При закрытии окна посреди зачётной попытки сохраняем набранный счёт
+fn save_daily_history(
+    In(cheated): In<bool>,
+    save_dir: Res<SaveDir>,
+    score: Res<GameScore>,
+    daily: Res<DailyRun>,
+    mut history: ResMut<DailyHistory>,
+) {
+    let scoring = daily
+        .active
+        .as_ref()
+        .filter(|active| active.scoring && !cheated);
+    if let Some(active) = scoring {
+        history.record(active.challenge.date, score.0);
+    }
+    save::save(&save_dir, &*history);
+}
+
+/// Попытка, изменённая из консоли, расходуется с нулевым счётом
+fn finish_daily_attempt(
+    In(cheated): In<bool>,
+    save_dir: Res<SaveDir>,
+    score: Res<GameScore>,
+    mut daily: ResMut<DailyRun>,
+    mut history: ResMut<DailyHistory>,
+) {
+    let Some(active) = daily.active.as_mut() else {
+        return;
+    };
+    active.last_scored = active.scoring;
+    if active.scoring {
+        active.scoring = false;
+        if !cheated {
+            history.record(active.challenge.date, score.0);
+            save::save(&save_dir, &*history);
+        }
+    }
+}
+
+/// Завершает испытание и возвращает настройки игрока
+fn stop_daily_challenge(
+    mut daily: ResMut<DailyRun>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut difficulty: ResMut<DifficultySettings>,
+) {
+    let Some(active) = daily.active.take() else {
+        return;
+    };
+    *mode_settings = active.restore_mode;
+    difficulty.set_level(active.restore_difficulty);
+}
+
+/// Описание испытания на кнопке выбора режима
+pub fn daily_button_description(history: &DailyHistory) -> String {
+    let challenge = DailyChallenge::today();
+    match history.result(challenge.date) {
+        Some(score) => format!("{} — засчитано {}, тренировка", challenge.summary(), score),
+        None => challenge.summary(),
+    }
+}
+
+/// Дата, серия и лучший результат испытаний на экране Game Over
+pub fn spawn_daily_results(
+    parent: &mut ChildBuilder,
+    daily: &DailyRun,
+    history: &DailyHistory,
+    assets: &GameAssets,
+) {
+    let Some(active) = daily.active.as_ref() else {
+        return;
+    };
+    let date = active.challenge.date;
+
+    let attempt = match history.result(date) {
+        Some(scored) if active.last_scored => format!("Засчитано: {}", scored),
+        Some(scored) => format!("Тренировка · засчитано: {}", scored),
+        None => "Тренировка".to_string(),
+    };
+    let mut lines = vec![
+        format!("📅 Испытание {}", date.format("%d.%m.%Y")),
+        attempt,
+        format!("Серия: {} дн.", history.streak(date)),
+    ];
+    if let Some((best_date, best_score)) = history.best() {
+        lines.push(format!(
+            "Лучшее испытание: {} ({})",
+            best_score,
+            best_date.format("%d.%m.%Y")
+        ));
+    }
+
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        })
+        .with_children(|parent| {
+            for line in lines {
+                parent.spawn((
+                    Text::new(line),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                ));
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::{GameModeSelectionButton, RestartButton};
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder, TempSaveDir};
+    use crate::plugins::bird::Bird;
+    use crate::plugins::pipes::{OscillatingGap, PipeGap, TopPipe};
+    use std::collections::HashSet;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_challenge_depends_only_on_date() {
+        let day = date(2026, 10, 17);
+        assert_eq!(DailyChallenge::for_date(day), DailyChallenge::for_date(day));
+
+        let seeds: std::collections::HashSet<u64> = (0..30)
+            .map(|offset| DailyChallenge::for_date(day + chrono::Days::new(offset)).seed)
+            .collect();
+        assert_eq!(seeds.len(), 30);
+    }
+
+    #[test]
+    fn test_history_streak_and_best() {
+        let mut history = DailyHistory::default();
+        history.record(date(2026, 10, 12), 40);
+        history.record(date(2026, 10, 15), 7);
+        history.record(date(2026, 10, 16), 12);
+        history.record(date(2026, 10, 17), 9);
+
+        assert_eq!(history.streak(date(2026, 10, 17)), 3);
+        assert_eq!(history.streak(date(2026, 10, 18)), 0);
+        assert_eq!(history.best(), Some((date(2026, 10, 12), 40)));
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DailyHistory>(&json).unwrap(),
+            history
+        );
+    }
+
+    /// Центры просветов пар в порядке появления, пока счёт держится на `score`
+    fn daily_gaps(score: u32) -> Vec<f32> {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.click_button::<GameModeSelectionButton>();
+        assert!(game.step_until_state(GameState::GameModeSelection));
+        game.click_button::<DailyChallengeButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        game.step();
+        // Без птицы забег не кончается, а трубы продолжают появляться
+        let world = game.app.world_mut();
+        let birds: Vec<Entity> = world
+            .query_filtered::<Entity, With<Bird>>()
+            .iter(world)
+            .collect();
+        for bird in birds {
+            world.entity_mut(bird).despawn_recursive();
+        }
+
+        let mut seen = HashSet::new();
+        let mut gaps = Vec::new();
+        for _ in 0..900 {
+            game.resource_mut::<GameScore>().0 = score;
+            game.step();
+            let world = game.app.world_mut();
+            let mut pipes = world.query_filtered::<
+                (Entity, &Transform, &PipeGap, Option<&OscillatingGap>),
+                With<TopPipe>,
+            >();
+            let mut spawned: Vec<(f32, f32)> = pipes
+                .iter(world)
+                .filter(|(entity, ..)| seen.insert(*entity))
+                .map(|(_, transform, gap, oscillating)| {
+                    let center = oscillating.map_or(gap.center, |o| o.base_center);
+                    (transform.translation.x, center)
+                })
+                .collect();
+            spawned.sort_by(|a, b| a.0.total_cmp(&b.0));
+            gaps.extend(spawned.into_iter().map(|(_, center)| center));
+        }
+        gaps
+    }
+
+    #[test]
+    fn test_daily_layout_does_not_depend_on_score() {
+        // Высокий счёт открывает подвижные трубы и препятствия, но просветы те же
+        let low = daily_gaps(0);
+        let high = daily_gaps(40);
+        assert!(low.len() >= 8, "пар всего {}", low.len());
+        assert_eq!(low, high);
+    }
+
+    fn finish_run(game: &mut HeadlessGame, score: u32) {
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        game.step();
+        game.resource_mut::<GameScore>().0 = score;
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::GameOver);
+        assert!(game.step_until_state(GameState::GameOver));
+    }
+
+    #[test]
+    fn test_one_scored_attempt_then_practice() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let player_mode = game.resource::<GameModeSettings>().current_mode;
+        let player_difficulty = game.resource::<DifficultySettings>().current_level;
+        let challenge = DailyChallenge::today();
+
+        game.click_button::<GameModeSelectionButton>();
+        assert!(game.step_until_state(GameState::GameModeSelection));
+        game.click_button::<DailyChallengeButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        assert_eq!(game.resource::<GameRng>().seed(), challenge.seed);
+        assert_eq!(
+            game.resource::<GameModeSettings>().current_mode,
+            challenge.mode
+        );
+
+        finish_run(&mut game, 7);
+        assert_eq!(
+            game.resource::<DailyHistory>().result(challenge.date),
+            Some(7)
+        );
+
+        // Повторный забег того же дня — тренировка на той же трассе
+        game.click_button::<RestartButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        assert_eq!(game.resource::<GameRng>().seed(), challenge.seed);
+        finish_run(&mut game, 20);
+        assert_eq!(
+            game.resource::<DailyHistory>().result(challenge.date),
+            Some(7)
+        );
+
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::MainMenu);
+        assert!(game.step_until_state(GameState::MainMenu));
+        assert_eq!(
+            game.resource::<GameModeSettings>().current_mode,
+            player_mode
+        );
+        assert_eq!(
+            game.resource::<DifficultySettings>().current_level,
+            player_difficulty
+        );
+        assert!(game.resource::<DailyRun>().challenge().is_none());
+    }
+
+    #[test]
+    fn test_attempt_saved_before_run_ends() {
+        let temp = TempSaveDir::new("daily_test");
+        let save_dir = temp.save_dir();
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app.insert_resource(save_dir.clone());
+        let challenge = DailyChallenge::today();
+
+        game.click_button::<GameModeSelectionButton>();
+        assert!(game.step_until_state(GameState::GameModeSelection));
+        game.click_button::<DailyChallengeButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        game.step();
+
+        // Процесс могут убить прямо сейчас: попытка уже должна быть на диске
+        let saved = save::load::<DailyHistory>(&save_dir).unwrap();
+        assert_eq!(saved.result(challenge.date), Some(0));
+    }
+}
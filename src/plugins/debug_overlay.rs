@@ -0,0 +1,277 @@
+//! Отладочная панель разработчика
+//!
+//! F3 показывает поверх игры FPS, число сущностей по видам, параметры
+//! сложности, активные эффекты, таймеры режима, скорость птицы и seed забега.
+//! Панель собирается только с feature `dev`: в релизной сборке её нет.
+
+use crate::{
+    core::{
+        components::{ActiveShield, DoubleScore, PowerUp, SlowMotion},
+        difficulty_types::DifficultySettings,
+        resources::{ActivePowerUps, GameAssets, GameModeSettings, GameTimer, SurvivalLives},
+    },
+    plugins::bird::Bird,
+    plugins::coins::Coin,
+    plugins::effects::Particle,
+    plugins::obstacles::Obstacle,
+    plugins::pipes::Pipe,
+    plugins::rng::GameRng,
+    states::{app_state::AppState, game_state::GameState},
+};
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Клавиша показа панели
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+/// Показана ли панель
+#[derive(Resource, Default)]
+pub struct DebugOverlay {
+    pub visible: bool,
+}
+
+/// Корень панели
+#[derive(Component)]
+pub struct DebugOverlayRoot;
+
+#[derive(Component)]
+pub struct DebugOverlayText;
+
+pub fn debug_overlay_visible(overlay: Res<DebugOverlay>) -> bool {
+    overlay.visible
+}
+
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<DebugOverlay>()
+            .add_systems(Startup, spawn_debug_overlay)
+            .add_systems(
+                Update,
+                (
+                    toggle_debug_overlay,
+                    update_debug_overlay.run_if(debug_overlay_visible),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Число сущностей по видам
+#[derive(SystemParam)]
+pub struct EntityCounts<'w, 's> {
+    all: Query<'w, 's, ()>,
+    pipes: Query<'w, 's, (), With<Pipe>>,
+    particles: Query<'w, 's, (), With<Particle>>,
+    power_ups: Query<'w, 's, (), With<PowerUp>>,
+    obstacles: Query<'w, 's, (), With<Obstacle>>,
+    coins: Query<'w, 's, (), With<Coin>>,
+}
+
+impl EntityCounts<'_, '_> {
+    fn describe(&self) -> String {
+        format!(
+            "Сущности: {} | трубы {}, частицы {}, power-ups {}, препятствия {}, монеты {}",
+            self.all.iter().count(),
+            self.pipes.iter().count(),
+            self.particles.iter().count(),
+            self.power_ups.iter().count(),
+            self.obstacles.iter().count(),
+            self.coins.iter().count(),
+        )
+    }
+}
+
+fn spawn_debug_overlay(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                top: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            DebugOverlayRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 1.0, 0.6)),
+                DebugOverlayText,
+            ));
+        });
+}
+
+fn toggle_debug_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut root_query: Query<&mut Visibility, With<DebugOverlayRoot>>,
+) {
+    if !keys.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    overlay.visible = !overlay.visible;
+    for mut visibility in &mut root_query {
+        *visibility = if overlay.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_debug_overlay(
+    mut text_query: Query<&mut Text, With<DebugOverlayText>>,
+    diagnostics: Option<Res<DiagnosticsStore>>,
+    counts: EntityCounts,
+    app_state: Res<State<AppState>>,
+    game_state: Res<State<GameState>>,
+    difficulty: Res<DifficultySettings>,
+    active_effects: Res<ActivePowerUps>,
+    mode_settings: Res<GameModeSettings>,
+    game_timer: Res<GameTimer>,
+    survival_lives: Res<SurvivalLives>,
+    rng: Res<GameRng>,
+    bird_query: Query<(
+        &Bird,
+        Option<&ActiveShield>,
+        Option<&DoubleScore>,
+        Option<&SlowMotion>,
+    )>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let diagnostic = |path: &DiagnosticPath| {
+        diagnostics
+            .as_ref()
+            .and_then(|store| store.get(path))
+            .and_then(|diagnostic| diagnostic.smoothed())
+    };
+    let fps = diagnostic(&FrameTimeDiagnosticsPlugin::FPS)
+        .map_or_else(|| "—".to_string(), |fps| format!("{:.0}", fps));
+    let frame_time = diagnostic(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .map_or_else(|| "—".to_string(), |ms| format!("{:.2} мс", ms));
+
+    let params = &difficulty.current_params;
+    let bird = bird_query.get_single().ok();
+    let effect = |active: bool, timer: Option<&Timer>| match (active, timer) {
+        (true, Some(timer)) => format!("{:.1} с", timer.remaining_secs()),
+        (true, None) => "да".to_string(),
+        (false, _) => "нет".to_string(),
+    };
+
+    let lines = [
+        format!("FPS: {} | кадр: {}", fps, frame_time),
+        format!("app: {:?} | game: {:?}", app_state.get(), game_state.get()),
+        counts.describe(),
+        format!(
+            "Сложность {:?}: просвет {:.0}, скорость {:.0}, интервал {:.2} с, шаг {:.0}",
+            difficulty.current_level,
+            params.pipe_gap,
+            params.pipe_speed,
+            params.spawn_interval,
+            params.pipe_distance,
+        ),
+        format!(
+            "Эффекты: щит {}, x2 {}, замедление {}",
+            effect(
+                active_effects.shield_active,
+                bird.and_then(|(_, shield, ..)| shield).map(|s| &s.timer),
+            ),
+            effect(
+                active_effects.double_score_active,
+                bird.and_then(|(_, _, double, _)| double).map(|d| &d.timer),
+            ),
+            effect(
+                active_effects.slow_motion_active,
+                bird.and_then(|(.., slow)| slow).map(|s| &s.timer),
+            ),
+        ),
+        format!(
+            "Режим {:?}: таймер {:.1} с ({}), жизни {}/{}, множитель {:.2}",
+            mode_settings.current_mode,
+            game_timer.remaining_time,
+            if game_timer.is_active {
+                "идёт"
+            } else {
+                "стоит"
+            },
+            survival_lives.current_lives,
+            survival_lives.max_lives,
+            mode_settings.difficulty_multiplier,
+        ),
+        bird.map_or_else(
+            || "Птица: нет".to_string(),
+            |(bird, ..)| format!("Птица: скорость {:.1}", bird.velocity),
+        ),
+        format!("Seed: {}", rng.seed()),
+    ];
+    **text = lines.join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::HeadlessGameBuilder;
+
+    fn overlay_text(game: &mut crate::core::headless::HeadlessGame) -> String {
+        let world = game.app.world_mut();
+        world
+            .query_filtered::<&Text, With<DebugOverlayText>>()
+            .single(world)
+            .0
+            .clone()
+    }
+
+    fn overlay_visibility(game: &mut crate::core::headless::HeadlessGame) -> Visibility {
+        let world = game.app.world_mut();
+        *world
+            .query_filtered::<&Visibility, With<DebugOverlayRoot>>()
+            .single(world)
+    }
+
+    #[test]
+    fn test_overlay_hidden_until_f3() {
+        let mut game = HeadlessGameBuilder::default().build();
+        assert_eq!(overlay_visibility(&mut game), Visibility::Hidden);
+        assert!(overlay_text(&mut game).is_empty());
+
+        game.tap_key(TOGGLE_KEY);
+        assert_eq!(overlay_visibility(&mut game), Visibility::Inherited);
+        game.tap_key(TOGGLE_KEY);
+        assert_eq!(overlay_visibility(&mut game), Visibility::Hidden);
+    }
+
+    #[test]
+    fn test_overlay_shows_run_state() {
+        let mut game = HeadlessGameBuilder::default().with_seed(42).build();
+        game.start_run();
+        game.tap_key(TOGGLE_KEY);
+        game.step();
+
+        let text = overlay_text(&mut game);
+        assert!(text.contains("Seed: 42"), "{}", text);
+        assert!(text.contains("game: Playing"), "{}", text);
+        assert!(text.contains("Птица: скорость"), "{}", text);
+        let pipes = game.count::<Pipe>();
+        assert!(pipes > 0);
+        assert!(text.contains(&format!("трубы {}", pipes)), "{}", text);
+    }
+}
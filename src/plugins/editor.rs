@@ -0,0 +1,1026 @@
+//! Редактор трасс
+//!
+//! Трасса показывается лентой, которая прокручивается колесом мыши или стрелками.
+//! Левая кнопка мыши ставит пару труб на пустом месте и тянет трубы и power-ups,
+//! за края просвета меняется его высота; правая кнопка удаляет, клавиши 1–3
+//! ставят power-up под курсором. Тестовый забег начинается с текущей позиции
+//! ленты (вертикальная линия — место птицы) и после падения возвращает
+//! обратно в редактор. Сохраняется трасса в `assets/courses`.
+
+use crate::{
+    core::{
+        components::PowerUpType,
+        difficulty_types::DifficultySettings,
+        resources::{GameAssets, GameMode, GameModeSettings, PlayArea},
+        save::write_atomic,
+        utils::despawn_entities,
+    },
+    plugins::{
+        courses::{
+            serialize_course, Course, CoursePipe, CoursePowerUp, EditCourseButton, NewCourseButton,
+            SelectedCourse,
+        },
+        game_modes::apply_game_mode,
+        pipes::PIPE_WIDTH,
+        powerups::{power_up_color, POWERUP_SIZE},
+        settings_ui::spawn_menu_button,
+    },
+    states::game_state::GameState,
+};
+use bevy::asset::io::file::FileAssetReader;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, PositionType, UiRect, Val};
+use bevy::window::PrimaryWindow;
+use chrono::Local;
+use std::path::{Path, PathBuf};
+
+/// Минимальное расстояние между соседними парами труб
+pub const MIN_PIPE_DISTANCE: f32 = 100.0;
+/// Пределы высоты просвета
+const MIN_GAP: f32 = 80.0;
+const MAX_GAP: f32 = 400.0;
+/// Насколько далеко от центра может уйти просвет
+const GAP_Y_LIMIT: f32 = 250.0;
+/// Как близко к краю просвета нужно попасть, чтобы менять его высоту
+const EDGE_GRAB: f32 = 10.0;
+/// Скорость прокрутки ленты стрелками, пикселей в секунду
+const SCROLL_SPEED: f32 = 600.0;
+/// Пикселей прокрутки на одну строку колеса мыши
+const WHEEL_LINE: f32 = 40.0;
+/// Трубы ближе к птице в начале тестового забега отбрасываются
+const PLAYTEST_CLEARANCE: f32 = 150.0;
+
+/// Пара труб в редакторе: позиция считается от начала трассы
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorPipe {
+    pub x: f32,
+    pub gap_y: f32,
+    pub gap: Option<f32>,
+}
+
+/// Что находится под курсором
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTarget {
+    Pipe(usize),
+    GapTop(usize),
+    GapBottom(usize),
+    PowerUp(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    target: EditorTarget,
+    /// Смещение курсора относительно перетаскиваемого объекта
+    offset: Vec2,
+}
+
+/// Редактируемая трасса
+#[derive(Resource, Debug, Default)]
+pub struct CourseEditor {
+    pub name: String,
+    /// Отсортированы по `x`
+    pub pipes: Vec<EditorPipe>,
+    pub power_ups: Vec<CoursePowerUp>,
+    /// Путь файла внутри `assets`; `None` — трасса ещё не сохранялась
+    pub file: Option<String>,
+    /// Позиция ленты: расстояние от начала трассы до птицы
+    pub scroll: f32,
+    pub dirty: bool,
+    pub status: String,
+    /// Выход с несохранёнными изменениями ждёт повторного нажатия
+    confirm_leave: bool,
+    drag: Option<Drag>,
+    /// Держит только что сохранённую трассу в списке трасс
+    saved: Option<Handle<Course>>,
+}
+
+impl CourseEditor {
+    /// Открывает трассу; power-ups из просветов становятся отдельными
+    pub fn open(course: &Course, file: Option<String>) -> Self {
+        let mut power_ups = course.power_ups.clone();
+        let pipes = course
+            .pipes
+            .iter()
+            .zip(course.pipe_positions())
+            .map(|(pipe, x)| {
+                if let Some(kind) = pipe.power_up {
+                    power_ups.push(CoursePowerUp {
+                        x,
+                        y: pipe.gap_y,
+                        kind,
+                    });
+                }
+                EditorPipe {
+                    x,
+                    gap_y: pipe.gap_y,
+                    gap: pipe.gap,
+                }
+            })
+            .collect();
+
+        Self {
+            name: course.name.clone(),
+            pipes,
+            power_ups,
+            file,
+            ..default()
+        }
+    }
+
+    /// Новая трасса из трёх ровных пар труб
+    pub fn new_course() -> Self {
+        Self {
+            name: format!("Трасса {}", Local::now().format("%d.%m %H:%M")),
+            pipes: (1..=3)
+                .map(|i| EditorPipe {
+                    x: 300.0 * i as f32,
+                    gap_y: 0.0,
+                    gap: None,
+                })
+                .collect(),
+            dirty: true,
+            ..default()
+        }
+    }
+
+    pub fn to_course(&self) -> Course {
+        let mut previous = 0.0;
+        let pipes = self
+            .pipes
+            .iter()
+            .map(|pipe| {
+                let spacing = pipe.x - previous;
+                previous = pipe.x;
+                CoursePipe {
+                    spacing,
+                    gap_y: pipe.gap_y,
+                    gap: pipe.gap,
+                    power_up: None,
+                }
+            })
+            .collect();
+
+        Course {
+            name: self.name.clone(),
+            pipes,
+            power_ups: self.power_ups.clone(),
+        }
+    }
+
+    /// Трасса для тестового забега: птица стоит в точке `from`
+    pub fn playtest_course(&self, from: f32) -> Result<Course, String> {
+        let shifted = CourseEditor {
+            name: self.name.clone(),
+            pipes: self
+                .pipes
+                .iter()
+                .filter(|pipe| pipe.x - from >= PLAYTEST_CLEARANCE)
+                .map(|pipe| EditorPipe {
+                    x: pipe.x - from,
+                    ..pipe.clone()
+                })
+                .collect(),
+            power_ups: self
+                .power_ups
+                .iter()
+                .filter(|power_up| power_up.x > from)
+                .map(|power_up| CoursePowerUp {
+                    x: power_up.x - from,
+                    ..power_up.clone()
+                })
+                .collect(),
+            ..default()
+        };
+        if shifted.pipes.is_empty() {
+            return Err("Впереди нет труб".to_string());
+        }
+        let course = shifted.to_course();
+        course.validate().map_err(|error| error.to_string())?;
+        Ok(course)
+    }
+
+    /// Ищет трубу, край просвета или power-up в точке ленты
+    pub fn target_at(&self, point: Vec2, default_gap: f32) -> Option<EditorTarget> {
+        let power_up = self.power_ups.iter().position(|power_up| {
+            point.distance(Vec2::new(power_up.x, power_up.y)) <= POWERUP_SIZE / 2.0
+        });
+        if let Some(index) = power_up {
+            return Some(EditorTarget::PowerUp(index));
+        }
+
+        let index = self
+            .pipes
+            .iter()
+            .position(|pipe| (point.x - pipe.x).abs() <= PIPE_WIDTH / 2.0)?;
+        let pipe = &self.pipes[index];
+        let half_gap = pipe.gap.unwrap_or(default_gap) / 2.0;
+        if (point.y - (pipe.gap_y + half_gap)).abs() <= EDGE_GRAB {
+            Some(EditorTarget::GapTop(index))
+        } else if (point.y - (pipe.gap_y - half_gap)).abs() <= EDGE_GRAB {
+            Some(EditorTarget::GapBottom(index))
+        } else {
+            Some(EditorTarget::Pipe(index))
+        }
+    }
+
+    /// Ставит пару труб, если рядом есть место
+    pub fn add_pipe(&mut self, point: Vec2) -> Option<usize> {
+        let index = self.pipes.partition_point(|pipe| pipe.x < point.x);
+        let previous_x = index
+            .checked_sub(1)
+            .map_or(0.0, |previous| self.pipes[previous].x);
+        let fits_before = point.x - previous_x >= MIN_PIPE_DISTANCE;
+        let fits_after = self
+            .pipes
+            .get(index)
+            .is_none_or(|next| next.x - point.x >= MIN_PIPE_DISTANCE);
+        if !fits_before || !fits_after {
+            return None;
+        }
+
+        self.pipes.insert(
+            index,
+            EditorPipe {
+                x: point.x,
+                gap_y: point.y.clamp(-GAP_Y_LIMIT, GAP_Y_LIMIT),
+                gap: None,
+            },
+        );
+        self.dirty = true;
+        Some(index)
+    }
+
+    /// Двигает пару труб, не давая ей обогнать соседей
+    pub fn move_pipe(&mut self, index: usize, position: Vec2) {
+        let lower = index
+            .checked_sub(1)
+            .map_or(0.0, |previous| self.pipes[previous].x)
+            + MIN_PIPE_DISTANCE;
+        let upper = self
+            .pipes
+            .get(index + 1)
+            .map_or(f32::INFINITY, |next| next.x - MIN_PIPE_DISTANCE);
+
+        let pipe = &mut self.pipes[index];
+        pipe.x = position.x.clamp(lower, upper.max(lower));
+        pipe.gap_y = position.y.clamp(-GAP_Y_LIMIT, GAP_Y_LIMIT);
+        self.dirty = true;
+    }
+
+    /// Меняет высоту просвета так, чтобы его край оказался на высоте `edge_y`
+    pub fn resize_gap(&mut self, index: usize, edge_y: f32) {
+        let pipe = &mut self.pipes[index];
+        pipe.gap = Some((2.0 * (edge_y - pipe.gap_y).abs()).clamp(MIN_GAP, MAX_GAP));
+        self.dirty = true;
+    }
+
+    pub fn add_power_up(&mut self, kind: PowerUpType, point: Vec2) {
+        if point.x > 0.0 {
+            self.power_ups.push(CoursePowerUp {
+                x: point.x,
+                y: point.y,
+                kind,
+            });
+            self.dirty = true;
+        }
+    }
+
+    pub fn remove(&mut self, target: EditorTarget) {
+        match target {
+            EditorTarget::PowerUp(index) => {
+                self.power_ups.remove(index);
+            }
+            EditorTarget::Pipe(index)
+            | EditorTarget::GapTop(index)
+            | EditorTarget::GapBottom(index) => {
+                self.pipes.remove(index);
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Нажатие левой кнопки: хватает объект под курсором или ставит новую пару труб
+    fn begin_drag(&mut self, point: Vec2, default_gap: f32) {
+        let target = match self.target_at(point, default_gap) {
+            Some(target) => target,
+            None => match self.add_pipe(point) {
+                Some(index) => EditorTarget::Pipe(index),
+                None => {
+                    self.status = "Слишком близко к соседней трубе".to_string();
+                    return;
+                }
+            },
+        };
+        let anchor = match target {
+            EditorTarget::Pipe(index) => Vec2::new(self.pipes[index].x, self.pipes[index].gap_y),
+            EditorTarget::PowerUp(index) => {
+                Vec2::new(self.power_ups[index].x, self.power_ups[index].y)
+            }
+            EditorTarget::GapTop(_) | EditorTarget::GapBottom(_) => point,
+        };
+        self.drag = Some(Drag {
+            target,
+            offset: point - anchor,
+        });
+    }
+
+    fn drag_to(&mut self, point: Vec2) {
+        let Some(drag) = self.drag else {
+            return;
+        };
+        let position = point - drag.offset;
+        match drag.target {
+            EditorTarget::Pipe(index) => self.move_pipe(index, position),
+            EditorTarget::GapTop(index) | EditorTarget::GapBottom(index) => {
+                self.resize_gap(index, point.y)
+            }
+            EditorTarget::PowerUp(index) => {
+                let power_up = &mut self.power_ups[index];
+                power_up.x = position.x.max(1.0);
+                power_up.y = position.y;
+                self.dirty = true;
+            }
+        }
+    }
+}
+
+/// Папка ассетов, куда сохраняются трассы
+#[derive(Resource)]
+pub struct CourseFolder(pub PathBuf);
+
+impl Default for CourseFolder {
+    fn default() -> Self {
+        Self(FileAssetReader::get_base_path().join("assets"))
+    }
+}
+
+/// Записывает трассу в файл; возвращает путь ассета
+pub fn save_course_file(folder: &Path, editor: &CourseEditor) -> Result<String, String> {
+    let course = editor.to_course();
+    course.validate().map_err(|error| error.to_string())?;
+
+    let file = editor.file.clone().unwrap_or_else(|| {
+        format!(
+            "courses/course-{}.course.ron",
+            Local::now().format("%Y%m%d-%H%M%S")
+        )
+    });
+    let content = serialize_course(&course, file.ends_with(".json"))?;
+    write_atomic(&folder.join(&file), content.as_bytes())?;
+    Ok(file)
+}
+
+/// Тестовый забег из редактора
+#[derive(Resource, Default)]
+pub struct EditorPlaytest {
+    active: Option<ActivePlaytest>,
+}
+
+struct ActivePlaytest {
+    restore_mode: GameModeSettings,
+    restore_selected: Option<Handle<Course>>,
+}
+
+/// Идёт тестовый забег: рекорды, статистика и достижения не обновляются
+pub fn is_editor_playtest(playtest: Res<EditorPlaytest>) -> bool {
+    playtest.active.is_some()
+}
+
+/// Команда редактора с кнопки или клавиатуры
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorAction {
+    Playtest,
+    Save,
+}
+
+#[derive(Component)]
+pub struct OnEditorScreen;
+
+/// Спрайты ленты, пересоздаются при каждом изменении трассы
+#[derive(Component)]
+struct EditorVisual;
+
+#[derive(Component)]
+struct EditorTitleText;
+
+#[derive(Component)]
+struct EditorStatusText;
+
+#[derive(Component)]
+pub struct EditorPlaytestButton;
+
+#[derive(Component)]
+pub struct EditorSaveButton;
+
+#[derive(Component)]
+pub struct EditorBackButton;
+
+/// Плагин редактора трасс
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CourseEditor>()
+            .init_resource::<EditorPlaytest>()
+            .init_resource::<CourseFolder>()
+            .add_event::<EditorAction>()
+            .add_systems(
+                Update,
+                open_editor.run_if(in_state(GameState::CourseSelection)),
+            )
+            .add_systems(
+                OnEnter(GameState::Editor),
+                (
+                    finish_playtest,
+                    spawn_editor_ui,
+                    redraw_timeline,
+                    update_editor_text,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        scroll_timeline,
+                        edit_with_mouse,
+                        edit_with_keys,
+                        handle_editor_buttons,
+                    ),
+                    run_editor_actions,
+                    (redraw_timeline, update_editor_text).run_if(resource_changed::<CourseEditor>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Editor)),
+            )
+            .add_systems(
+                OnExit(GameState::Editor),
+                (
+                    despawn_entities::<OnEditorScreen>,
+                    despawn_entities::<EditorVisual>,
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                return_to_editor.run_if(is_editor_playtest),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), finish_playtest);
+    }
+}
+
+fn open_editor(
+    edit_query: Query<(&Interaction, &EditCourseButton), Changed<Interaction>>,
+    new_query: Query<&Interaction, (Changed<Interaction>, With<NewCourseButton>)>,
+    courses: Res<Assets<Course>>,
+    asset_server: Res<AssetServer>,
+    mut editor: ResMut<CourseEditor>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, EditCourseButton(id)) in &edit_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(course) = courses.get(*id) {
+            let file = asset_server
+                .get_path(*id)
+                .map(|path| path.path().to_string_lossy().replace('\\', "/"));
+            *editor = CourseEditor::open(course, file);
+            next_state.set(GameState::Editor);
+            return;
+        }
+    }
+
+    if new_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        *editor = CourseEditor::new_course();
+        next_state.set(GameState::Editor);
+    }
+}
+
+fn spawn_editor_ui(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            OnEditorScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 28.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        EditorTitleText,
+                    ));
+                    header.spawn((
+                        Text::new(
+                            "ЛКМ — поставить/тянуть, край просвета — высота, ПКМ — удалить\n\
+                             1/2/3 — щит/x2/замедление, ←/→ или колесо — прокрутка",
+                        ),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                        TextLayout::new_with_justify(JustifyText::Center),
+                    ));
+                    header.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                        EditorStatusText,
+                    ));
+                });
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|buttons| {
+                    spawn_menu_button(buttons, &assets, "▶ Отсюда (P)", EditorPlaytestButton);
+                    spawn_menu_button(buttons, &assets, "💾 Сохранить (S)", EditorSaveButton);
+                    spawn_menu_button(buttons, &assets, "🔙 К трассам", EditorBackButton);
+                });
+        });
+}
+
+fn scroll_timeline(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut wheel: EventReader<MouseWheel>,
+    mut editor: ResMut<CourseEditor>,
+) {
+    let mut delta = 0.0;
+    if keys.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        delta += SCROLL_SPEED * time.delta_secs();
+    }
+    if keys.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        delta -= SCROLL_SPEED * time.delta_secs();
+    }
+    for event in wheel.read() {
+        let amount = match event.unit {
+            MouseScrollUnit::Line => event.y * WHEEL_LINE,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        delta -= amount;
+    }
+
+    if keys.just_pressed(KeyCode::Home) {
+        editor.scroll = 0.0;
+    } else if delta != 0.0 {
+        editor.scroll = (editor.scroll + delta).max(0.0);
+    }
+}
+
+/// Положение курсора в координатах мира
+fn cursor_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let cursor = windows.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = cameras.get_single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+}
+
+fn edit_with_mouse(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    buttons: Query<&Interaction, With<Button>>,
+    difficulty: Res<DifficultySettings>,
+    mut editor: ResMut<CourseEditor>,
+) {
+    if mouse.just_released(MouseButton::Left) && editor.drag.is_some() {
+        editor.drag = None;
+    }
+    let Some(cursor) = cursor_position(&windows, &cameras) else {
+        return;
+    };
+    let point = Vec2::new(cursor.x + editor.scroll, cursor.y);
+    let default_gap = difficulty.current_params.pipe_gap;
+    // Клики по кнопкам редактора не должны ставить трубы
+    let over_ui = buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+
+    if mouse.just_pressed(MouseButton::Left) && !over_ui {
+        editor.begin_drag(point, default_gap);
+    } else if mouse.pressed(MouseButton::Left) && editor.drag.is_some() {
+        editor.drag_to(point);
+    }
+
+    if mouse.just_pressed(MouseButton::Right) && !over_ui {
+        if let Some(target) = editor.target_at(point, default_gap) {
+            editor.remove(target);
+        }
+    }
+}
+
+fn edit_with_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut editor: ResMut<CourseEditor>,
+    mut actions: EventWriter<EditorAction>,
+) {
+    if keys.just_pressed(KeyCode::KeyP) {
+        actions.send(EditorAction::Playtest);
+    }
+    if keys.just_pressed(KeyCode::KeyS) {
+        actions.send(EditorAction::Save);
+    }
+
+    let kind = if keys.just_pressed(KeyCode::Digit1) {
+        PowerUpType::Shield
+    } else if keys.just_pressed(KeyCode::Digit2) {
+        PowerUpType::DoubleScore
+    } else if keys.just_pressed(KeyCode::Digit3) {
+        PowerUpType::SlowMotion
+    } else {
+        return;
+    };
+    if let Some(cursor) = cursor_position(&windows, &cameras) {
+        let point = Vec2::new(cursor.x + editor.scroll, cursor.y);
+        editor.add_power_up(kind, point);
+    }
+}
+
+fn handle_editor_buttons(
+    playtest_query: Query<&Interaction, (Changed<Interaction>, With<EditorPlaytestButton>)>,
+    save_query: Query<&Interaction, (Changed<Interaction>, With<EditorSaveButton>)>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<EditorBackButton>)>,
+    mut editor: ResMut<CourseEditor>,
+    mut actions: EventWriter<EditorAction>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+
+    if playtest_query.iter().any(pressed) {
+        actions.send(EditorAction::Playtest);
+    }
+    if save_query.iter().any(pressed) {
+        actions.send(EditorAction::Save);
+    }
+    if back_query.iter().any(pressed) {
+        if editor.dirty && !editor.confirm_leave {
+            editor.confirm_leave = true;
+            editor.status =
+                "Изменения не сохранены: S — сохранить, «К трассам» ещё раз — выйти без сохранения"
+                    .to_string();
+        } else {
+            next_state.set(GameState::CourseSelection);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_editor_actions(
+    mut actions: EventReader<EditorAction>,
+    folder: Res<CourseFolder>,
+    asset_server: Res<AssetServer>,
+    mut courses: ResMut<Assets<Course>>,
+    mut editor: ResMut<CourseEditor>,
+    mut playtest: ResMut<EditorPlaytest>,
+    mut selected: ResMut<SelectedCourse>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for action in actions.read() {
+        editor.confirm_leave = false;
+        match action {
+            EditorAction::Save => match save_course_file(&folder.0, &editor) {
+                Ok(file) => {
+                    // Перечитываем трассу, чтобы список трасс увидел изменения
+                    if asset_server.get_handle::<Course>(&file).is_some() {
+                        asset_server.reload(file.clone());
+                    } else {
+                        editor.saved = Some(asset_server.load(file.clone()));
+                    }
+                    editor.status = format!("Сохранено: assets/{}", file);
+                    editor.file = Some(file);
+                    editor.dirty = false;
+                }
+                Err(error) => editor.status = format!("Не удалось сохранить: {}", error),
+            },
+            EditorAction::Playtest => match editor.playtest_course(editor.scroll) {
+                Ok(course) => {
+                    let handle = courses.add(course);
+                    playtest.active = Some(ActivePlaytest {
+                        restore_mode: mode_settings.clone(),
+                        restore_selected: selected.0.replace(handle),
+                    });
+                    apply_game_mode(&mut mode_settings, GameMode::Course);
+                    next_state.set(GameState::PreGame);
+                    return;
+                }
+                Err(error) => editor.status = error,
+            },
+        }
+    }
+}
+
+/// Рисует трубы, просветы и power-ups видимого участка ленты
+fn redraw_timeline(
+    mut commands: Commands,
+    editor: Res<CourseEditor>,
+    assets: Res<GameAssets>,
+    play_area: Res<PlayArea>,
+    difficulty: Res<DifficultySettings>,
+    visuals: Query<Entity, With<EditorVisual>>,
+) {
+    for entity in &visuals {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let height = play_area.height;
+    let default_gap = difficulty.current_params.pipe_gap;
+    let dragged = editor.drag.map(|drag| drag.target);
+    let visible = |x: f32| (x - editor.scroll).abs() <= play_area.width / 2.0 + PIPE_WIDTH;
+
+    // Линия птицы: отсюда начнётся тестовый забег
+    commands.spawn((
+        Sprite::from_color(Color::srgba(0.3, 1.0, 0.3, 0.5), Vec2::new(2.0, height)),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+        EditorVisual,
+    ));
+    // Начало трассы
+    if visible(0.0) {
+        commands.spawn((
+            Sprite::from_color(Color::srgba(1.0, 0.3, 0.3, 0.5), Vec2::new(2.0, height)),
+            Transform::from_xyz(-editor.scroll, 0.0, 1.0),
+            EditorVisual,
+        ));
+    }
+
+    for (index, pipe) in editor.pipes.iter().enumerate() {
+        if !visible(pipe.x) {
+            continue;
+        }
+        let x = pipe.x - editor.scroll;
+        let gap = pipe.gap.unwrap_or(default_gap);
+        let tint = if dragged == Some(EditorTarget::Pipe(index)) {
+            Color::srgb(1.0, 1.0, 0.6)
+        } else {
+            Color::WHITE
+        };
+
+        for (sign, flip) in [(1.0, -1.0), (-1.0, 1.0)] {
+            commands.spawn((
+                Sprite {
+                    image: assets.pipe_texture.clone(),
+                    color: tint,
+                    custom_size: Some(Vec2::new(PIPE_WIDTH, height)),
+                    ..default()
+                },
+                Transform {
+                    translation: Vec3::new(x, pipe.gap_y + sign * (gap + height) / 2.0, 2.0),
+                    scale: Vec3::new(1.0, flip, 1.0),
+                    ..default()
+                },
+                EditorVisual,
+            ));
+            // Край просвета, за который меняется его высота
+            commands.spawn((
+                Sprite::from_color(Color::srgba(1.0, 1.0, 1.0, 0.7), Vec2::new(PIPE_WIDTH, 4.0)),
+                Transform::from_xyz(x, pipe.gap_y + sign * gap / 2.0, 3.0),
+                EditorVisual,
+            ));
+        }
+    }
+
+    for power_up in &editor.power_ups {
+        if !visible(power_up.x) {
+            continue;
+        }
+        commands.spawn((
+            Sprite::from_color(power_up_color(power_up.kind), Vec2::splat(POWERUP_SIZE)),
+            Transform::from_xyz(power_up.x - editor.scroll, power_up.y, 4.0),
+            EditorVisual,
+        ));
+    }
+}
+
+fn update_editor_text(
+    editor: Res<CourseEditor>,
+    mut title_query: Query<&mut Text, (With<EditorTitleText>, Without<EditorStatusText>)>,
+    mut status_query: Query<&mut Text, (With<EditorStatusText>, Without<EditorTitleText>)>,
+) {
+    let title = format!(
+        "✏ {}{} · {} труб · x = {:.0}",
+        editor.name,
+        if editor.dirty { " *" } else { "" },
+        editor.pipes.len(),
+        editor.scroll
+    );
+    for mut text in &mut title_query {
+        **text = title.clone();
+    }
+    for mut text in &mut status_query {
+        **text = editor.status.clone();
+    }
+}
+
+/// Тестовый забег закончился — сразу назад в редактор
+fn return_to_editor(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Editor);
+}
+
+/// Возвращает режим и выбранную трассу, которые были до тестового забега
+fn finish_playtest(
+    mut playtest: ResMut<EditorPlaytest>,
+    mut selected: ResMut<SelectedCourse>,
+    mut mode_settings: ResMut<GameModeSettings>,
+) {
+    if let Some(active) = playtest.active.take() {
+        *mode_settings = active.restore_mode;
+        selected.0 = active.restore_selected;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::{CoursesButton, GameModeSelectionButton, PowerUp};
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder, TempSaveDir};
+    use crate::core::resources::HighScores;
+    use crate::plugins::courses::parse_course;
+    use crate::plugins::pipes::Pipe;
+    use crate::plugins::replay::ReplayRecorder;
+
+    fn editor_with_pipes(xs: &[f32]) -> CourseEditor {
+        CourseEditor {
+            name: "Тест".to_string(),
+            pipes: xs
+                .iter()
+                .map(|&x| EditorPipe {
+                    x,
+                    gap_y: 0.0,
+                    gap: None,
+                })
+                .collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_open_and_save_keep_layout() {
+        let course = parse_course(
+            br#"(name: "Test", pipes: [
+                (spacing: 300, gap_y: 10),
+                (spacing: 200, gap_y: -20, gap: 180, power_up: Shield),
+            ])"#,
+            false,
+        )
+        .unwrap();
+        let editor = CourseEditor::open(&course, None);
+
+        assert_eq!(editor.pipes[1].x, 500.0);
+        assert_eq!(editor.power_ups.len(), 1);
+        assert_eq!(
+            (editor.power_ups[0].x, editor.power_ups[0].y),
+            (500.0, -20.0)
+        );
+
+        let saved = editor.to_course();
+        assert_eq!(saved.pipe_positions(), vec![300.0, 500.0]);
+        assert_eq!(saved.pipes[1].gap, Some(180.0));
+        assert_eq!(saved.pipes[1].power_up, None);
+        assert_eq!(saved.power_ups, editor.power_ups);
+    }
+
+    #[test]
+    fn test_drag_keeps_pipes_in_order_and_resizes_gap() {
+        let mut editor = editor_with_pipes(&[300.0, 600.0]);
+
+        // Новая пара встаёт между существующими, но не вплотную к ним
+        assert_eq!(editor.add_pipe(Vec2::new(450.0, 30.0)), Some(1));
+        assert_eq!(editor.add_pipe(Vec2::new(520.0, 0.0)), None);
+
+        // Перетаскивание не даёт обогнать соседа
+        editor.begin_drag(Vec2::new(455.0, 30.0), 200.0);
+        editor.drag_to(Vec2::new(900.0, 60.0));
+        assert_eq!(editor.pipes[1].x, 600.0 - MIN_PIPE_DISTANCE);
+        assert_eq!(editor.pipes[1].gap_y, 60.0);
+
+        // Край просвета меняет его высоту
+        assert_eq!(
+            editor.target_at(Vec2::new(300.0, 100.0), 200.0),
+            Some(EditorTarget::GapTop(0))
+        );
+        editor.begin_drag(Vec2::new(300.0, 100.0), 200.0);
+        editor.drag_to(Vec2::new(300.0, 75.0));
+        assert_eq!(editor.pipes[0].gap, Some(150.0));
+    }
+
+    #[test]
+    fn test_playtest_starts_from_scroll_position() {
+        let mut editor = editor_with_pipes(&[300.0, 600.0, 900.0]);
+        editor.add_power_up(PowerUpType::DoubleScore, Vec2::new(700.0, 0.0));
+
+        let course = editor.playtest_course(500.0).unwrap();
+        assert_eq!(course.pipe_positions(), vec![400.0]);
+        assert_eq!(course.power_ups[0].x, 200.0);
+
+        assert!(editor.playtest_course(1000.0).is_err());
+    }
+
+    fn open_new_course(game: &mut HeadlessGame) {
+        game.click_button::<GameModeSelectionButton>();
+        assert!(game.step_until_state(GameState::GameModeSelection));
+        game.click_button::<CoursesButton>();
+        assert!(game.step_until_state(GameState::CourseSelection));
+        game.click_button::<NewCourseButton>();
+        assert!(game.step_until_state(GameState::Editor));
+    }
+
+    #[test]
+    fn test_playtest_returns_to_editor() {
+        let mut game = HeadlessGameBuilder::default().build();
+        open_new_course(&mut game);
+        game.resource_mut::<CourseEditor>()
+            .add_power_up(PowerUpType::Shield, Vec2::new(300.0, 50.0));
+
+        game.click_button::<EditorPlaytestButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        assert_eq!(game.count::<Pipe>(), 6);
+        assert_eq!(game.count::<PowerUp>(), 1);
+
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        assert!(game.step_until_state(GameState::Playing));
+        // Без прыжков птица падает и забег заканчивается
+        game.step_frames(60);
+
+        assert!(game.step_until_state(GameState::Editor));
+        assert_eq!(
+            game.resource::<GameModeSettings>().current_mode,
+            GameMode::Classic
+        );
+        assert!(game.resource::<SelectedCourse>().0.is_none());
+        assert!(game.resource::<HighScores>().scores.is_empty());
+        assert!(game.resource::<ReplayRecorder>().last_replay.is_none());
+        assert_eq!(game.resource::<CourseEditor>().pipes.len(), 3);
+    }
+
+    #[test]
+    fn test_leaving_dirty_editor_needs_second_press() {
+        let mut game = HeadlessGameBuilder::default().build();
+        open_new_course(&mut game);
+        assert!(game.resource::<CourseEditor>().dirty);
+
+        game.click_button::<EditorBackButton>();
+        game.step();
+        assert_eq!(game.game_state(), GameState::Editor);
+        assert!(game
+            .resource::<CourseEditor>()
+            .status
+            .contains("не сохранены"));
+
+        game.click_button::<EditorBackButton>();
+        assert!(game.step_until_state(GameState::CourseSelection));
+    }
+
+    #[test]
+    fn test_save_writes_loadable_course() {
+        let temp = TempSaveDir::new("editor_save");
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app.insert_resource(CourseFolder(temp.path().to_path_buf()));
+        open_new_course(&mut game);
+        game.resource_mut::<CourseEditor>().file = Some("courses/test.course.ron".to_string());
+
+        game.click_button::<EditorSaveButton>();
+
+        let content = std::fs::read(temp.path().join("courses/test.course.ron")).unwrap();
+        let course = parse_course(&content, false).unwrap();
+        assert_eq!(course, game.resource::<CourseEditor>().to_course());
+        assert!(!game.resource::<CourseEditor>().dirty);
+    }
+}
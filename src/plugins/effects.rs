@@ -1,5 +1,6 @@
 use crate::core::{resources::ActivePowerUps, utils::despawn_entities};
 use crate::plugins::audio::{CollisionEvent, ScoreEvent};
+use crate::plugins::rng::GameRng;
 use crate::states::{
     app_state::AppState,
     game_state::{EffectsSet, GameState},
@@ -93,10 +94,12 @@ fn setup_camera(mut commands: Commands, query: Query<Entity, With<Camera2d>>) {
 fn spawn_collision_particles(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     for _event in collision_events.read() {
         spawn_particle_explosion(
             &mut commands,
+            &mut rng,
             Vec3::new(0.0, 0.0, 1.0),   // Центр экрана
             Color::srgb(1.0, 0.5, 0.0), // Оранжевый цвет для столкновения
             200,                        // Количество частиц
@@ -107,10 +110,15 @@ fn spawn_collision_particles(
 }
 
 /// Создание частиц при получении очков
-fn spawn_score_particles(mut commands: Commands, mut score_events: EventReader<ScoreEvent>) {
+fn spawn_score_particles(
+    mut commands: Commands,
+    mut score_events: EventReader<ScoreEvent>,
+    mut rng: ResMut<GameRng>,
+) {
     for _event in score_events.read() {
         spawn_particle_explosion(
             &mut commands,
+            &mut rng,
             Vec3::new(0.0, 100.0, 1.0), // Центр экрана
             Color::srgb(0.0, 1.0, 0.5), // Зелёный цвет для очков
             250,
@@ -153,8 +161,9 @@ fn spawn_score_floating_text(
 }
 
 /// Вспомогательная функция для создания взрыва частиц
-fn spawn_particle_explosion(
+pub(crate) fn spawn_particle_explosion(
     commands: &mut Commands,
+    rng: &mut GameRng,
     position: Vec3,
     color: Color,
     count: u32,
@@ -162,7 +171,7 @@ fn spawn_particle_explosion(
     base_speed: f32,
 ) {
     for _ in 0..count {
-        let mut rng = rand::rng();
+        let rng = rng.cosmetic();
 
         // Случайное направление и скорость
         let angle: f32 = rng.random_range(0.0..2.0 * std::f32::consts::PI);
@@ -263,6 +272,7 @@ fn cleanup_particles(mut commands: Commands, query: Query<Entity, With<Particle>
 fn apply_screen_shake(
     time: Res<Time>,
     mut shake: ResMut<ScreenShake>,
+    mut rng: ResMut<GameRng>,
     mut camera_query: Query<&mut Transform, With<MainCamera>>,
 ) {
     if let Ok(mut camera_transform) = camera_query.get_single_mut() {
@@ -276,7 +286,7 @@ fn apply_screen_shake(
                 camera_transform.translation.y = 0.0;
             } else {
                 // Применяем случайное смещение
-                let mut rng = rand::rng();
+                let rng = rng.cosmetic();
                 let shake_x = rng.random_range(-shake.intensity..=shake.intensity);
                 let shake_y = rng.random_range(-shake.intensity..=shake.intensity);
 
@@ -5,17 +5,19 @@ use bevy::ui::{
 };
 
 use crate::core::components::{
-    ClassicModeButton, GameModeButton, GameModeInfoText, GameModeTimerText, MenuButton,
-    OnGameModeSelectionScreen, OnGameModeUI, SurvivalModeButton, TimeAttackModeButton,
-    ZenModeButton,
+    ClassicModeButton, CoursesButton, DailyChallengeButton, GameModeButton, GameModeInfoText,
+    GameModeTimerText, MenuButton, OnGameModeSelectionScreen, OnGameModeUI, SurvivalModeButton,
+    TimeAttackModeButton, ZenModeButton,
 };
 use crate::core::resources::{
-    GameAssets, GameMode, GameModeSettings, GameScore, GameTimer, PipeSpawner, SurvivalLives,
+    GameAssets, GameMode, GameModeSettings, GameScore, GameTimer, PipeSpawner, StartStyle,
+    SurvivalLives,
 };
 use crate::core::utils::despawn_entities;
 use crate::plugins::audio::CollisionEvent;
+use crate::plugins::daily::{daily_button_description, DailyHistory};
 use crate::states::app_state::AppState;
-use crate::states::game_state::GameState;
+use crate::states::game_state::{GameState, SimulationSet};
 
 pub struct GameModesPlugin;
 
@@ -37,14 +39,15 @@ impl Plugin for GameModesPlugin {
                 handle_game_mode_selection
                     .run_if(in_state(GameState::GameModeSelection).and(in_state(AppState::Loaded))),
             )
+            .add_systems(
+                FixedUpdate,
+                (check_time_attack_victory, check_survival_lives)
+                    .in_set(SimulationSet::Timers)
+                    .run_if(in_state(GameState::Playing)),
+            )
             .add_systems(
                 Update,
-                (
-                    check_time_attack_victory,
-                    check_zen_mode_scoring,
-                    check_survival_lives,
-                    apply_mode_difficulty,
-                )
+                (check_zen_mode_scoring, apply_mode_difficulty)
                     .run_if(in_state(GameState::Playing)),
             )
             .add_systems(OnEnter(GameState::PreGame), setup_game_mode)
@@ -60,7 +63,11 @@ impl Plugin for GameModesPlugin {
     }
 }
 
-fn spawn_game_mode_selection(mut commands: Commands, assets: Res<GameAssets>) {
+fn spawn_game_mode_selection(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    daily_history: Res<DailyHistory>,
+) {
     commands
         .spawn((
             Node {
@@ -79,12 +86,12 @@ fn spawn_game_mode_selection(mut commands: Commands, assets: Res<GameAssets>) {
                 Text::new("Выберите режим игры"),
                 TextFont {
                     font: assets.font.clone(),
-                    font_size: 60.0,
+                    font_size: 48.0,
                     ..default()
                 },
                 TextColor(Color::WHITE),
                 Node {
-                    margin: UiRect::bottom(Val::Px(50.0)),
+                    margin: UiRect::bottom(Val::Px(20.0)),
                     ..default()
                 },
             ));
@@ -125,6 +132,24 @@ fn spawn_game_mode_selection(mut commands: Commands, assets: Res<GameAssets>) {
                 SurvivalModeButton,
             );
 
+            // Кнопка испытания дня
+            spawn_mode_button(
+                parent,
+                "📅 Испытание дня",
+                &daily_button_description(&daily_history),
+                &assets,
+                DailyChallengeButton,
+            );
+
+            // Кнопка трасс из файлов
+            spawn_mode_button(
+                parent,
+                "🏁 Трассы",
+                "Готовые трассы из папки assets/courses",
+                &assets,
+                CoursesButton,
+            );
+
             // Кнопка назад
             parent
                 .spawn((
@@ -134,7 +159,7 @@ fn spawn_game_mode_selection(mut commands: Commands, assets: Res<GameAssets>) {
                         height: Val::Px(50.0),
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
-                        margin: UiRect::top(Val::Px(30.0)),
+                        margin: UiRect::top(Val::Px(10.0)),
                         ..default()
                     },
                     BorderRadius::all(Val::Px(8.0)),
@@ -168,11 +193,11 @@ fn spawn_mode_button(
             Button,
             Node {
                 width: Val::Px(400.0),
-                height: Val::Px(100.0),
+                height: Val::Px(64.0),
                 flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
-                margin: UiRect::bottom(Val::Px(20.0)),
+                margin: UiRect::bottom(Val::Px(8.0)),
                 ..default()
             },
             BorderRadius::all(Val::Px(12.0)),
@@ -207,6 +232,7 @@ fn spawn_mode_button(
         });
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn handle_game_mode_selection(
     _commands: Commands,
     mut interaction_query: Query<
@@ -236,8 +262,7 @@ fn handle_game_mode_selection(
     // Обрабатываем нажатия для каждого типа кнопок отдельно
     for interaction in &classic_query {
         if *interaction == Interaction::Pressed {
-            mode_settings.current_mode = GameMode::Classic;
-            setup_classic_mode(&mut mode_settings);
+            apply_game_mode(&mut mode_settings, GameMode::Classic);
             next_state.set(GameState::PreGame);
             return;
         }
@@ -245,8 +270,7 @@ fn handle_game_mode_selection(
 
     for interaction in &time_attack_query {
         if *interaction == Interaction::Pressed {
-            mode_settings.current_mode = GameMode::TimeAttack;
-            setup_time_attack_mode(&mut mode_settings);
+            apply_game_mode(&mut mode_settings, GameMode::TimeAttack);
             next_state.set(GameState::PreGame);
             return;
         }
@@ -254,8 +278,7 @@ fn handle_game_mode_selection(
 
     for interaction in &zen_query {
         if *interaction == Interaction::Pressed {
-            mode_settings.current_mode = GameMode::Zen;
-            setup_zen_mode(&mut mode_settings);
+            apply_game_mode(&mut mode_settings, GameMode::Zen);
             next_state.set(GameState::PreGame);
             return;
         }
@@ -263,8 +286,7 @@ fn handle_game_mode_selection(
 
     for interaction in &survival_query {
         if *interaction == Interaction::Pressed {
-            mode_settings.current_mode = GameMode::Survival;
-            setup_survival_mode(&mut mode_settings);
+            apply_game_mode(&mut mode_settings, GameMode::Survival);
             next_state.set(GameState::PreGame);
             return;
         }
@@ -284,11 +306,24 @@ fn handle_game_mode_selection(
     }
 }
 
+/// Выбирает режим и выставляет его параметры
+pub fn apply_game_mode(mode_settings: &mut GameModeSettings, mode: GameMode) {
+    mode_settings.current_mode = mode;
+    match mode {
+        GameMode::Classic => setup_classic_mode(mode_settings),
+        GameMode::TimeAttack => setup_time_attack_mode(mode_settings),
+        GameMode::Zen => setup_zen_mode(mode_settings),
+        GameMode::Survival => setup_survival_mode(mode_settings),
+        GameMode::Course => setup_course_mode(mode_settings),
+    }
+}
+
 fn setup_classic_mode(mode_settings: &mut GameModeSettings) {
     mode_settings.time_limit = None;
     mode_settings.target_score = None;
     mode_settings.lives = None;
     mode_settings.difficulty_multiplier = 1.0;
+    mode_settings.start_style = StartStyle::TapToStart;
 }
 
 fn setup_time_attack_mode(mode_settings: &mut GameModeSettings) {
@@ -296,6 +331,7 @@ fn setup_time_attack_mode(mode_settings: &mut GameModeSettings) {
     mode_settings.target_score = None;
     mode_settings.lives = None;
     mode_settings.difficulty_multiplier = 1.2;
+    mode_settings.start_style = StartStyle::Countdown(3);
 }
 
 fn setup_zen_mode(mode_settings: &mut GameModeSettings) {
@@ -303,6 +339,7 @@ fn setup_zen_mode(mode_settings: &mut GameModeSettings) {
     mode_settings.target_score = None;
     mode_settings.lives = None; // Бесконечные жизни
     mode_settings.difficulty_multiplier = 0.8; // Легче обычного
+    mode_settings.start_style = StartStyle::TapToStart;
 }
 
 fn setup_survival_mode(mode_settings: &mut GameModeSettings) {
@@ -310,6 +347,15 @@ fn setup_survival_mode(mode_settings: &mut GameModeSettings) {
     mode_settings.target_score = None;
     mode_settings.lives = Some(1); // Одна жизнь
     mode_settings.difficulty_multiplier = 1.5; // Сложнее обычного
+    mode_settings.start_style = StartStyle::Countdown(3);
+}
+
+fn setup_course_mode(mode_settings: &mut GameModeSettings) {
+    mode_settings.time_limit = None;
+    mode_settings.target_score = None;
+    mode_settings.lives = None;
+    mode_settings.difficulty_multiplier = 1.0;
+    mode_settings.start_style = StartStyle::TapToStart;
 }
 
 fn setup_game_mode(
@@ -428,6 +474,7 @@ fn spawn_game_mode_ui(
                 GameMode::TimeAttack => ("⏱ Гонка на время", Color::srgb(1.0, 1.0, 0.0)),
                 GameMode::Zen => ("🧘 Дзен режим", Color::srgb(0.0, 1.0, 0.0)),
                 GameMode::Survival => ("💀 Режим выживания", Color::srgb(1.0, 0.0, 0.0)),
+                GameMode::Course => ("🏁 Трасса", Color::srgb(0.6, 0.9, 1.0)),
             };
 
             parent.spawn((
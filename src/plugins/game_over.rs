@@ -1,10 +1,16 @@
 use crate::{
     core::{
-        components::{MainMenuButton, MenuButton, RestartButton},
+        components::{MainMenuButton, MenuButton, RestartButton, WatchReplayButton},
         resources::{GameAssets, GameOverUIState, GameScore, HighScores},
         utils::despawn_entities,
     },
-    plugins::high_score::spawn_game_over_high_scores,
+    plugins::{
+        courses::{spawn_course_results, CourseResults},
+        daily::{spawn_daily_results, DailyHistory, DailyRun},
+        high_score::spawn_game_over_high_scores,
+        replay::{ReplayPlayback, ReplayRecorder},
+        rng::GameRng,
+    },
     states::{
         app_state::AppState,
         game_state::{GameOverSet, GameState},
@@ -68,11 +74,18 @@ fn show_game_over_ui(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_game_over_screen(
     mut commands: Commands,
     score: Res<GameScore>,
     high_scores: Res<HighScores>,
     asset: Res<GameAssets>,
+    rng: Res<GameRng>,
+    recorder: Res<ReplayRecorder>,
+    playback: Res<ReplayPlayback>,
+    daily: Res<DailyRun>,
+    daily_history: Res<DailyHistory>,
+    course_results: Res<CourseResults>,
     mut ui_state: ResMut<GameOverUIState>,
 ) {
     // Устанавливаем флаг состояния
@@ -119,6 +132,26 @@ fn spawn_game_over_screen(
 
             spawn_game_over_high_scores(parent, &score, &high_scores, &asset);
 
+            if !playback.is_active() {
+                spawn_daily_results(parent, &daily, &daily_history, &asset);
+            }
+            spawn_course_results(parent, &course_results, &asset);
+
+            // Seed забега для воспроизведения (--seed)
+            parent.spawn((
+                Text::new(format!("Seed: {}", rng.seed())),
+                TextFont {
+                    font: asset.font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
             // Кнопка перезапуска
             parent
                 .spawn((
@@ -149,6 +182,38 @@ fn spawn_game_over_screen(
                     ));
                 });
 
+            // Кнопка просмотра повтора только что сыгранного забега
+            if recorder.last_replay.is_some() && !playback.is_active() {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(200.0),
+                            height: Val::Px(50.0),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Px(10.0)),
+                            overflow: Overflow::clip(),
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(8.0)),
+                        BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                        MenuButton,
+                        WatchReplayButton,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new("Смотреть повтор"),
+                            TextFont {
+                                font: asset.font.clone(),
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+            }
+
             // Кнопка главного меню
             parent
                 .spawn((
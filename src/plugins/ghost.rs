@@ -0,0 +1,441 @@
+//! Призрак лучшего забега
+//!
+//! Во время игры рядом с птицей летит полупрозрачный призрак, который повторяет
+//! траекторию личного рекорда в том же режиме и на той же сложности.
+//! Траектория восстанавливается из повтора: те же прыжки на тех же шагах
+//! симуляции и та же физика, что у птицы. Призрак не сталкивается с трубами,
+//! не набирает очки и не подбирает power-ups.
+
+use crate::core::difficulty_types::{DifficultyLevel, DifficultySettings};
+use crate::core::resources::{is_cheated_run, GameMode, GameModeSettings, HighScores};
+use crate::core::save::{self, SaveDir};
+use crate::core::utils::despawn_entities;
+use crate::plugins::bird::{advance_bird_animation, step_bird_physics, BirdAnimation, BIRD_SIZE};
+use crate::plugins::courses::ActiveCourse;
+use crate::plugins::editor::is_editor_playtest;
+use crate::plugins::high_score::load_high_scores;
+use crate::plugins::hitboxes::Hitboxes;
+use crate::plugins::profiles::ProfileSwitched;
+use crate::plugins::replay::{
+    is_replay_playing, load_replay_file, Replay, ReplayLoadError, ReplayRecorder,
+};
+use crate::plugins::simulation::{InterpolatedTransform, RunClock};
+use crate::plugins::skins::Skins;
+use crate::states::game_state::{GameOverSet, GameState, PreGameSet, SimulationSet};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Прозрачность призрака
+const GHOST_ALPHA: f32 = 0.35;
+
+/// Настройки призрака
+#[derive(Resource)]
+pub struct GhostSettings {
+    pub enabled: bool,
+}
+
+impl Default for GhostSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Лучшие забеги по режиму, сложности, трассе (хэшу её раскладки) и хитбоксам
+#[derive(Resource, Default)]
+pub struct BestRuns {
+    runs: HashMap<(GameMode, DifficultyLevel, Option<u64>, u64), Replay>,
+}
+
+impl BestRuns {
+    /// Лучший забег для режима, сложности, трассы и хэша хитбоксов
+    pub fn best(
+        &self,
+        mode: GameMode,
+        difficulty: DifficultyLevel,
+        course: Option<u64>,
+        hitboxes: u64,
+    ) -> Option<&Replay> {
+        self.runs.get(&(mode, difficulty, course, hitboxes))
+    }
+
+    /// Запоминает забег, если он лучше известного. Возвращает `true`, если запомнил
+    pub fn offer(&mut self, replay: &Replay) -> bool {
+        let key = (
+            replay.mode,
+            replay.difficulty,
+            replay.course,
+            replay.hitboxes,
+        );
+        let is_better = self
+            .runs
+            .get(&key)
+            .is_none_or(|best| replay.score > best.score);
+        if is_better {
+            self.runs.insert(key, replay.clone());
+        }
+        is_better
+    }
+}
+
+/// Призрак: прыгает по записанным шагам лучшего забега
+#[derive(Component)]
+pub struct Ghost {
+    velocity: f32,
+    flaps: Vec<u32>,
+    next_flap: usize,
+    final_tick: u32,
+}
+
+/// Плагин призрака лучшего забега
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GhostSettings>()
+            .init_resource::<BestRuns>()
+            .add_systems(Startup, load_best_runs.after(load_high_scores))
+            .add_systems(
+                Update,
+                load_best_runs
+                    .after(load_high_scores)
+                    .run_if(on_event::<ProfileSwitched>),
+            )
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (
+                    despawn_entities::<Ghost>.in_set(PreGameSet::Cleanup),
+                    spawn_ghost
+                        .in_set(PreGameSet::Spawn)
+                        .run_if(not(is_replay_playing)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                move_ghost
+                    .in_set(SimulationSet::Movement)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, animate_ghost.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                remember_best_run.after(GameOverSet::SaveReplay).run_if(
+                    not(is_replay_playing)
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_entities::<Ghost>)
+            .add_systems(OnEnter(GameState::MainMenu), despawn_entities::<Ghost>);
+    }
+}
+
+/// Загружает повторы рекордов, чтобы у призрака была траектория с прошлых запусков.
+/// Ссылки на повторы старых версий убираются из рекордов: воспроизвести их уже нельзя
+fn load_best_runs(
+    save_dir: Res<SaveDir>,
+    mut high_scores: ResMut<HighScores>,
+    mut best_runs: ResMut<BestRuns>,
+) {
+    *best_runs = BestRuns::default();
+    let mut outdated = false;
+    for entry in &mut high_scores.scores {
+        let Some(name) = entry.replay.as_deref() else {
+            continue;
+        };
+        match load_replay_file(name, &save_dir) {
+            Ok(replay) => {
+                best_runs.offer(&replay);
+            }
+            Err(ReplayLoadError::Outdated(_)) => {
+                entry.replay = None;
+                outdated = true;
+            }
+            Err(_) => {}
+        }
+    }
+    if outdated {
+        save::save(&save_dir, &*high_scores);
+    }
+}
+
+fn remember_best_run(recorder: Res<ReplayRecorder>, mut best_runs: ResMut<BestRuns>) {
+    if let Some(replay) = recorder.last_replay.as_ref() {
+        if replay.score > 0 {
+            best_runs.offer(replay);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_ghost(
+    mut commands: Commands,
+    settings: Res<GhostSettings>,
+    best_runs: Res<BestRuns>,
+    mode_settings: Res<GameModeSettings>,
+    difficulty: Res<DifficultySettings>,
+    course: ActiveCourse,
+    hitboxes: Res<Hitboxes>,
+    skins: Skins,
+) {
+    if !settings.enabled {
+        return;
+    }
+    let Some(best) = best_runs.best(
+        mode_settings.current_mode,
+        difficulty.current_level,
+        course.content_hash(),
+        hitboxes.content_hash(),
+    ) else {
+        return;
+    };
+
+    let sprite = skins
+        .selected()
+        .map(|skin| skin.sprite())
+        .unwrap_or_default();
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 1.0, 1.0, GHOST_ALPHA),
+            custom_size: Some(Vec2::new(BIRD_SIZE, BIRD_SIZE)),
+            ..sprite
+        },
+        // Чуть позади птицы, чтобы не перекрывать её
+        Transform::from_xyz(0.0, 0.0, -0.5),
+        Ghost {
+            velocity: 0.0,
+            flaps: best.flaps.clone(),
+            next_flap: 0,
+            final_tick: best.final_tick,
+        },
+        InterpolatedTransform::default(),
+        BirdAnimation::default(),
+        Name::new("Ghost"),
+    ));
+}
+
+/// Двигает призрака той же физикой, что и птицу; на последнем шаге рекорда он исчезает
+fn move_ghost(
+    mut commands: Commands,
+    time: Res<Time>,
+    clock: Res<RunClock>,
+    mut query: Query<(Entity, &mut Ghost, &mut Transform)>,
+) {
+    for (entity, mut ghost, mut transform) in &mut query {
+        if clock.tick >= ghost.final_tick {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let mut jumped = false;
+        while let Some(&tick) = ghost.flaps.get(ghost.next_flap) {
+            if tick > clock.tick {
+                break;
+            }
+            jumped |= tick == clock.tick;
+            ghost.next_flap += 1;
+        }
+
+        step_bird_physics(
+            &mut ghost.velocity,
+            &mut transform,
+            jumped,
+            time.delta_secs(),
+        );
+    }
+}
+
+fn animate_ghost(
+    time: Res<Time>,
+    mut query: Query<(&Ghost, &mut BirdAnimation, &mut Sprite)>,
+    skins: Skins,
+) {
+    let Some(skin) = skins.selected() else {
+        return;
+    };
+    for (ghost, mut animation, mut sprite) in &mut query {
+        advance_bird_animation(
+            &mut animation,
+            &mut sprite,
+            ghost.velocity,
+            false,
+            &time,
+            &skin.animation,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::RestartButton;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder, TempSaveDir};
+    use crate::plugins::bird::Bird;
+
+    fn default_hitboxes() -> u64 {
+        Hitboxes::default().content_hash()
+    }
+
+    fn replay_with_score(mode: GameMode, difficulty: DifficultyLevel, score: u32) -> Replay {
+        let mut replay = Replay::new(1, mode, difficulty);
+        replay.score = score;
+        replay
+    }
+
+    #[test]
+    fn test_best_runs_keep_highest_score_per_mode() {
+        let mut best_runs = BestRuns::default();
+        let normal = DifficultyLevel::Normal;
+        assert!(best_runs.offer(&replay_with_score(GameMode::Classic, normal, 5)));
+        assert!(!best_runs.offer(&replay_with_score(GameMode::Classic, normal, 3)));
+        assert!(best_runs.offer(&replay_with_score(GameMode::Zen, normal, 1)));
+
+        let classic = best_runs.best(
+            GameMode::Classic,
+            DifficultyLevel::Normal,
+            None,
+            default_hitboxes(),
+        );
+        assert_eq!(classic.map(|replay| replay.score), Some(5));
+        assert!(best_runs
+            .best(
+                GameMode::Classic,
+                DifficultyLevel::Hard,
+                None,
+                default_hitboxes()
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_best_runs_are_kept_per_course() {
+        let mut best_runs = BestRuns::default();
+        let normal = DifficultyLevel::Normal;
+        let mut course_a = replay_with_score(GameMode::Course, normal, 4);
+        course_a.course = Some(1);
+        let mut course_b = replay_with_score(GameMode::Course, normal, 2);
+        course_b.course = Some(2);
+        assert!(best_runs.offer(&course_a));
+        assert!(best_runs.offer(&course_b));
+
+        let best_b = best_runs.best(GameMode::Course, normal, Some(2), default_hitboxes());
+        assert_eq!(best_b.map(|replay| replay.score), Some(2));
+        assert!(best_runs
+            .best(GameMode::Course, normal, Some(3), default_hitboxes())
+            .is_none());
+    }
+
+    #[test]
+    fn test_best_runs_are_kept_per_hitboxes() {
+        let mut best_runs = BestRuns::default();
+        let mut other = replay_with_score(GameMode::Classic, DifficultyLevel::Normal, 7);
+        other.hitboxes = default_hitboxes() ^ 1;
+        assert!(best_runs.offer(&other));
+
+        let normal = DifficultyLevel::Normal;
+        let classic = best_runs.best(GameMode::Classic, normal, None, default_hitboxes());
+        assert!(classic.is_none());
+    }
+
+    fn ghost_y(game: &mut HeadlessGame) -> Option<f32> {
+        let world = game.app.world_mut();
+        world
+            .query_filtered::<&Transform, With<Ghost>>()
+            .iter(world)
+            .next()
+            .map(|transform| transform.translation.y)
+    }
+
+    #[test]
+    fn test_ghost_follows_best_run_without_touching_the_game() {
+        let mut game = HeadlessGameBuilder::default().with_seed(5).build();
+        let mut best = replay_with_score(GameMode::Classic, DifficultyLevel::default(), 10);
+        best.final_tick = 200;
+        best.flaps = vec![0, 20, 40, 60];
+        game.resource_mut::<BestRuns>().offer(&best);
+
+        game.start_run();
+        assert_eq!(game.count::<Ghost>(), 1);
+        assert_eq!(game.count::<Bird>(), 1);
+
+        // Призрак прыгает сам, поэтому он выше падающей птицы
+        game.step_frames(20);
+        let world = game.app.world_mut();
+        let bird_y = world
+            .query_filtered::<&Transform, With<Bird>>()
+            .single(world)
+            .translation
+            .y;
+        assert!(ghost_y(&mut game).unwrap() > bird_y);
+
+        // Птица разбилась, а призрак не повлиял на счёт и не пережил забег
+        game.step_frames(120);
+        assert_eq!(game.game_state(), GameState::GameOver);
+        assert_eq!(game.score(), 0);
+        game.click_button::<RestartButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        assert_eq!(game.count::<Ghost>(), 1);
+    }
+
+    #[test]
+    fn test_ghost_disabled_in_settings() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.resource_mut::<BestRuns>().offer(&replay_with_score(
+            GameMode::Classic,
+            DifficultyLevel::default(),
+            1,
+        ));
+        game.resource_mut::<GhostSettings>().enabled = false;
+        game.start_run();
+
+        assert_eq!(game.count::<Ghost>(), 0);
+    }
+
+    #[test]
+    fn test_outdated_replays_are_dropped_from_high_scores() {
+        let temp = TempSaveDir::new("ghost_outdated");
+        let save_dir = temp.save_dir();
+        let dir = crate::plugins::replay::replays_dir(&save_dir).unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let current = replay_with_score(GameMode::Classic, DifficultyLevel::Normal, 5);
+        current.save(&dir.join("replay_current.json")).unwrap();
+        let mut old = current.clone();
+        old.version = 1;
+        old.save(&dir.join("replay_old.json")).unwrap();
+
+        let mut high_scores = HighScores::default();
+        for name in ["replay_current.json", "replay_old.json"] {
+            let mut entry = crate::core::resources::HighScoreEntry::new(5, String::new());
+            entry.replay = Some(name.to_string());
+            high_scores.scores.push(entry);
+        }
+        save::save(&save_dir, &high_scores);
+
+        // Рекорды и призраки перечитываются так же, как при смене профиля
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app.insert_resource(save_dir.clone());
+        game.app.world_mut().send_event(ProfileSwitched);
+        game.step();
+
+        let replays: Vec<Option<String>> = game
+            .resource::<HighScores>()
+            .scores
+            .iter()
+            .map(|entry| entry.replay.clone())
+            .collect();
+        let saved = save::load::<HighScores>(&save_dir).unwrap();
+
+        assert_eq!(replays, vec![Some("replay_current.json".to_string()), None]);
+        assert_eq!(saved.scores[1].replay, None);
+        assert!(game
+            .resource::<BestRuns>()
+            .best(
+                GameMode::Classic,
+                DifficultyLevel::Normal,
+                None,
+                default_hitboxes()
+            )
+            .is_some());
+    }
+}
@@ -1,13 +1,15 @@
 use crate::core::{
     difficulty_types::DifficultySettings,
-    resources::{GameAssets, GameScore, HighScoreEntry, HighScores},
+    resources::{is_cheated_run, GameAssets, GameScore, HighScoreEntry, HighScores},
+    save::{self, is_app_exiting, SaveDir},
 };
+use crate::plugins::editor::is_editor_playtest;
+use crate::plugins::profiles::ProfileSwitched;
+use crate::plugins::replay::{is_replay_playing, prune_replays, ReplayRecorder};
 use crate::states::game_state::{GameOverSet, GameState};
 use bevy::prelude::*;
 use bevy::text::{TextColor, TextFont};
 use bevy::ui::{AlignItems, FlexDirection, Node, UiRect, Val};
-use std::fs;
-use std::path::PathBuf;
 
 /// Плагин для управления системой рекордов
 pub struct HighScorePlugin;
@@ -16,71 +18,53 @@ impl Plugin for HighScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<HighScores>()
             .add_systems(Startup, load_high_scores)
+            .add_systems(Update, load_high_scores.run_if(on_event::<ProfileSwitched>))
             .add_systems(
                 OnEnter(GameState::GameOver),
-                update_high_scores_and_save.in_set(GameOverSet::UpdateScores),
+                (
+                    update_high_scores_and_save,
+                    prune_replay_files,
+                    save_high_scores,
+                )
+                    .chain()
+                    .in_set(GameOverSet::UpdateScores)
+                    .after(GameOverSet::SaveReplay)
+                    .run_if(
+                        not(is_replay_playing)
+                            .and(not(is_editor_playtest))
+                            .and(not(is_cheated_run)),
+                    ),
             )
-            .add_systems(OnExit(GameState::GameOver), save_high_scores);
+            .add_systems(OnExit(GameState::GameOver), save_high_scores)
+            .add_systems(Last, save_high_scores.run_if(is_app_exiting));
     }
 }
 
-/// Загружает рекорды из файла при запуске
-pub fn load_high_scores(mut high_scores: ResMut<HighScores>) {
-    if let Some(path) = get_high_scores_path() {
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => match serde_json::from_str::<HighScores>(&content) {
-                    Ok(loaded_scores) => {
-                        *high_scores = loaded_scores;
-                        println!("Загружено {} рекордов", high_scores.scores.len());
-                    }
-                    Err(e) => {
-                        eprintln!("Ошибка десериализации рекордов: {}", e);
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Ошибка чтения файла рекордов: {}", e);
-                }
-            }
-        }
-    }
+/// Загружает рекорды активного профиля при запуске и при смене профиля
+pub fn load_high_scores(save_dir: Res<SaveDir>, mut high_scores: ResMut<HighScores>) {
+    *high_scores = save::load::<HighScores>(&save_dir).unwrap_or_default();
+    println!("Загружено {} рекордов", high_scores.scores.len());
 }
 
 /// Сохраняет рекорды в файл
-pub fn save_high_scores(high_scores: Res<HighScores>) {
-    if let Some(path) = get_high_scores_path() {
-        // Создаем директорию если её нет
-        if let Some(parent) = path.parent() {
-            if let Err(e) = fs::create_dir_all(parent) {
-                eprintln!("Ошибка создания директории для рекордов: {}", e);
-                return;
-            }
-        }
-
-        match serde_json::to_string_pretty(&*high_scores) {
-            Ok(json) => {
-                if let Err(e) = fs::write(&path, json) {
-                    eprintln!("Ошибка сохранения рекордов: {}", e);
-                }
-            }
-            Err(e) => {
-                eprintln!("Ошибка сериализации рекордов: {}", e);
-            }
-        }
-    }
+pub fn save_high_scores(save_dir: Res<SaveDir>, high_scores: Res<HighScores>) {
+    save::save(&save_dir, &*high_scores);
 }
 
 /// Обновляет рекорды и сохраняет их при окончании игры
+#[allow(clippy::unnecessary_sort_by)]
 pub fn update_high_scores_and_save(
     mut high_scores: ResMut<HighScores>,
     game_score: Res<GameScore>,
     difficulty: Res<DifficultySettings>,
+    recorder: Res<ReplayRecorder>,
 ) {
     if game_score.0 > 0 {
-        let new_entry = HighScoreEntry::new(
+        let mut new_entry = HighScoreEntry::new(
             game_score.0,
             difficulty.current_level.russian_name().to_string(),
         );
+        new_entry.replay = recorder.last_file.clone();
 
         // Добавляем новый рекорд
         high_scores.scores.push(new_entry);
@@ -102,6 +86,21 @@ pub fn update_high_scores_and_save(
     }
 }
 
+/// Оставляет на диске только повтор последнего забега и повторы из таблицы рекордов
+pub fn prune_replay_files(
+    save_dir: Res<SaveDir>,
+    high_scores: Res<HighScores>,
+    recorder: Res<ReplayRecorder>,
+) {
+    let keep: Vec<&str> = high_scores
+        .scores
+        .iter()
+        .filter_map(|entry| entry.replay.as_deref())
+        .chain(recorder.last_file.as_deref())
+        .collect();
+    prune_replays(&save_dir, &keep);
+}
+
 /// Отображает рекорды на экране Game Over
 pub fn spawn_game_over_high_scores(
     parent: &mut ChildBuilder,
@@ -196,21 +195,6 @@ pub fn spawn_game_over_high_scores(
     }
 }
 
-/// Возвращает путь к файлу с рекордами
-fn get_high_scores_path() -> Option<PathBuf> {
-    match dirs::data_dir() {
-        Some(mut path) => {
-            path.push("my_project");
-            path.push("high_scores.json");
-            Some(path)
-        }
-        None => {
-            // Fallback к локальному файлу если системная директория недоступна
-            Some(PathBuf::from("high_scores.json"))
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +208,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_sort_by)]
     fn test_high_scores_sorting() {
         let mut high_scores = HighScores::default();
         high_scores
@@ -243,4 +228,37 @@ mod tests {
         assert_eq!(high_scores.scores[2].score, 50);
         assert_eq!(high_scores.scores[0].difficulty, "Сложно");
     }
+
+    #[test]
+    fn test_only_last_and_high_score_replays_kept() {
+        let temp = crate::core::headless::TempSaveDir::new("high_score_replays");
+        let save_dir = temp.save_dir();
+        let dir = crate::plugins::replay::replays_dir(&save_dir).unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["replay_old.json", "replay_best.json", "notes.txt"] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        let mut game = crate::core::headless::HeadlessGameBuilder::default().build();
+        game.app.insert_resource(save_dir);
+        let mut best = HighScoreEntry::new(10, "Нормально".to_string());
+        best.replay = Some("replay_best.json".to_string());
+        game.resource_mut::<HighScores>().scores.push(best);
+
+        // Без прыжков птица разбивается с нулевым счётом
+        game.start_run();
+        game.step_frames(120);
+        assert_eq!(game.game_state(), GameState::GameOver);
+        let last = game.resource::<ReplayRecorder>().last_file.clone().unwrap();
+        let exists = |name: &str| dir.join(name).exists();
+        let kept = (
+            exists("replay_best.json"),
+            exists(&last),
+            exists("notes.txt"),
+        );
+        let old_removed = !exists("replay_old.json");
+
+        assert_eq!(kept, (true, true, true));
+        assert!(old_removed);
+    }
 }
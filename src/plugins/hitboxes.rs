@@ -0,0 +1,563 @@
+//! Хитбоксы
+//!
+//! У каждого объекта, с которым сталкивается птица, есть `Collider` из одной
+//! или нескольких фигур: прямоугольников, кругов и капсул. Размеры фигур
+//! задаются в `assets/hitboxes/default.hitboxes.ron` и меняются только между
+//! забегами. F2 рисует все хитбоксы поверх игры; фигуры, которых касается птица,
+//! выделены красным.
+
+use crate::core::{resources::GameAssets, utils::fnv1a};
+use crate::plugins::bird::Bird;
+use crate::states::game_state::{GameState, PreGameSet};
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::math::Isometry2d;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Файл хитбоксов внутри `assets`
+pub const HITBOXES_PATH: &str = "hitboxes/default.hitboxes.ron";
+/// Клавиша показа хитбоксов
+const DEBUG_TOGGLE_KEY: KeyCode = KeyCode::F2;
+/// Шагов поиска ближайшей точки вдоль оси капсулы
+const CAPSULE_SEARCH_STEPS: usize = 24;
+
+const BIRD_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
+const HIT_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+const COLLIDER_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+
+/// Форма хитбокса
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HitboxShape {
+    /// Прямоугольник по осям экрана; не поворачивается вместе со спрайтом
+    Box {
+        width: f32,
+        height: f32,
+    },
+    Circle {
+        radius: f32,
+    },
+    /// Отрезок длиной `length` вдоль спрайта, раздутый на `radius`
+    Capsule {
+        radius: f32,
+        length: f32,
+    },
+}
+
+impl HitboxShape {
+    fn is_valid(&self) -> bool {
+        match *self {
+            HitboxShape::Box { width, height } => width > 0.0 && height > 0.0,
+            HitboxShape::Circle { radius } => radius > 0.0,
+            HitboxShape::Capsule { radius, length } => radius > 0.0 && length >= 0.0,
+        }
+    }
+}
+
+/// Фигура со смещением от центра объекта
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub shape: HitboxShape,
+    pub offset: Vec2,
+}
+
+/// Хитбокс объекта
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Collider {
+    pub hitboxes: Vec<Hitbox>,
+}
+
+impl Collider {
+    /// Одна фигура в центре объекта
+    pub fn new(shape: HitboxShape) -> Self {
+        Self {
+            hitboxes: vec![Hitbox {
+                shape,
+                offset: Vec2::ZERO,
+            }],
+        }
+    }
+
+    /// Тело трубы длиной `length` и шапка у её верхнего края. У верхней трубы
+    /// масштаб по y отрицательный, поэтому шапка оказывается внизу, у просвета
+    pub fn pipe(pipe: &PipeHitbox, length: f32) -> Self {
+        Self {
+            hitboxes: vec![
+                Hitbox {
+                    shape: HitboxShape::Box {
+                        width: pipe.body_width,
+                        height: length,
+                    },
+                    offset: Vec2::ZERO,
+                },
+                Hitbox {
+                    shape: HitboxShape::Box {
+                        width: pipe.cap_width,
+                        height: pipe.cap_height,
+                    },
+                    offset: Vec2::new(0.0, (length - pipe.cap_height) / 2.0),
+                },
+            ],
+        }
+    }
+
+    pub fn intersects(
+        &self,
+        transform: &Transform,
+        other: &Collider,
+        other_transform: &Transform,
+    ) -> bool {
+        self.placed(transform).any(|shape| {
+            other
+                .placed(other_transform)
+                .any(|other| shape.intersects(&other))
+        })
+    }
+
+    fn placed<'a>(&'a self, transform: &'a Transform) -> impl Iterator<Item = PlacedShape> + 'a {
+        self.hitboxes
+            .iter()
+            .map(move |hitbox| PlacedShape::new(hitbox, transform))
+    }
+}
+
+/// Фигура, поставленная на поле
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlacedShape {
+    Rect {
+        min: Vec2,
+        max: Vec2,
+    },
+    /// Круг — капсула с совпадающими концами
+    Capsule {
+        start: Vec2,
+        end: Vec2,
+        radius: f32,
+    },
+}
+
+impl PlacedShape {
+    fn new(hitbox: &Hitbox, transform: &Transform) -> Self {
+        let scale = transform.scale.truncate();
+        let offset = transform.rotation * (hitbox.offset * scale).extend(0.0);
+        let center = transform.translation.truncate() + offset.truncate();
+        match hitbox.shape {
+            HitboxShape::Box { width, height } => {
+                let half = Vec2::new(width, height) * scale.abs() / 2.0;
+                PlacedShape::Rect {
+                    min: center - half,
+                    max: center + half,
+                }
+            }
+            HitboxShape::Circle { radius } => PlacedShape::Capsule {
+                start: center,
+                end: center,
+                radius: radius * scale.x.abs(),
+            },
+            HitboxShape::Capsule { radius, length } => {
+                let axis = (transform.rotation * Vec3::X).truncate() * length * scale.x.abs() / 2.0;
+                PlacedShape::Capsule {
+                    start: center - axis,
+                    end: center + axis,
+                    radius: radius * scale.x.abs(),
+                }
+            }
+        }
+    }
+
+    fn intersects(&self, other: &PlacedShape) -> bool {
+        match (*self, *other) {
+            (
+                PlacedShape::Rect {
+                    min: a_min,
+                    max: a_max,
+                },
+                PlacedShape::Rect {
+                    min: b_min,
+                    max: b_max,
+                },
+            ) => a_min.x < b_max.x && a_max.x > b_min.x && a_min.y < b_max.y && a_max.y > b_min.y,
+            (PlacedShape::Capsule { start, end, radius }, PlacedShape::Rect { min, max })
+            | (PlacedShape::Rect { min, max }, PlacedShape::Capsule { start, end, radius }) => {
+                min_along(start, end, |point| point.distance(point.clamp(min, max))) < radius
+            }
+            (
+                PlacedShape::Capsule {
+                    start: a_start,
+                    end: a_end,
+                    radius: a_radius,
+                },
+                PlacedShape::Capsule {
+                    start: b_start,
+                    end: b_end,
+                    radius: b_radius,
+                },
+            ) => {
+                min_along(a_start, a_end, |point| {
+                    point.distance(closest_on_segment(point, b_start, b_end))
+                }) < a_radius + b_radius
+            }
+        }
+    }
+}
+
+/// Ближайшая к `point` точка отрезка
+fn closest_on_segment(point: Vec2, start: Vec2, end: Vec2) -> Vec2 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return start;
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    start + segment * t
+}
+
+/// Наименьшее расстояние вдоль отрезка. Расстояние до выпуклой фигуры выпукло,
+/// поэтому троичный поиск находит минимум
+fn min_along(start: Vec2, end: Vec2, distance: impl Fn(Vec2) -> f32) -> f32 {
+    if start == end {
+        return distance(start);
+    }
+    let (mut low, mut high) = (0.0_f32, 1.0_f32);
+    for _ in 0..CAPSULE_SEARCH_STEPS {
+        let left = low + (high - low) / 3.0;
+        let right = high - (high - low) / 3.0;
+        if distance(start.lerp(end, left)) <= distance(start.lerp(end, right)) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+    distance(start.lerp(end, (low + high) / 2.0))
+}
+
+/// Хитбокс трубы: тело во всю длину и шапка у края просвета
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PipeHitbox {
+    pub body_width: f32,
+    pub cap_width: f32,
+    pub cap_height: f32,
+}
+
+/// Размеры хитбоксов из файла `HITBOXES_PATH`.
+///
+/// Один тип служит и ассетом, и ресурсом: игра читает ресурс, а загруженный
+/// файл переписывает его перед каждым забегом. Пока файл не загружен (и в тестах),
+/// действуют значения по умолчанию — те же, что в файле.
+#[derive(Asset, TypePath, Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hitboxes {
+    pub bird: HitboxShape,
+    pub pipe: PipeHitbox,
+    pub power_up: HitboxShape,
+    pub coin: HitboxShape,
+    pub mine: HitboxShape,
+    pub enemy_bird: HitboxShape,
+    pub rock: HitboxShape,
+}
+
+impl Default for Hitboxes {
+    fn default() -> Self {
+        Self {
+            bird: HitboxShape::Capsule {
+                radius: 18.0,
+                length: 10.0,
+            },
+            pipe: PipeHitbox {
+                body_width: 71.0,
+                cap_width: 80.0,
+                cap_height: 20.0,
+            },
+            power_up: HitboxShape::Circle { radius: 20.0 },
+            coin: HitboxShape::Circle { radius: 12.0 },
+            mine: HitboxShape::Circle { radius: 13.0 },
+            enemy_bird: HitboxShape::Capsule {
+                radius: 12.0,
+                length: 8.0,
+            },
+            rock: HitboxShape::Circle { radius: 14.0 },
+        }
+    }
+}
+
+impl Hitboxes {
+    /// Хэш размеров (FNV-1a). Им помечаются повторы и призраки: с другими
+    /// хитбоксами записанные прыжки приводят к другому забегу
+    pub fn content_hash(&self) -> u64 {
+        fnv1a(&serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Проверяет, что размеры положительные
+    pub fn validate(&self) -> Result<(), HitboxesLoadError> {
+        let shapes = [
+            ("bird", self.bird),
+            ("power_up", self.power_up),
+            ("coin", self.coin),
+            ("mine", self.mine),
+            ("enemy_bird", self.enemy_bird),
+            ("rock", self.rock),
+        ];
+        if let Some((name, _)) = shapes.iter().find(|(_, shape)| !shape.is_valid()) {
+            return Err(HitboxesLoadError::Invalid(format!(
+                "хитбокс «{}»: размеры должны быть больше нуля",
+                name
+            )));
+        }
+        let pipe = self.pipe;
+        if pipe.body_width <= 0.0 || pipe.cap_width <= 0.0 || pipe.cap_height <= 0.0 {
+            return Err(HitboxesLoadError::Invalid(
+                "хитбокс трубы: размеры должны быть больше нуля".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Ошибка загрузки хитбоксов
+#[derive(Debug)]
+pub enum HitboxesLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for HitboxesLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HitboxesLoadError::Io(error) => write!(f, "не удалось прочитать хитбоксы: {}", error),
+            HitboxesLoadError::Parse(error) => write!(f, "ошибка в файле хитбоксов: {}", error),
+            HitboxesLoadError::Invalid(error) => write!(f, "некорректные хитбоксы: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for HitboxesLoadError {}
+
+impl From<std::io::Error> for HitboxesLoadError {
+    fn from(error: std::io::Error) -> Self {
+        HitboxesLoadError::Io(error)
+    }
+}
+
+/// Разбирает хитбоксы из RON
+pub fn parse_hitboxes(bytes: &[u8]) -> Result<Hitboxes, HitboxesLoadError> {
+    let hitboxes: Hitboxes =
+        ron::de::from_bytes(bytes).map_err(|error| HitboxesLoadError::Parse(error.to_string()))?;
+    hitboxes.validate()?;
+    Ok(hitboxes)
+}
+
+/// Загрузчик `*.hitboxes.ron`
+#[derive(Default)]
+pub struct HitboxesLoader;
+
+impl AssetLoader for HitboxesLoader {
+    type Asset = Hitboxes;
+    type Settings = ();
+    type Error = HitboxesLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Hitboxes, HitboxesLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        parse_hitboxes(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["hitboxes.ron"]
+    }
+}
+
+/// Показ хитбоксов поверх игры
+#[derive(Resource, Default)]
+pub struct HitboxDebug {
+    pub enabled: bool,
+}
+
+pub fn hitbox_debug_enabled(debug: Res<HitboxDebug>) -> bool {
+    debug.enabled
+}
+
+/// Плагин хитбоксов
+pub struct HitboxesPlugin;
+
+impl Plugin for HitboxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Hitboxes>()
+            .init_asset_loader::<HitboxesLoader>()
+            .init_resource::<Hitboxes>()
+            .init_resource::<HitboxDebug>()
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                apply_hitboxes.in_set(PreGameSet::Cleanup),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_hitbox_debug,
+                    draw_hitboxes.run_if(hitbox_debug_enabled),
+                ),
+            );
+    }
+}
+
+/// Хитбоксы, с которыми пойдёт следующий забег
+#[derive(SystemParam)]
+pub struct NextRunHitboxes<'w> {
+    files: Res<'w, Assets<Hitboxes>>,
+    assets: Res<'w, GameAssets>,
+    current: Res<'w, Hitboxes>,
+}
+
+impl NextRunHitboxes<'_> {
+    pub fn get(&self) -> &Hitboxes {
+        self.files
+            .get(&self.assets.hitboxes)
+            .unwrap_or(&self.current)
+    }
+}
+
+/// Переносит загруженный файл в ресурс перед забегом. Посреди забега размеры
+/// не меняются: уже созданные `Collider` не пересобираются, а изменённый файл
+/// подхватит следующий забег
+fn apply_hitboxes(
+    files: Res<Assets<Hitboxes>>,
+    assets: Res<GameAssets>,
+    mut hitboxes: ResMut<Hitboxes>,
+) {
+    if let Some(loaded) = files.get(&assets.hitboxes) {
+        if *hitboxes != *loaded {
+            *hitboxes = loaded.clone();
+        }
+    }
+}
+
+fn toggle_hitbox_debug(keys: Res<ButtonInput<KeyCode>>, mut debug: ResMut<HitboxDebug>) {
+    if keys.just_pressed(DEBUG_TOGGLE_KEY) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+/// Рисует хитбоксы: птицу зелёным, задетое птицей красным, остальное жёлтым
+fn draw_hitboxes(
+    mut gizmos: Gizmos,
+    bird_query: Query<(&Transform, &Collider), With<Bird>>,
+    query: Query<(&Transform, &Collider), Without<Bird>>,
+) {
+    for (transform, collider) in &bird_query {
+        draw_collider(&mut gizmos, transform, collider, BIRD_COLOR);
+    }
+    for (transform, collider) in &query {
+        let hit = bird_query
+            .iter()
+            .any(|(bird_transform, bird)| bird.intersects(bird_transform, collider, transform));
+        let color = if hit { HIT_COLOR } else { COLLIDER_COLOR };
+        draw_collider(&mut gizmos, transform, collider, color);
+    }
+}
+
+fn draw_collider(gizmos: &mut Gizmos, transform: &Transform, collider: &Collider, color: Color) {
+    for shape in collider.placed(transform) {
+        match shape {
+            PlacedShape::Rect { min, max } => {
+                gizmos.rect_2d(
+                    Isometry2d::from_translation((min + max) / 2.0),
+                    max - min,
+                    color,
+                );
+            }
+            PlacedShape::Capsule { start, end, radius } => {
+                gizmos.circle_2d(Isometry2d::from_translation(start), radius, color);
+                if start == end {
+                    continue;
+                }
+                gizmos.circle_2d(Isometry2d::from_translation(end), radius, color);
+                let side = (end - start).normalize().perp() * radius;
+                gizmos.line_2d(start + side, end + side, color);
+                gizmos.line_2d(start - side, end - side, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    const BIRD: HitboxShape = HitboxShape::Capsule {
+        radius: 18.0,
+        length: 10.0,
+    };
+
+    fn square(size: f32) -> Collider {
+        Collider::new(HitboxShape::Box {
+            width: size,
+            height: size,
+        })
+    }
+
+    #[test]
+    fn test_capsule_is_tighter_than_its_bounding_box() {
+        let bird = Collider::new(BIRD);
+        let at = |x: f32, y: f32| Transform::from_xyz(x, y, 0.0);
+
+        // Угол квадрата заходит в описанный прямоугольник птицы 46×36, но не в капсулу
+        assert!(!bird.intersects(&at(0.0, 0.0), &square(40.0), &at(40.0, 36.0)));
+        assert!(bird.intersects(&at(0.0, 0.0), &square(40.0), &at(40.0, 0.0)));
+
+        // Повёрнутая капсула дотягивается вверх дальше радиуса
+        let nose_up = at(0.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2));
+        let above = at(0.0, 40.0);
+        assert!(bird.intersects(&nose_up, &square(40.0), &above));
+        assert!(!bird.intersects(&at(0.0, 0.0), &square(40.0), &above));
+
+        let coin = Collider::new(HitboxShape::Circle { radius: 12.0 });
+        assert!(bird.intersects(&at(0.0, 0.0), &coin, &at(34.0, 0.0)));
+        assert!(!bird.intersects(&at(0.0, 0.0), &coin, &at(36.0, 0.0)));
+    }
+
+    #[test]
+    fn test_pipe_cap_sits_at_the_gap() {
+        let pipes = Hitboxes::default().pipe;
+        let pipe = Collider::pipe(&pipes, 400.0);
+        let probe = Collider::new(HitboxShape::Circle { radius: 1.0 });
+        // Чуть правее тела трубы, но в пределах шапки
+        let x = (pipes.body_width + pipes.cap_width) / 4.0;
+
+        let bottom = Transform::from_xyz(0.0, -300.0, 0.0);
+        assert!(probe.intersects(&Transform::from_xyz(x, -105.0, 0.0), &pipe, &bottom));
+        assert!(!probe.intersects(&Transform::from_xyz(x, -200.0, 0.0), &pipe, &bottom));
+
+        let top = Transform::from_xyz(0.0, 300.0, 0.0).with_scale(Vec3::new(1.0, -1.0, 1.0));
+        assert!(probe.intersects(&Transform::from_xyz(x, 105.0, 0.0), &pipe, &top));
+        assert!(!probe.intersects(&Transform::from_xyz(x, 200.0, 0.0), &pipe, &top));
+    }
+
+    #[test]
+    fn test_content_hash_follows_sizes() {
+        let defaults = Hitboxes::default();
+        let mut wider = defaults.clone();
+        wider.pipe.body_width += 1.0;
+
+        assert_eq!(defaults.content_hash(), Hitboxes::default().content_hash());
+        assert_ne!(defaults.content_hash(), wider.content_hash());
+    }
+
+    #[test]
+    fn test_shipped_file_matches_defaults() {
+        let shipped = include_bytes!("../../assets/hitboxes/default.hitboxes.ron");
+        assert_eq!(parse_hitboxes(shipped).unwrap(), Hitboxes::default());
+
+        let text = std::str::from_utf8(shipped).unwrap();
+        let broken = text.replace("coin: Circle(radius: 12.0)", "coin: Circle(radius: 0.0)");
+        assert!(matches!(
+            parse_hitboxes(broken.as_bytes()),
+            Err(HitboxesLoadError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! Слой игровых действий
+//!
+//! Системы не читают клавиши напрямую: они спрашивают `ActionState`, нажато ли
+//! действие (прыжок, пауза, подтверждение, назад, рестарт). Какие клавиши, кнопки
+//! мыши и геймпада или касания вызывают действие, задаёт `InputBindings`; привязки
+//! можно менять в настройках, они сохраняются на диск.
+//! Геймпады читаются все сразу, подключение и отключение подхватывается на лету.
+
+use crate::core::save::{self, SaveData, SaveDir};
+use crate::plugins::profiles::ProfileSwitched;
+use bevy::input::gamepad::GamepadConnectionEvent;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Игровые действия
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameAction {
+    Flap,
+    Pause,
+    Confirm,
+    Back,
+    Restart,
+}
+
+/// Где действие читается. Действия из разных контекстов могут делить клавишу
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionContext {
+    /// Во время забега
+    Gameplay,
+    /// В меню и на экране Game Over
+    Menu,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 5] = [
+        GameAction::Flap,
+        GameAction::Pause,
+        GameAction::Confirm,
+        GameAction::Back,
+        GameAction::Restart,
+    ];
+
+    /// Возвращает русское название действия
+    pub fn russian_name(self) -> &'static str {
+        match self {
+            GameAction::Flap => "Прыжок",
+            GameAction::Pause => "Пауза",
+            GameAction::Confirm => "Подтвердить",
+            GameAction::Back => "Назад",
+            GameAction::Restart => "Заново",
+        }
+    }
+
+    pub fn context(self) -> ActionContext {
+        match self {
+            GameAction::Flap | GameAction::Pause => ActionContext::Gameplay,
+            GameAction::Confirm | GameAction::Back | GameAction::Restart => ActionContext::Menu,
+        }
+    }
+}
+
+/// Клавиша, кнопка мыши или геймпада, привязанная к действию
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Кнопка любого подключённого геймпада
+    Gamepad(GamepadButton),
+    /// Касание в любом месте экрана
+    Touch,
+}
+
+impl InputBinding {
+    /// Короткое название для экрана управления
+    pub fn label(self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{:?}", key);
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            InputBinding::Mouse(MouseButton::Left) => "ЛКМ".to_string(),
+            InputBinding::Mouse(MouseButton::Right) => "ПКМ".to_string(),
+            InputBinding::Mouse(MouseButton::Middle) => "СКМ".to_string(),
+            InputBinding::Mouse(button) => format!("Мышь {:?}", button),
+            InputBinding::Gamepad(button) => format!("🎮 {:?}", button),
+            InputBinding::Touch => "Касание".to_string(),
+        }
+    }
+}
+
+/// Две привязки одной клавиши к действиям одного контекста
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingConflict {
+    pub binding: InputBinding,
+    pub first: GameAction,
+    pub second: GameAction,
+}
+
+/// Привязки действий к вводу
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings {
+    bindings: HashMap<GameAction, Vec<InputBinding>>,
+}
+
+impl SaveData for InputBindings {
+    const FILE_NAME: &'static str = "bindings.json";
+    const VERSION: u32 = 1;
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let bindings = HashMap::from([
+            (
+                GameAction::Flap,
+                vec![
+                    InputBinding::Key(KeyCode::Space),
+                    InputBinding::Mouse(MouseButton::Left),
+                    InputBinding::Gamepad(GamepadButton::South),
+                    InputBinding::Gamepad(GamepadButton::LeftTrigger2),
+                    InputBinding::Gamepad(GamepadButton::RightTrigger2),
+                    InputBinding::Touch,
+                ],
+            ),
+            (
+                GameAction::Pause,
+                vec![
+                    InputBinding::Key(KeyCode::Escape),
+                    InputBinding::Key(KeyCode::KeyP),
+                    InputBinding::Gamepad(GamepadButton::Start),
+                ],
+            ),
+            (
+                GameAction::Confirm,
+                vec![
+                    InputBinding::Key(KeyCode::Space),
+                    InputBinding::Key(KeyCode::Enter),
+                    InputBinding::Gamepad(GamepadButton::South),
+                    InputBinding::Touch,
+                ],
+            ),
+            (
+                GameAction::Back,
+                vec![
+                    InputBinding::Key(KeyCode::Escape),
+                    InputBinding::Gamepad(GamepadButton::East),
+                ],
+            ),
+            (
+                GameAction::Restart,
+                vec![
+                    InputBinding::Key(KeyCode::KeyR),
+                    InputBinding::Gamepad(GamepadButton::North),
+                ],
+            ),
+        ]);
+        Self { bindings }
+    }
+}
+
+impl InputBindings {
+    /// Привязки действия
+    pub fn get(&self, action: GameAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Добавляет привязку, если она не конфликтует с другим действием
+    pub fn bind(
+        &mut self,
+        action: GameAction,
+        binding: InputBinding,
+    ) -> Result<(), BindingConflict> {
+        if let Some(other) = self.conflicting_action(action, binding) {
+            return Err(BindingConflict {
+                binding,
+                first: other,
+                second: action,
+            });
+        }
+
+        let list = self.bindings.entry(action).or_default();
+        if !list.contains(&binding) {
+            list.push(binding);
+        }
+        Ok(())
+    }
+
+    /// Убирает все привязки действия
+    pub fn clear(&mut self, action: GameAction) {
+        self.bindings.remove(&action);
+    }
+
+    /// Действие того же контекста, которое уже использует эту привязку
+    fn conflicting_action(&self, action: GameAction, binding: InputBinding) -> Option<GameAction> {
+        GameAction::ALL.into_iter().find(|&other| {
+            other != action
+                && other.context() == action.context()
+                && self.get(other).contains(&binding)
+        })
+    }
+
+    /// Все конфликты привязок (например, в отредактированном вручную файле)
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let mut conflicts = Vec::new();
+        for (index, &first) in GameAction::ALL.iter().enumerate() {
+            for &second in &GameAction::ALL[index + 1..] {
+                if first.context() != second.context() {
+                    continue;
+                }
+                for &binding in self.get(first) {
+                    if self.get(second).contains(&binding) {
+                        conflicts.push(BindingConflict {
+                            binding,
+                            first,
+                            second,
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Состояние действий в текущем кадре
+#[derive(Resource, Default)]
+pub struct ActionState {
+    just_pressed: HashSet<GameAction>,
+    /// Пока идёт назначение клавиши, действия не срабатывают
+    pub suspended: bool,
+}
+
+impl ActionState {
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+/// Плагин слоя действий
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>()
+            .init_resource::<ActionState>()
+            .add_systems(Startup, load_bindings)
+            .add_systems(Update, load_bindings.run_if(on_event::<ProfileSwitched>))
+            .add_systems(PreUpdate, update_action_state.after(InputSystem))
+            .add_systems(Update, log_gamepad_connections);
+    }
+}
+
+/// Загружает привязки активного профиля при запуске и при смене профиля
+fn load_bindings(save_dir: Res<SaveDir>, mut bindings: ResMut<InputBindings>) {
+    let Some(loaded) = save::load::<InputBindings>(&save_dir) else {
+        *bindings = InputBindings::default();
+        return;
+    };
+    for conflict in loaded.conflicts() {
+        warn!(
+            "Клавиша {} назначена и на «{}», и на «{}»",
+            conflict.binding.label(),
+            conflict.first.russian_name(),
+            conflict.second.russian_name()
+        );
+    }
+    *bindings = loaded;
+}
+
+/// Переводит нажатия клавиш, кнопок мыши и геймпадов и касания в действия
+fn update_action_state(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
+    mut state: ResMut<ActionState>,
+) {
+    state.just_pressed.clear();
+    if state.suspended {
+        return;
+    }
+
+    for action in GameAction::ALL {
+        for &binding in bindings.get(action) {
+            let just_pressed = match binding {
+                InputBinding::Key(key) => keys.just_pressed(key),
+                InputBinding::Mouse(button) => mouse_buttons.just_pressed(button),
+                InputBinding::Gamepad(button) => {
+                    gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+                }
+                InputBinding::Touch => touches.any_just_pressed(),
+            };
+            if just_pressed {
+                state.just_pressed.insert(action);
+            }
+        }
+    }
+}
+
+fn log_gamepad_connections(mut events: EventReader<GamepadConnectionEvent>) {
+    for event in events.read() {
+        if event.connected() {
+            info!("Геймпад подключён: {:?}", event.gamepad);
+        } else {
+            info!("Геймпад отключён: {:?}", event.gamepad);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::plugins::bird::Bird;
+    use crate::states::game_state::{GameState, PauseState};
+
+    fn bird_velocity(game: &mut HeadlessGame) -> f32 {
+        let world = game.app.world_mut();
+        world.query::<&Bird>().single(world).velocity
+    }
+
+    #[test]
+    fn test_rebound_flap_key_drives_bird() {
+        let mut game = HeadlessGameBuilder::default().build();
+        {
+            let mut bindings = game.resource_mut::<InputBindings>();
+            bindings.clear(GameAction::Flap);
+            bindings
+                .bind(GameAction::Flap, InputBinding::Key(KeyCode::KeyW))
+                .unwrap();
+        }
+        game.start_run();
+
+        game.tap_key(KeyCode::Space);
+        assert!(bird_velocity(&mut game) < 0.0);
+
+        game.tap_key(KeyCode::KeyW);
+        assert!(bird_velocity(&mut game) > 0.0);
+        assert_eq!(game.game_state(), GameState::Playing);
+    }
+
+    #[test]
+    fn test_default_bindings_have_no_conflicts() {
+        assert!(InputBindings::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_bind_rejects_conflict_in_same_context() {
+        let mut bindings = InputBindings::default();
+        let space = InputBinding::Key(KeyCode::Space);
+
+        let conflict = bindings.bind(GameAction::Pause, space).unwrap_err();
+        assert_eq!(conflict.first, GameAction::Flap);
+        assert!(!bindings.get(GameAction::Pause).contains(&space));
+
+        // Подтверждение читается только в меню, поэтому может делить клавишу с прыжком
+        bindings
+            .bind(GameAction::Flap, InputBinding::Key(KeyCode::Enter))
+            .unwrap();
+        bindings.bind(GameAction::Flap, space).unwrap();
+        let flap = bindings.get(GameAction::Flap);
+        assert_eq!(flap.iter().filter(|&&binding| binding == space).count(), 1);
+        assert!(flap.contains(&InputBinding::Key(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn test_bindings_roundtrip() {
+        let mut bindings = InputBindings::default();
+        bindings.clear(GameAction::Flap);
+        bindings
+            .bind(GameAction::Flap, InputBinding::Key(KeyCode::ArrowUp))
+            .unwrap();
+
+        let json = serde_json::to_string(&bindings).unwrap();
+        let loaded: InputBindings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, bindings);
+        assert_eq!(InputBinding::Key(KeyCode::KeyW).label(), "W");
+    }
+
+    #[test]
+    fn test_any_connected_gamepad_flaps_and_pauses() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let first = game.connect_gamepad();
+        let second = game.connect_gamepad();
+        game.start_run();
+
+        game.tap_gamepad_button(second, GamepadButton::South);
+        assert!(bird_velocity(&mut game) > 0.0);
+
+        game.step_frames(10);
+        game.tap_gamepad_button(first, GamepadButton::RightTrigger2);
+        assert!(bird_velocity(&mut game) > 0.0);
+
+        game.tap_gamepad_button(first, GamepadButton::Start);
+        game.step();
+        assert_eq!(
+            *game.resource::<State<PauseState>>().get(),
+            PauseState::Paused
+        );
+    }
+
+    #[test]
+    fn test_disconnected_gamepad_is_ignored() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let gamepad = game.connect_gamepad();
+        assert_eq!(game.count::<Gamepad>(), 1);
+        game.disconnect_gamepad(gamepad);
+        assert_eq!(game.count::<Gamepad>(), 0);
+
+        // Повторное подключение той же сущности
+        let gamepad = game.connect_gamepad();
+        game.tap_gamepad_button(gamepad, GamepadButton::South);
+        assert!(game.step_until_state(GameState::PreGame));
+        game.tap_gamepad_button(gamepad, GamepadButton::South);
+        assert!(game.step_until_state(GameState::Playing));
+    }
+
+    #[test]
+    fn test_tap_flaps_and_confirms() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.tap_screen();
+        assert!(game.step_until_state(GameState::PreGame));
+
+        // Касание начинает забег прыжком
+        game.tap_screen();
+        assert!(game.step_until_state(GameState::Playing));
+        assert!(bird_velocity(&mut game) > 0.0);
+    }
+}
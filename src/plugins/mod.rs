@@ -1,14 +1,38 @@
+pub mod achievements;
+pub mod animation;
 pub mod asset_loader;
 pub mod audio;
 pub mod background;
 pub mod bird;
+pub mod coins;
+#[cfg(feature = "dev")]
+pub mod console;
+pub mod controls_ui;
+pub mod courses;
+pub mod daily;
+#[cfg(feature = "dev")]
+pub mod debug_overlay;
 pub mod difficulty;
+pub mod editor;
 pub mod effects;
 pub mod game_modes;
 pub mod game_over;
+pub mod ghost;
 pub mod high_score;
+pub mod hitboxes;
+pub mod input_map;
+pub mod obstacles;
+pub mod pause;
 pub mod pipes;
 pub mod powerups;
+pub mod preferences;
+pub mod pregame;
+pub mod profiles;
 pub mod progressive_difficulty;
+pub mod replay;
+pub mod rng;
 pub mod settings_ui;
+pub mod shop;
+pub mod simulation;
+pub mod skins;
 pub mod statistics;
@@ -0,0 +1,575 @@
+//! Препятствия помимо труб: плавающие мины, встречные птицы и падающие камни
+//!
+//! У каждого вида свой таймер появления и счёт, с которого он начинает
+//! встречаться. Препятствия используют общий `Collider` и `CollisionEvent`,
+//! щит спасает от удара, а замедление времени замедляет их так же, как трубы.
+//! Каждое препятствие, оставшееся позади птицы, засчитывается в статистику.
+
+#[cfg(feature = "dev")]
+use crate::plugins::console::GodMode;
+use crate::{
+    core::{
+        components::{ActiveShield, Collider},
+        difficulty_types::{DifficultyLevel, DifficultySettings},
+        resources::{ActivePowerUps, GameMode, GameModeSettings, GameScore, PlayArea},
+        utils::despawn_entities,
+    },
+    plugins::audio::{CollisionEvent, GameOverEvent},
+    plugins::bird::Bird,
+    plugins::courses::is_course_run,
+    plugins::hitboxes::Hitboxes,
+    plugins::rng::{GameRng, OBSTACLE_STREAM},
+    plugins::simulation::InterpolatedTransform,
+    plugins::skins::Skins,
+    states::game_state::{GameState, PreGameSet, SimulationSet},
+};
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+/// Размер мины вместе с шипами
+const MINE_SIZE: f32 = 36.0;
+/// Размах покачивания мины
+const MINE_BOB_AMPLITUDE: f32 = 15.0;
+/// Угловая скорость покачивания мины, рад/с
+const MINE_BOB_SPEED: f32 = 2.0;
+
+const ENEMY_BIRD_SIZE: f32 = 36.0;
+/// Скорость встречной птицы сверх скорости труб
+const ENEMY_BIRD_SPEED: f32 = 90.0;
+const ENEMY_BIRD_AMPLITUDE: f32 = 70.0;
+/// Угловая скорость волны, по которой летит встречная птица, рад/с
+const ENEMY_BIRD_WAVE_SPEED: f32 = 3.0;
+
+const ROCK_SIZE: f32 = 30.0;
+const ROCK_GRAVITY: f32 = 350.0;
+/// Скорость вращения камня в полёте, рад/с
+const ROCK_SPIN: f32 = 3.0;
+/// Скорость, с которой камень отскакивает от щита
+const ROCK_BOUNCE: Vec2 = Vec2::new(220.0, 260.0);
+
+/// Препятствие засчитывается, когда оказывается настолько левее птицы
+const DODGE_DISTANCE: f32 = 50.0;
+/// Точка появления препятствий за правым краем экрана
+const SPAWN_X: f32 = 450.0;
+const OFFSCREEN_THRESHOLD: f32 = -500.0;
+
+/// Вид препятствия
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObstacleKind {
+    Mine,
+    EnemyBird,
+    Rock,
+}
+
+impl ObstacleKind {
+    pub const ALL: [ObstacleKind; 3] = [
+        ObstacleKind::Mine,
+        ObstacleKind::EnemyBird,
+        ObstacleKind::Rock,
+    ];
+
+    /// Счёт, с которого препятствие начинает встречаться на этой сложности
+    pub fn unlock_score(self, level: DifficultyLevel) -> Option<u32> {
+        let (easy, normal, hard) = match self {
+            ObstacleKind::Mine => (Some(6), Some(3), Some(2)),
+            ObstacleKind::EnemyBird => (Some(15), Some(8), Some(5)),
+            ObstacleKind::Rock => (None, Some(12), Some(8)),
+        };
+        match level {
+            DifficultyLevel::Easy => easy,
+            DifficultyLevel::Normal | DifficultyLevel::Progressive => normal,
+            DifficultyLevel::Hard => hard,
+        }
+    }
+
+    /// Промежуток между появлениями, секунды
+    pub fn spawn_interval(self, level: DifficultyLevel) -> f32 {
+        let base = match self {
+            ObstacleKind::Mine => 7.0,
+            ObstacleKind::EnemyBird => 9.0,
+            ObstacleKind::Rock => 6.0,
+        };
+        match level {
+            DifficultyLevel::Easy => base * 1.5,
+            DifficultyLevel::Normal | DifficultyLevel::Progressive => base,
+            DifficultyLevel::Hard => base * 0.75,
+        }
+    }
+}
+
+/// Общий компонент всех препятствий
+#[derive(Component, Debug)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    /// Препятствие уже пройдено или сработало и больше не опасно
+    pub spent: bool,
+}
+
+/// Мина покачивается вокруг `base_y`
+#[derive(Component, Debug)]
+pub struct Mine {
+    pub base_y: f32,
+    pub phase: f32,
+}
+
+/// Встречная птица летит навстречу по синусоиде вокруг `base_y`
+#[derive(Component, Debug)]
+pub struct EnemyBird {
+    pub base_y: f32,
+    pub amplitude: f32,
+    pub phase: f32,
+}
+
+/// Падающий камень; `velocity` — скорость относительно труб
+#[derive(Component, Debug)]
+pub struct FallingRock {
+    pub velocity: Vec2,
+}
+
+/// Препятствие осталось позади птицы
+#[derive(Event)]
+pub struct ObstacleDodgedEvent;
+
+/// Таймеры появления для каждого вида препятствий
+#[derive(Resource)]
+pub struct ObstacleSpawner {
+    timers: [Timer; 3],
+    /// Сколько раз сработал таймер каждого вида; номер для `GameRng::spawn_stream`
+    spawned: [u64; 3],
+}
+
+impl Default for ObstacleSpawner {
+    fn default() -> Self {
+        Self::for_level(DifficultyLevel::default())
+    }
+}
+
+impl ObstacleSpawner {
+    fn for_level(level: DifficultyLevel) -> Self {
+        Self {
+            timers: ObstacleKind::ALL
+                .map(|kind| Timer::from_seconds(kind.spawn_interval(level), TimerMode::Repeating)),
+            spawned: [0; 3],
+        }
+    }
+}
+
+pub struct ObstaclesPlugin;
+
+impl Plugin for ObstaclesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObstacleSpawner>()
+            .add_event::<ObstacleDodgedEvent>()
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (despawn_entities::<Obstacle>, reset_obstacle_spawner).in_set(PreGameSet::Cleanup),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    // На трассе препятствий нет: она целиком задана автором
+                    spawn_obstacles
+                        .in_set(SimulationSet::Spawn)
+                        .run_if(not(is_course_run)),
+                    (move_mines, move_enemy_birds, move_rocks).in_set(SimulationSet::Movement),
+                    check_obstacle_collisions.in_set(SimulationSet::Collision),
+                    (count_dodged_obstacles, cleanup_offscreen_obstacles)
+                        .chain()
+                        .in_set(SimulationSet::Scoring),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_entities::<Obstacle>)
+            .add_systems(OnEnter(GameState::MainMenu), despawn_entities::<Obstacle>);
+    }
+}
+
+/// Множитель скорости с учётом замедления времени
+fn speed_multiplier(active_effects: &ActivePowerUps) -> f32 {
+    if active_effects.slow_motion_active {
+        0.3 // Замедление в 3 раза, как у труб
+    } else {
+        1.0
+    }
+}
+
+fn reset_obstacle_spawner(
+    mut spawner: ResMut<ObstacleSpawner>,
+    difficulty: Res<DifficultySettings>,
+) {
+    *spawner = ObstacleSpawner::for_level(difficulty.current_level);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_obstacles(
+    mut commands: Commands,
+    skins: Skins,
+    hitboxes: Res<Hitboxes>,
+    play_area: Res<PlayArea>,
+    time: Res<Time>,
+    mut spawner: ResMut<ObstacleSpawner>,
+    rng: Res<GameRng>,
+    difficulty: Res<DifficultySettings>,
+    score: Res<GameScore>,
+    active_effects: Res<ActivePowerUps>,
+    mode_settings: Res<GameModeSettings>,
+) {
+    // Дзен-режим остаётся спокойным
+    if mode_settings.current_mode == GameMode::Zen {
+        return;
+    }
+
+    let delta = time.delta().mul_f32(speed_multiplier(&active_effects));
+    let spawner = &mut *spawner;
+    let slots = spawner.timers.iter_mut().zip(&mut spawner.spawned);
+    for ((stream, kind), (timer, spawned)) in (OBSTACLE_STREAM..).zip(ObstacleKind::ALL).zip(slots)
+    {
+        timer.tick(delta);
+        if !timer.just_finished() {
+            continue;
+        }
+        // Номер считается и у ещё закрытых видов: так раскладка не зависит от счёта
+        let mut rng = rng.spawn_stream(stream, *spawned);
+        *spawned += 1;
+        let unlocked = kind
+            .unlock_score(difficulty.current_level)
+            .is_some_and(|unlock| score.0 >= unlock);
+        if !unlocked {
+            continue;
+        }
+
+        let half_height = play_area.height / 2.0;
+        let position = match kind {
+            ObstacleKind::Mine => Vec2::new(SPAWN_X, rng.random_range(-150.0..150.0)),
+            ObstacleKind::EnemyBird => Vec2::new(SPAWN_X, rng.random_range(-120.0..120.0)),
+            // Камень падает сверху перед птицей
+            ObstacleKind::Rock => {
+                Vec2::new(rng.random_range(120.0..380.0), half_height + ROCK_SIZE)
+            }
+        };
+        let phase = rng.random_range(0.0..TAU);
+        spawn_obstacle_at(&mut commands, &skins, &hitboxes, kind, position, phase);
+    }
+}
+
+/// Создаёт препятствие в заданной точке; `phase` задаёт начало покачивания
+pub fn spawn_obstacle_at(
+    commands: &mut Commands,
+    skins: &Skins,
+    hitboxes: &Hitboxes,
+    kind: ObstacleKind,
+    position: Vec2,
+    phase: f32,
+) -> Entity {
+    let mut obstacle = commands.spawn((
+        Obstacle { kind, spent: false },
+        Transform::from_translation(position.extend(4.0)),
+        InterpolatedTransform::default(),
+    ));
+
+    match kind {
+        ObstacleKind::Mine => {
+            obstacle
+                .insert((
+                    Mine {
+                        base_y: position.y,
+                        phase,
+                    },
+                    Sprite {
+                        color: Color::srgb(0.25, 0.25, 0.3),
+                        custom_size: Some(Vec2::splat(MINE_SIZE * 0.7)),
+                        ..default()
+                    },
+                    Collider::new(hitboxes.mine),
+                ))
+                .with_children(|mine| {
+                    // Повёрнутый квадрат за корпусом изображает шипы
+                    mine.spawn((
+                        Sprite {
+                            color: Color::srgb(0.8, 0.2, 0.2),
+                            custom_size: Some(Vec2::splat(MINE_SIZE * 0.7)),
+                            ..default()
+                        },
+                        Transform::from_xyz(0.0, 0.0, -0.1)
+                            .with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                    ));
+                });
+        }
+        ObstacleKind::EnemyBird => {
+            // Встречная птица всегда в облике по умолчанию, чтобы не путать её с игроком
+            let sprite = skins
+                .default_skin()
+                .map(|skin| skin.sprite())
+                .unwrap_or_default();
+            obstacle.insert((
+                EnemyBird {
+                    base_y: position.y,
+                    amplitude: ENEMY_BIRD_AMPLITUDE,
+                    phase,
+                },
+                Sprite {
+                    color: Color::srgb(1.0, 0.45, 0.45),
+                    custom_size: Some(Vec2::splat(ENEMY_BIRD_SIZE)),
+                    // Летит навстречу
+                    flip_x: true,
+                    ..sprite
+                },
+                Collider::new(hitboxes.enemy_bird),
+            ));
+        }
+        ObstacleKind::Rock => {
+            obstacle.insert((
+                FallingRock {
+                    velocity: Vec2::ZERO,
+                },
+                Sprite {
+                    color: Color::srgb(0.5, 0.45, 0.4),
+                    custom_size: Some(Vec2::splat(ROCK_SIZE)),
+                    ..default()
+                },
+                Collider::new(hitboxes.rock),
+            ));
+        }
+    }
+
+    obstacle.id()
+}
+
+fn move_mines(
+    mut query: Query<(&mut Transform, &mut Mine)>,
+    time: Res<Time>,
+    difficulty: Res<DifficultySettings>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let dt = time.delta_secs() * speed_multiplier(&active_effects);
+    for (mut transform, mut mine) in &mut query {
+        mine.phase = (mine.phase + MINE_BOB_SPEED * dt) % TAU;
+        transform.translation.x -= difficulty.current_params.pipe_speed * dt;
+        transform.translation.y = mine.base_y + MINE_BOB_AMPLITUDE * mine.phase.sin();
+    }
+}
+
+fn move_enemy_birds(
+    mut query: Query<(&mut Transform, &mut EnemyBird)>,
+    time: Res<Time>,
+    difficulty: Res<DifficultySettings>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let dt = time.delta_secs() * speed_multiplier(&active_effects);
+    for (mut transform, mut enemy) in &mut query {
+        enemy.phase = (enemy.phase + ENEMY_BIRD_WAVE_SPEED * dt) % TAU;
+        transform.translation.x -= (difficulty.current_params.pipe_speed + ENEMY_BIRD_SPEED) * dt;
+        transform.translation.y = enemy.base_y + enemy.amplitude * enemy.phase.sin();
+    }
+}
+
+fn move_rocks(
+    mut query: Query<(&mut Transform, &mut FallingRock)>,
+    time: Res<Time>,
+    difficulty: Res<DifficultySettings>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let dt = time.delta_secs() * speed_multiplier(&active_effects);
+    for (mut transform, mut rock) in &mut query {
+        rock.velocity.y -= ROCK_GRAVITY * dt;
+        transform.translation.x += (rock.velocity.x - difficulty.current_params.pipe_speed) * dt;
+        transform.translation.y += rock.velocity.y * dt;
+        transform.rotate_z(ROCK_SPIN * dt);
+    }
+}
+
+/// Удар о препятствие заканчивает забег, если нет щита. Щит спасает по-разному:
+/// мина взрывается и сжигает щит, встречную птицу сбивает, камень отскакивает
+#[allow(clippy::too_many_arguments)]
+fn check_obstacle_collisions(
+    mut commands: Commands,
+    bird_query: Query<(Entity, &Transform, &Collider), With<Bird>>,
+    mut obstacle_query: Query<(
+        Entity,
+        &Transform,
+        &Collider,
+        &mut Obstacle,
+        Option<&mut FallingRock>,
+    )>,
+    mut active_effects: ResMut<ActivePowerUps>,
+    mode_settings: Res<GameModeSettings>,
+    #[cfg(feature = "dev")] god_mode: Res<GodMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut collision_events: EventWriter<CollisionEvent>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+) {
+    let Ok((bird_entity, bird_transform, bird_collider)) = bird_query.get_single() else {
+        return;
+    };
+
+    for (entity, transform, collider, mut obstacle, rock) in &mut obstacle_query {
+        if obstacle.spent || !bird_collider.intersects(bird_transform, collider, transform) {
+            continue;
+        }
+
+        collision_events.send(CollisionEvent);
+        obstacle.spent = true;
+
+        let immune = active_effects.shield_active || mode_settings.current_mode == GameMode::Zen;
+        // Бессмертие из консоли переживает удар так же, как щит
+        #[cfg(feature = "dev")]
+        let immune = immune || god_mode.0;
+        if !immune {
+            game_over_events.send(GameOverEvent);
+            next_state.set(GameState::GameOver);
+            return;
+        }
+
+        match obstacle.kind {
+            ObstacleKind::Mine => {
+                commands.entity(entity).despawn_recursive();
+                commands.entity(bird_entity).remove::<ActiveShield>();
+                active_effects.shield_active = false;
+            }
+            ObstacleKind::EnemyBird => {
+                commands.entity(entity).despawn_recursive();
+            }
+            ObstacleKind::Rock => {
+                if let Some(mut rock) = rock {
+                    rock.velocity = ROCK_BOUNCE;
+                }
+            }
+        }
+    }
+}
+
+/// Засчитывает препятствия, оставшиеся позади птицы или упавшие за край экрана
+fn count_dodged_obstacles(
+    bird_query: Query<&Transform, With<Bird>>,
+    mut obstacle_query: Query<(&Transform, &mut Obstacle)>,
+    play_area: Res<PlayArea>,
+    mut dodged_events: EventWriter<ObstacleDodgedEvent>,
+) {
+    let Ok(bird_transform) = bird_query.get_single() else {
+        return;
+    };
+    let bottom = -play_area.height / 2.0;
+
+    for (transform, mut obstacle) in &mut obstacle_query {
+        if obstacle.spent {
+            continue;
+        }
+        let behind = transform.translation.x < bird_transform.translation.x - DODGE_DISTANCE;
+        let fell = transform.translation.y < bottom;
+        if behind || fell {
+            obstacle.spent = true;
+            dodged_events.send(ObstacleDodgedEvent);
+        }
+    }
+}
+
+fn cleanup_offscreen_obstacles(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), With<Obstacle>>,
+    play_area: Res<PlayArea>,
+) {
+    let bottom = -play_area.height / 2.0 - ROCK_SIZE * 2.0;
+    for (entity, transform) in &query {
+        if transform.translation.x < OFFSCREEN_THRESHOLD || transform.translation.y < bottom {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::plugins::statistics::GameStatistics;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn spawn(game: &mut HeadlessGame, kind: ObstacleKind, position: Vec2) -> Entity {
+        game.app
+            .world_mut()
+            .run_system_once(
+                move |mut commands: Commands, skins: Skins, hitboxes: Res<Hitboxes>| {
+                    spawn_obstacle_at(&mut commands, &skins, &hitboxes, kind, position, 0.0)
+                },
+            )
+            .unwrap()
+    }
+
+    fn bird_position(game: &mut HeadlessGame) -> Vec2 {
+        let world = game.app.world_mut();
+        world
+            .query_filtered::<&Transform, With<Bird>>()
+            .single(world)
+            .translation
+            .truncate()
+    }
+
+    #[test]
+    fn test_obstacles_unlock_by_difficulty_and_score() {
+        assert_eq!(ObstacleKind::Rock.unlock_score(DifficultyLevel::Easy), None);
+        for kind in ObstacleKind::ALL {
+            let hard = kind.unlock_score(DifficultyLevel::Hard).unwrap();
+            let normal = kind.unlock_score(DifficultyLevel::Normal).unwrap();
+            assert!(hard <= normal);
+            assert!(
+                kind.spawn_interval(DifficultyLevel::Hard)
+                    < kind.spawn_interval(DifficultyLevel::Easy)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hit_without_shield_ends_run() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+
+        let bird = bird_position(&mut game);
+        spawn(&mut game, ObstacleKind::EnemyBird, bird);
+        game.step_frames(2);
+
+        assert_eq!(game.game_state(), GameState::GameOver);
+    }
+
+    #[test]
+    fn test_shield_survives_mine_but_is_used_up() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+
+        let world = game.app.world_mut();
+        let bird = world.query_filtered::<Entity, With<Bird>>().single(world);
+        world.entity_mut(bird).insert(ActiveShield {
+            timer: Timer::from_seconds(8.0, TimerMode::Once),
+        });
+        game.step();
+        assert!(game.resource::<ActivePowerUps>().shield_active);
+
+        let position = bird_position(&mut game);
+        let mine = spawn(&mut game, ObstacleKind::Mine, position);
+        game.step();
+
+        assert_eq!(game.game_state(), GameState::Playing);
+        assert!(!game.app.world().entities().contains(mine));
+        assert!(!game.resource::<ActivePowerUps>().shield_active);
+    }
+
+    #[test]
+    fn test_obstacle_left_behind_counts_as_dodged() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+        let before = game.resource::<GameStatistics>().obstacles_dodged;
+
+        let bird = bird_position(&mut game);
+        spawn(
+            &mut game,
+            ObstacleKind::Mine,
+            Vec2::new(bird.x - 80.0, 250.0),
+        );
+        game.step_frames(2);
+
+        assert_eq!(
+            game.resource::<GameStatistics>().obstacles_dodged,
+            before + 1
+        );
+        assert_eq!(game.count::<Obstacle>(), 1);
+    }
+}
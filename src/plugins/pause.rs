@@ -0,0 +1,267 @@
+//! Пауза забега и меню паузы
+//!
+//! Пауза — подсостояние `PauseState` внутри `GameState::Playing`: забег не покидает
+//! `Playing`, а виртуальное время останавливается, поэтому симуляция и таймеры
+//! замирают и продолжаются с того же места.
+
+use crate::{
+    core::{resources::GameAssets, utils::despawn_entities},
+    plugins::{
+        input_map::{ActionState, GameAction},
+        replay::is_replay_playing,
+        settings_ui::spawn_menu_button,
+    },
+    states::game_state::{GameState, PauseState},
+};
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, UiRect, Val};
+use bevy::window::WindowFocused;
+
+/// Затемнение поверх замершего забега
+pub const PAUSE_BACKDROP: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+
+/// Плагин паузы
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PauseState>()
+            .add_systems(OnEnter(PauseState::Running), resume_time)
+            .add_systems(OnExit(PauseState::Running), pause_time)
+            .add_systems(OnExit(GameState::Playing), resume_time)
+            .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+            .add_systems(OnExit(PauseState::Paused), despawn_entities::<OnPauseMenu>)
+            .add_systems(
+                Update,
+                (
+                    handle_pause_actions,
+                    handle_pause_menu_buttons.run_if(in_state(PauseState::Paused)),
+                    pause_on_focus_loss.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(not(is_replay_playing)),
+            );
+    }
+}
+
+/// Маркер-компонент для меню паузы
+#[derive(Component)]
+pub struct OnPauseMenu;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct PauseRestartButton;
+
+#[derive(Component)]
+pub struct PauseSettingsButton;
+
+#[derive(Component)]
+pub struct QuitToMenuButton;
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Пауза, продолжение и рестарт с клавиатуры и геймпада
+fn handle_pause_actions(
+    actions: Res<ActionState>,
+    pause_state: Option<Res<State<PauseState>>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(pause_state) = pause_state else {
+        return;
+    };
+
+    match pause_state.get() {
+        PauseState::Running if actions.just_pressed(GameAction::Pause) => {
+            next_pause_state.set(PauseState::Paused);
+        }
+        PauseState::Paused => {
+            if actions.just_pressed(GameAction::Pause) || actions.just_pressed(GameAction::Back) {
+                next_pause_state.set(PauseState::Running);
+            } else if actions.just_pressed(GameAction::Restart) {
+                next_state.set(GameState::PreGame);
+            }
+        }
+        PauseState::Settings if actions.just_pressed(GameAction::Back) => {
+            next_pause_state.set(PauseState::Paused);
+        }
+        _ => {}
+    }
+}
+
+/// Ставит игру на паузу, когда окно теряет фокус
+fn pause_on_focus_loss(
+    mut focus_events: EventReader<WindowFocused>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if focus_events.read().any(|event| !event.focused) {
+        next_pause_state.set(PauseState::Paused);
+    }
+}
+
+fn spawn_pause_menu(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(PAUSE_BACKDROP),
+            // Поверх HUD забега
+            GlobalZIndex(10),
+            OnPauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("⏸ Пауза"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            spawn_menu_button(parent, &assets, "▶ Продолжить", ResumeButton);
+            spawn_menu_button(parent, &assets, "🔄 Заново", PauseRestartButton);
+            spawn_menu_button(parent, &assets, "⚙️ Настройки", PauseSettingsButton);
+            spawn_menu_button(parent, &assets, "🏠 В меню", QuitToMenuButton);
+        });
+}
+
+fn handle_pause_menu_buttons(
+    resume_query: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    restart_query: Query<&Interaction, (Changed<Interaction>, With<PauseRestartButton>)>,
+    settings_query: Query<&Interaction, (Changed<Interaction>, With<PauseSettingsButton>)>,
+    quit_query: Query<&Interaction, (Changed<Interaction>, With<QuitToMenuButton>)>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+
+    if resume_query.iter().any(pressed) {
+        next_pause_state.set(PauseState::Running);
+    }
+    if restart_query.iter().any(pressed) {
+        next_state.set(GameState::PreGame);
+    }
+    if settings_query.iter().any(pressed) {
+        next_pause_state.set(PauseState::Settings);
+    }
+    if quit_query.iter().any(pressed) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::PowerUp;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::core::resources::{GameMode, GameModeSettings, GameTimer};
+    use crate::plugins::bird::Bird;
+    use crate::plugins::pipes::Pipe;
+    use crate::plugins::simulation::RunClock;
+    use crate::states::game_state::SettingsPage;
+
+    fn pause_state(game: &HeadlessGame) -> Option<PauseState> {
+        game.app
+            .world()
+            .get_resource::<State<PauseState>>()
+            .map(|state| *state.get())
+    }
+
+    fn start_time_attack() -> HeadlessGame {
+        let mut game = HeadlessGameBuilder::default()
+            .with_window_size(800.0, 4000.0)
+            .build();
+        crate::plugins::game_modes::apply_game_mode(
+            &mut game.resource_mut::<GameModeSettings>(),
+            GameMode::TimeAttack,
+        );
+        game.start_run();
+        game.step_frames(10);
+        game
+    }
+
+    #[test]
+    fn test_pause_keeps_run_state() {
+        let mut game = start_time_attack();
+        game.app.world_mut().spawn(PowerUp {
+            power_type: crate::core::components::PowerUpType::Shield,
+            effect_duration: 5.0,
+        });
+        game.tap_key(KeyCode::Escape);
+        game.step();
+        assert_eq!(pause_state(&game), Some(PauseState::Paused));
+
+        let tick = game.resource::<RunClock>().tick;
+        let remaining = game.resource::<GameTimer>().remaining_time;
+        game.step_frames(30);
+        assert_eq!(game.game_state(), GameState::Playing);
+        assert_eq!(game.resource::<RunClock>().tick, tick);
+        assert_eq!(game.resource::<GameTimer>().remaining_time, remaining);
+        assert_eq!(game.count::<PowerUp>(), 1);
+        assert_eq!(game.count::<OnPauseMenu>(), 1);
+
+        game.click_button::<ResumeButton>();
+        game.step_frames(5);
+        assert_eq!(pause_state(&game), Some(PauseState::Running));
+        assert_eq!(game.count::<OnPauseMenu>(), 0);
+        assert!(game.resource::<RunClock>().tick > tick);
+        assert!(game.resource::<GameTimer>().is_active);
+    }
+
+    #[test]
+    fn test_focus_loss_pauses_and_settings_return_to_pause() {
+        let mut game = start_time_attack();
+        game.app.world_mut().send_event(WindowFocused {
+            window: Entity::PLACEHOLDER,
+            focused: false,
+        });
+        game.step_frames(2);
+        assert_eq!(pause_state(&game), Some(PauseState::Paused));
+
+        game.click_button::<PauseSettingsButton>();
+        game.step();
+        assert!(game
+            .app
+            .world()
+            .get_resource::<State<SettingsPage>>()
+            .is_some());
+
+        game.tap_key(KeyCode::Escape);
+        game.step();
+        assert_eq!(pause_state(&game), Some(PauseState::Paused));
+        assert_eq!(game.count::<Bird>(), 1);
+    }
+
+    #[test]
+    fn test_quit_to_menu_clears_run() {
+        let mut game = start_time_attack();
+        game.tap_key(KeyCode::Escape);
+        game.step();
+        game.click_button::<QuitToMenuButton>();
+
+        assert!(game.step_until_state(GameState::MainMenu));
+        assert_eq!(pause_state(&game), None);
+        assert_eq!(game.count::<Bird>(), 0);
+        assert_eq!(game.count::<Pipe>(), 0);
+        assert!(!game.resource::<Time<Virtual>>().is_paused());
+    }
+}
@@ -1,26 +1,160 @@
+//! Трубы
+//!
+//! Каждая пара труб хранит положение просвета в `PipeGap`, а верхняя и нижняя
+//! трубы расставляются по нему. Подвижные варианты (`OscillatingGap`,
+//! `BreathingGap`) меняют `PipeGap` каждый шаг симуляции, узкие (`NarrowGap`)
+//! и ворота (`BonusGate`) задаются при появлении пары. Какие варианты
+//! встречаются, зависит от сложности и текущего счёта.
+
+#[cfg(feature = "dev")]
+use crate::plugins::console::GodMode;
 use crate::{
     core::{
         components::{Collider, Scrollable},
-        difficulty_types::{DifficultyParams, DifficultySettings},
+        difficulty_types::{DifficultyLevel, DifficultyParams, DifficultySettings},
         resources::{ActivePowerUps, GameMode, GameModeSettings, *},
         utils::despawn_entities,
     },
     plugins::audio::{CollisionEvent, GameOverEvent, ScoreEvent},
     plugins::bird::Bird,
-    states::game_state::GameState,
+    plugins::courses::is_course_run,
+    plugins::hitboxes::Hitboxes,
+    plugins::rng::{GameRng, PIPE_STREAM},
+    plugins::simulation::InterpolatedTransform,
+    states::game_state::{GameState, PreGameSet, SimulationSet},
 };
 use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::f32::consts::TAU;
 
-const PIPE_WIDTH: f32 = 80.0;
+pub const PIPE_WIDTH: f32 = 80.0;
 const OFFSCREEN_THRESHOLD: f32 = -400.0; // Порог удаления труб
 
+/// Доля обычного просвета у узкой пары
+const NARROW_GAP_FACTOR: f32 = 0.75;
+/// Размах качания просвета вверх-вниз
+const OSCILLATION_AMPLITUDE: f32 = 60.0;
+/// Угловая скорость качания, рад/с
+const OSCILLATION_SPEED: f32 = 1.5;
+/// Доля обычного просвета, до которой сужается «дышащая» пара
+const BREATHING_CLOSED_FACTOR: f32 = 0.6;
+/// Угловая скорость сужения и раскрытия, рад/с
+const BREATHING_SPEED: f32 = 1.2;
+/// Бонусные очки за пролёт через ворота
+pub const BONUS_GATE_POINTS: u32 = 2;
+
 #[derive(Component)]
 pub struct Pipe;
 
+/// Верхняя труба пары
+#[derive(Component)]
+pub struct TopPipe;
+
+/// Просвет пары труб: центр и высота. Есть у обеих труб пары
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PipeGap {
+    pub center: f32,
+    pub size: f32,
+}
+
+/// Просвет качается вверх-вниз вокруг `base_center`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OscillatingGap {
+    pub base_center: f32,
+    pub amplitude: f32,
+    pub speed: f32,
+    pub phase: f32,
+}
+
+/// Просвет медленно сужается до `closed_size` и снова раскрывается
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BreathingGap {
+    pub open_size: f32,
+    pub closed_size: f32,
+    pub speed: f32,
+    pub phase: f32,
+}
+
+/// Пара с узким просветом
+#[derive(Component, Debug, Clone, Copy)]
+pub struct NarrowGap;
+
+/// Ворота: за чистый пролёт даются бонусные очки
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BonusGate {
+    pub bonus: u32,
+    /// Птица задела ворота (щитом или в дзен-режиме) — бонуса не будет
+    pub touched: bool,
+}
+
+/// Обе трубы пары ворот указывают на нижнюю: она хранит `BonusGate` и приносит очки
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PairGate(pub Entity);
+
+/// Поведение новой пары труб
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeVariant {
+    Static,
+    Oscillating,
+    Breathing,
+    Narrow,
+    Gate,
+}
+
+impl PipeVariant {
+    const SPECIAL: [PipeVariant; 4] = [
+        PipeVariant::Gate,
+        PipeVariant::Narrow,
+        PipeVariant::Oscillating,
+        PipeVariant::Breathing,
+    ];
+
+    /// Счёт, с которого вариант начинает встречаться на этой сложности
+    fn unlock_score(self, level: DifficultyLevel) -> Option<u32> {
+        let (easy, normal, hard) = match self {
+            PipeVariant::Static | PipeVariant::Gate => (Some(0), Some(0), Some(0)),
+            PipeVariant::Narrow => (Some(10), Some(5), Some(3)),
+            PipeVariant::Oscillating => (Some(20), Some(10), Some(5)),
+            PipeVariant::Breathing => (None, Some(20), Some(10)),
+        };
+        match level {
+            DifficultyLevel::Easy => easy,
+            DifficultyLevel::Normal | DifficultyLevel::Progressive => normal,
+            DifficultyLevel::Hard => hard,
+        }
+    }
+}
+
+/// Выбирает вариант пары: чем выше сложность и счёт, тем чаще необычные трубы
+pub fn choose_pipe_variant(rng: &mut impl Rng, level: DifficultyLevel, score: u32) -> PipeVariant {
+    let base_chance = match level {
+        DifficultyLevel::Easy => 0.1,
+        DifficultyLevel::Normal | DifficultyLevel::Progressive => 0.2,
+        DifficultyLevel::Hard => 0.3,
+    };
+    let chance = (base_chance + score as f32 * 0.02).min(0.6);
+    if rng.random::<f32>() >= chance {
+        return PipeVariant::Static;
+    }
+
+    let available: Vec<PipeVariant> = PipeVariant::SPECIAL
+        .into_iter()
+        .filter(|variant| {
+            variant
+                .unlock_score(level)
+                .is_some_and(|unlock| score >= unlock)
+        })
+        .collect();
+    available[rng.random_range(0..available.len())]
+}
+
 #[derive(Resource)]
 pub struct PipeSpawner {
     pub timer: Timer,
     pub last_pipe_x: f32,
+    /// Сколько пар поставлено в этом забеге
+    pub pairs: u64,
 }
 
 impl Default for PipeSpawner {
@@ -28,54 +162,88 @@ impl Default for PipeSpawner {
         Self {
             timer: Timer::from_seconds(2.0, TimerMode::Repeating),
             last_pipe_x: 400.0,
+            pairs: 0,
         }
     }
 }
 
+impl PipeSpawner {
+    /// Генератор следующей пары: её числа не зависят от предыдущих пар
+    fn next_pair_rng(&mut self, rng: &GameRng) -> StdRng {
+        let pair_rng = rng.spawn_stream(PIPE_STREAM, self.pairs);
+        self.pairs += 1;
+        pair_rng
+    }
+}
+
 pub struct PipesPlugin;
 
 impl Plugin for PipesPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PipeSpawner>()
-            .add_systems(OnEnter(GameState::PreGame), reset_and_spawn_pipes)
             .add_systems(
-                Update,
+                OnEnter(GameState::PreGame),
                 (
-                    move_pipes,
-                    check_collisions,
-                    score_system,
-                    spawn_pipes_continuously,
-                    cleanup_offscreen_pipes,
+                    despawn_entities::<Pipe>.in_set(PreGameSet::Cleanup),
+                    reset_and_spawn_pipes
+                        .in_set(PreGameSet::Spawn)
+                        .run_if(not(is_course_run)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    // На трассе трубы расставлены заранее
+                    spawn_pipes_continuously
+                        .in_set(SimulationSet::Spawn)
+                        .run_if(not(is_course_run)),
+                    (
+                        move_pipes,
+                        (oscillate_gaps, breathe_gaps),
+                        place_pipe_halves,
+                    )
+                        .chain()
+                        .in_set(SimulationSet::Movement),
+                    check_collisions.in_set(SimulationSet::Collision),
+                    (score_system, cleanup_offscreen_pipes).in_set(SimulationSet::Scoring),
                 )
                     .run_if(in_state(GameState::Playing)),
             )
             .add_systems(OnExit(GameState::GameOver), despawn_entities::<Pipe>)
+            .add_systems(OnEnter(GameState::MainMenu), despawn_entities::<Pipe>)
             .add_systems(OnExit(GameState::Playing), reset_pipe_spawner);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn reset_and_spawn_pipes(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    windows: Query<&Window>,
+    hitboxes: Res<Hitboxes>,
+    play_area: Res<PlayArea>,
     mut spawner: ResMut<PipeSpawner>,
+    rng: Res<GameRng>,
     difficulty: Res<DifficultySettings>,
+    score: Res<GameScore>,
 ) {
     // Сбрасываем состояние спавнера
     spawner.last_pipe_x = 400.0;
+    spawner.pairs = 0;
     spawner.timer.reset();
 
-    let window = windows.single();
-    let window_height = window.height();
+    let window_height = play_area.height;
 
     for i in 0..3 {
         let pipe_x = 400.0 + i as f32 * difficulty.current_params.pipe_distance;
         spawn_pipe_pair(
             &mut commands,
             &assets,
+            &hitboxes,
+            &mut spawner.next_pair_rng(&rng),
             window_height,
             pipe_x,
-            &difficulty.current_params,
+            &difficulty,
+            score.0,
         );
         spawner.last_pipe_x = pipe_x;
     }
@@ -83,72 +251,168 @@ fn reset_and_spawn_pipes(
 
 fn reset_pipe_spawner(mut spawner: ResMut<PipeSpawner>) {
     spawner.last_pipe_x = 400.0;
+    spawner.pairs = 0;
     spawner.timer.reset();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_pipe_pair(
     commands: &mut Commands,
     assets: &GameAssets,
+    hitboxes: &Hitboxes,
+    rng: &mut impl Rng,
     window_height: f32,
     pipe_x: f32,
-    params: &DifficultyParams,
+    difficulty: &DifficultySettings,
+    score: u32,
 ) {
-    let gap_y = rand::random::<f32>() * 200.0 - 100.0;
+    let params: &DifficultyParams = &difficulty.current_params;
+    let gap_y = rng.random::<f32>() * 200.0 - 100.0;
+    let variant = choose_pipe_variant(rng, difficulty.current_level, score);
+    let pipe_gap = match variant {
+        PipeVariant::Narrow => params.pipe_gap * NARROW_GAP_FACTOR,
+        _ => params.pipe_gap,
+    };
+    let pair = spawn_pipes_at(
+        commands,
+        assets,
+        hitboxes,
+        window_height,
+        pipe_x,
+        gap_y,
+        pipe_gap,
+    );
+
+    let phase = match variant {
+        PipeVariant::Oscillating | PipeVariant::Breathing => rng.random_range(0.0..TAU),
+        _ => 0.0,
+    };
+    // Очки за пару приносит нижняя труба, поэтому бонус ворот хранится на ней
+    let gate = pair[1];
+    for entity in pair {
+        let mut pipe = commands.entity(entity);
+        match variant {
+            PipeVariant::Static => {}
+            PipeVariant::Oscillating => {
+                pipe.insert(OscillatingGap {
+                    base_center: gap_y,
+                    amplitude: OSCILLATION_AMPLITUDE,
+                    speed: OSCILLATION_SPEED,
+                    phase,
+                });
+            }
+            PipeVariant::Breathing => {
+                pipe.insert(BreathingGap {
+                    open_size: pipe_gap,
+                    closed_size: pipe_gap * BREATHING_CLOSED_FACTOR,
+                    speed: BREATHING_SPEED,
+                    phase,
+                });
+            }
+            PipeVariant::Narrow => {
+                pipe.insert(NarrowGap);
+            }
+            PipeVariant::Gate => {
+                pipe.insert(PairGate(gate));
+                if entity == gate {
+                    pipe.insert(BonusGate {
+                        bonus: BONUS_GATE_POINTS,
+                        touched: false,
+                    });
+                }
+            }
+        }
+        if let Some(color) = variant_tint(variant) {
+            pipe.entry::<Sprite>()
+                .and_modify(move |mut sprite| sprite.color = color);
+        }
+    }
+}
+
+/// Подкраска необычных труб, чтобы их было видно издалека
+fn variant_tint(variant: PipeVariant) -> Option<Color> {
+    match variant {
+        PipeVariant::Static => None,
+        PipeVariant::Oscillating => Some(Color::srgb(0.6, 0.8, 1.0)),
+        PipeVariant::Breathing => Some(Color::srgb(1.0, 0.6, 0.6)),
+        PipeVariant::Narrow => Some(Color::srgb(0.7, 0.7, 0.7)),
+        PipeVariant::Gate => Some(Color::srgb(1.0, 0.85, 0.3)),
+    }
+}
+
+/// Ставит пару труб с просветом высотой `pipe_gap` вокруг `gap_y`;
+/// возвращает верхнюю и нижнюю трубы
+pub fn spawn_pipes_at(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    hitboxes: &Hitboxes,
+    window_height: f32,
+    pipe_x: f32,
+    gap_y: f32,
+    pipe_gap: f32,
+) -> [Entity; 2] {
+    let gap = PipeGap {
+        center: gap_y,
+        size: pipe_gap,
+    };
 
     // Верхняя труба
-    commands.spawn((
-        Sprite {
-            image: assets.pipe_texture.clone(),
-            custom_size: Some(Vec2::new(PIPE_WIDTH, window_height)),
-            ..default()
-        },
-        Transform {
-            translation: Vec3::new(
-                pipe_x,
-                gap_y + params.pipe_gap / 2.0 + window_height / 2.0,
-                0.0,
-            ),
-            scale: Vec3::new(1.0, -1.0, 1.0), // Переворачиваем
-            ..default()
-        },
-        Pipe,
-        Collider {
-            size: Vec2::new(PIPE_WIDTH, window_height),
-        },
-    ));
+    let top = commands
+        .spawn((
+            Sprite {
+                image: assets.pipe_texture.clone(),
+                custom_size: Some(Vec2::new(PIPE_WIDTH, window_height)),
+                ..default()
+            },
+            Transform {
+                translation: Vec3::new(pipe_x, gap_y + pipe_gap / 2.0 + window_height / 2.0, 0.0),
+                scale: Vec3::new(1.0, -1.0, 1.0), // Переворачиваем
+                ..default()
+            },
+            Pipe,
+            TopPipe,
+            gap,
+            InterpolatedTransform::default(),
+            Collider::pipe(&hitboxes.pipe, window_height),
+        ))
+        .id();
 
     // Нижняя труба
-    commands.spawn((
-        Sprite {
-            image: assets.pipe_texture.clone(),
-            custom_size: Some(Vec2::new(PIPE_WIDTH, window_height)),
-            ..default()
-        },
-        Transform {
-            translation: Vec3::new(
-                pipe_x,
-                gap_y - params.pipe_gap / 2.0 - window_height / 2.0,
-                0.0,
-            ),
-            ..default()
-        },
-        Pipe,
-        Collider {
-            size: Vec2::new(PIPE_WIDTH, window_height),
-        },
-        Scrollable,
-    ));
+    let bottom = commands
+        .spawn((
+            Sprite {
+                image: assets.pipe_texture.clone(),
+                custom_size: Some(Vec2::new(PIPE_WIDTH, window_height)),
+                ..default()
+            },
+            Transform {
+                translation: Vec3::new(pipe_x, gap_y - pipe_gap / 2.0 - window_height / 2.0, 0.0),
+                ..default()
+            },
+            Pipe,
+            gap,
+            InterpolatedTransform::default(),
+            Collider::pipe(&hitboxes.pipe, window_height),
+            Scrollable,
+        ))
+        .id();
+
+    [top, bottom]
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_pipes_continuously(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    windows: Query<&Window>,
+    hitboxes: Res<Hitboxes>,
+    play_area: Res<PlayArea>,
     time: Res<Time>,
     mut spawner: ResMut<PipeSpawner>,
+    rng: Res<GameRng>,
     difficulty: Res<DifficultySettings>,
     active_effects: Res<ActivePowerUps>,
     mode_settings: Res<GameModeSettings>,
+    score: Res<GameScore>,
 ) {
     let time_multiplier = if active_effects.slow_motion_active {
         0.3 // Замедление времени влияет на спавн
@@ -173,16 +437,18 @@ fn spawn_pipes_continuously(
     spawner.timer.tick(adjusted_delta);
 
     if spawner.timer.just_finished() {
-        let window = windows.single();
-        let window_height = window.height();
+        let window_height = play_area.height;
 
         let new_pipe_x = spawner.last_pipe_x + difficulty.current_params.pipe_distance;
         spawn_pipe_pair(
             &mut commands,
             &assets,
+            &hitboxes,
+            &mut spawner.next_pair_rng(&rng),
             window_height,
             new_pipe_x,
-            &difficulty.current_params,
+            &difficulty,
+            score.0,
         );
         spawner.last_pipe_x = new_pipe_x;
     }
@@ -217,81 +483,148 @@ fn move_pipes(
     }
 }
 
+fn oscillate_gaps(
+    mut query: Query<(&mut PipeGap, &mut OscillatingGap)>,
+    time: Res<Time>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let speed_multiplier = if active_effects.slow_motion_active {
+        0.3
+    } else {
+        1.0
+    };
+
+    for (mut gap, mut oscillation) in &mut query {
+        oscillation.phase =
+            (oscillation.phase + oscillation.speed * speed_multiplier * time.delta_secs()) % TAU;
+        gap.center = oscillation.base_center + oscillation.amplitude * oscillation.phase.sin();
+    }
+}
+
+fn breathe_gaps(
+    mut query: Query<(&mut PipeGap, &mut BreathingGap)>,
+    time: Res<Time>,
+    active_effects: Res<ActivePowerUps>,
+) {
+    let speed_multiplier = if active_effects.slow_motion_active {
+        0.3
+    } else {
+        1.0
+    };
+
+    for (mut gap, mut breathing) in &mut query {
+        breathing.phase =
+            (breathing.phase + breathing.speed * speed_multiplier * time.delta_secs()) % TAU;
+        // cos: в начале фазы просвет полностью открыт
+        let openness = 0.5 + 0.5 * breathing.phase.cos();
+        gap.size = breathing.closed_size + (breathing.open_size - breathing.closed_size) * openness;
+    }
+}
+
+/// Расставляет верхнюю и нижнюю трубы по краям просвета
+fn place_pipe_halves(
+    mut query: Query<(&mut Transform, &PipeGap, &Sprite, Has<TopPipe>), Changed<PipeGap>>,
+) {
+    for (mut transform, gap, sprite, is_top) in &mut query {
+        let length = sprite.custom_size.map_or(0.0, |size| size.y);
+        let offset = gap.size / 2.0 + length / 2.0;
+        transform.translation.y = if is_top {
+            gap.center + offset
+        } else {
+            gap.center - offset
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn check_collisions(
-    bird_query: Query<&Transform, With<Bird>>,
-    pipe_query: Query<(&Transform, Entity), With<Pipe>>,
-    collider_query: Query<&Collider>,
+    bird_query: Query<(&Transform, &Collider), With<Bird>>,
+    pipe_query: Query<(&Transform, &Collider, Option<&PairGate>), With<Pipe>>,
+    mut gate_query: Query<&mut BonusGate>,
     active_effects: Res<ActivePowerUps>,
     mode_settings: Res<GameModeSettings>,
+    #[cfg(feature = "dev")] god_mode: Res<GodMode>,
     mut next_state: ResMut<NextState<GameState>>,
     mut collision_events: EventWriter<CollisionEvent>,
     mut game_over_events: EventWriter<GameOverEvent>,
 ) {
-    if let Ok(bird_transform) = bird_query.get_single() {
-        let bird_collider = Collider {
-            size: Vec2::new(40.0, 40.0),
-        };
-
+    if let Ok((bird_transform, bird_collider)) = bird_query.get_single() {
         // Проверяем столкновения только если не в дзен режиме
-        if mode_settings.current_mode != GameMode::Zen {
-            for (pipe_transform, _pipe_entity) in &pipe_query {
-                // Получаем коллайдер для трубы
-                if let Ok(pipe_collider) = collider_query.get(_pipe_entity) {
-                    if collide(
-                        bird_transform.translation,
-                        bird_collider.size,
-                        pipe_transform.translation,
-                        pipe_collider.size,
-                    ) {
-                        // Проверяем наличие щита
-                        if active_effects.shield_active {
-                            // Щит поглощает столкновение, но создаём эффект частиц
-                            collision_events.send(CollisionEvent);
-                            // Не отправляем GameOverEvent и не меняем состояние
-                        } else {
-                            // Отправляем звуковые события
-                            collision_events.send(CollisionEvent);
-                            game_over_events.send(GameOverEvent);
-                            next_state.set(GameState::GameOver);
-                        }
-                        return;
+        let immune = mode_settings.current_mode == GameMode::Zen;
+        // Бессмертие из консоли проводит птицу сквозь трубы так же, как дзен
+        #[cfg(feature = "dev")]
+        let immune = immune || god_mode.0;
+        if !immune {
+            for (pipe_transform, pipe_collider, pair_gate) in &pipe_query {
+                if bird_collider.intersects(bird_transform, pipe_collider, pipe_transform) {
+                    // Задетые ворота больше не дают бонус
+                    touch_gate(&mut gate_query, pair_gate);
+
+                    // Проверяем наличие щита
+                    if active_effects.shield_active {
+                        // Щит поглощает столкновение, но создаём эффект частиц
+                        collision_events.send(CollisionEvent);
+                        // Не отправляем GameOverEvent и не меняем состояние
+                    } else {
+                        // Отправляем звуковые события
+                        collision_events.send(CollisionEvent);
+                        game_over_events.send(GameOverEvent);
+                        next_state.set(GameState::GameOver);
                     }
+                    return;
                 }
             }
         } else {
+            // В дзен режиме трубы не останавливают птицу, но ворота засчитываются
+            // только при чистом пролёте
+            for (pipe_transform, pipe_collider, pair_gate) in &pipe_query {
+                if bird_collider.intersects(bird_transform, pipe_collider, pipe_transform) {
+                    touch_gate(&mut gate_query, pair_gate);
+                }
+            }
+
             // В дзен режиме просто проверяем proximity для эффектов, но без GameOver
-            for (pipe_transform, _pipe_entity) in &pipe_query {
-                if let Ok(_pipe_collider) = collider_query.get(_pipe_entity) {
-                    let proximity_threshold = 50.0; // Расстояние для эффектов в дзен режиме
-                    let distance =
-                        (bird_transform.translation - pipe_transform.translation).length();
-                    if distance < proximity_threshold {
-                        collision_events.send(CollisionEvent); // только для эффектов
-                        break;
-                    }
+            for (pipe_transform, _, _) in &pipe_query {
+                let proximity_threshold = 50.0; // Расстояние для эффектов в дзен режиме
+                let distance = (bird_transform.translation - pipe_transform.translation).length();
+                if distance < proximity_threshold {
+                    collision_events.send(CollisionEvent); // только для эффектов
+                    break;
                 }
             }
         }
     }
 }
 
+/// Отмечает задетыми ворота пары, к которой относится труба
+fn touch_gate(gate_query: &mut Query<&mut BonusGate>, pair_gate: Option<&PairGate>) {
+    if let Some(mut gate) = pair_gate.and_then(|pair| gate_query.get_mut(pair.0).ok()) {
+        gate.touched = true;
+    }
+}
+
 fn score_system(
     mut score: ResMut<GameScore>,
     mut commands: Commands,
-    query: Query<(Entity, &Transform), With<Scrollable>>,
+    query: Query<(Entity, &Transform, Option<&BonusGate>), With<Scrollable>>,
     bird_query: Query<&Transform, With<Bird>>,
     mut score_events: EventWriter<ScoreEvent>,
     active_effects: Res<ActivePowerUps>,
 ) {
     if let Ok(bird_transform) = bird_query.get_single() {
-        for (entity, transform) in &query {
+        for (entity, transform, gate) in &query {
             if transform.translation.x < bird_transform.translation.x - 50.0 {
+                // Чистый пролёт через ворота добавляет бонус
+                let bonus = gate
+                    .filter(|gate| !gate.touched)
+                    .map_or(0, |gate| gate.bonus);
                 // Удваиваем очки если активен DoubleScore
-                let points = if active_effects.double_score_active {
+                let multiplier = if active_effects.double_score_active {
                     2
                 } else {
                     1
                 };
+                let points = (1 + bonus) * multiplier;
                 score.0 += points;
                 commands.entity(entity).remove::<Scrollable>();
                 score_events.send(ScoreEvent);
@@ -302,11 +635,172 @@ fn score_system(
     }
 }
 
-fn collide(a_pos: Vec3, a_size: Vec2, b_pos: Vec3, b_size: Vec2) -> bool {
-    let a_min = a_pos.truncate() - a_size / 2.0;
-    let a_max = a_pos.truncate() + a_size / 2.0;
-    let b_min = b_pos.truncate() - b_size / 2.0;
-    let b_max = b_pos.truncate() + b_size / 2.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn variants(level: DifficultyLevel, score: u32) -> Vec<PipeVariant> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..2000)
+            .map(|_| choose_pipe_variant(&mut rng, level, score))
+            .collect()
+    }
+
+    /// Оставляет в игре одну нижнюю трубу, которая ещё может принести очки
+    fn single_scoring_pipe(game: &mut HeadlessGame) -> Entity {
+        let world = game.app.world_mut();
+        let pipes: Vec<Entity> = world
+            .query_filtered::<Entity, (With<Pipe>, With<Scrollable>)>()
+            .iter(world)
+            .collect();
+        for &pipe in &pipes[1..] {
+            world.entity_mut(pipe).remove::<Scrollable>();
+        }
+        pipes[0]
+    }
+
+    #[test]
+    fn test_variants_unlock_with_score_and_difficulty() {
+        let easy = variants(DifficultyLevel::Easy, 0);
+        assert!(easy
+            .iter()
+            .all(|v| matches!(v, PipeVariant::Static | PipeVariant::Gate)));
+        assert!(easy.contains(&PipeVariant::Gate));
+
+        let hard = variants(DifficultyLevel::Hard, 30);
+        for variant in PipeVariant::SPECIAL {
+            assert!(hard.contains(&variant), "нет варианта {variant:?}");
+        }
+        let special = hard.iter().filter(|v| **v != PipeVariant::Static).count();
+        assert!(special > easy.iter().filter(|v| **v != PipeVariant::Static).count());
+
+        assert!(!variants(DifficultyLevel::Easy, 100).contains(&PipeVariant::Breathing));
+    }
+
+    #[test]
+    fn test_pipe_halves_follow_moving_gap() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.start_run();
+
+        let world = game.app.world_mut();
+        let (top, gap) = world
+            .query_filtered::<(Entity, &PipeGap), With<TopPipe>>()
+            .iter(world)
+            .map(|(entity, gap)| (entity, *gap))
+            .next()
+            .unwrap();
+        let pair: Vec<Entity> = world
+            .query::<(Entity, &PipeGap, &Transform)>()
+            .iter(world)
+            .filter(|(entity, other, transform)| {
+                *entity == top || (**other == gap && transform.translation.y < gap.center)
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        assert_eq!(pair.len(), 2);
+        // Без интерполяции `Transform` показывает ровно состояние симуляции
+        for &entity in &pair {
+            world.entity_mut(entity).remove::<InterpolatedTransform>();
+            world.entity_mut(entity).insert((
+                OscillatingGap {
+                    base_center: gap.center,
+                    amplitude: 50.0,
+                    speed: 2.0,
+                    phase: 0.0,
+                },
+                BreathingGap {
+                    open_size: gap.size,
+                    closed_size: gap.size * 0.5,
+                    speed: 2.0,
+                    phase: 0.0,
+                },
+            ));
+        }
+
+        game.step_frames(20);
 
-    a_min.x < b_max.x && a_max.x > b_min.x && a_min.y < b_max.y && a_max.y > b_min.y
+        let world = game.app.world_mut();
+        let moved = *world.get::<PipeGap>(top).unwrap();
+        assert_ne!(moved, gap);
+        assert!(moved.size < gap.size);
+        for &entity in &pair {
+            let transform = world.get::<Transform>(entity).unwrap();
+            let half = world.get::<Sprite>(entity).unwrap().custom_size.unwrap().y / 2.0;
+            let edge = moved.size / 2.0 + half;
+            let expected = if entity == top {
+                moved.center + edge
+            } else {
+                moved.center - edge
+            };
+            assert!((transform.translation.y - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_clean_gate_gives_bonus_points() {
+        for (touched, expected) in [(false, 1 + BONUS_GATE_POINTS), (true, 1)] {
+            let mut game = HeadlessGameBuilder::default().build();
+            game.start_run();
+
+            let pipe = single_scoring_pipe(&mut game);
+            let mut entity = game.app.world_mut().entity_mut(pipe);
+            entity.insert((
+                BonusGate {
+                    bonus: BONUS_GATE_POINTS,
+                    touched,
+                },
+                InterpolatedTransform::default(),
+            ));
+            entity.get_mut::<Transform>().unwrap().translation.x = -60.0;
+
+            let before = game.score();
+            game.step();
+            assert_eq!(game.score() - before, expected);
+        }
+    }
+
+    #[test]
+    fn test_hitting_a_pipe_spoils_only_its_own_gate() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.resource_mut::<GameModeSettings>().current_mode = GameMode::Zen;
+        game.start_run();
+
+        let world = game.app.world_mut();
+        let mut pipes =
+            world.query_filtered::<(Entity, &Transform, &PipeGap, Has<TopPipe>), With<Pipe>>();
+        let halves: Vec<(Entity, f32, PipeGap, bool)> = pipes
+            .iter(world)
+            .map(|(entity, transform, gap, top)| (entity, transform.translation.x, *gap, top))
+            .collect();
+        let &(top, x, gap, _) = halves.iter().find(|half| half.3).unwrap();
+        let &(bottom, ..) = halves
+            .iter()
+            .find(|half| !half.3 && (half.1 - x).abs() < 1e-3)
+            .unwrap();
+
+        // Ворота на той же x, но другой пары
+        let gate = BonusGate {
+            bonus: BONUS_GATE_POINTS,
+            touched: false,
+        };
+        let other = world.spawn((Transform::from_xyz(x, 0.0, 0.0), gate)).id();
+        world.entity_mut(top).insert(PairGate(bottom));
+        world.entity_mut(bottom).insert((gate, PairGate(bottom)));
+
+        // Птица влетает в верхнюю трубу над просветом
+        let mut birds = world.query_filtered::<Entity, With<Bird>>();
+        let bird = birds.single(world);
+        let y = gap.center + gap.size / 2.0 + 40.0;
+        world.entity_mut(bird).insert((
+            Transform::from_xyz(x, y, 0.0),
+            InterpolatedTransform::default(),
+        ));
+        game.step();
+
+        let world = game.app.world();
+        assert!(world.get::<BonusGate>(bottom).unwrap().touched);
+        assert!(!world.get::<BonusGate>(other).unwrap().touched);
+    }
 }
@@ -1,7 +1,14 @@
 use crate::core::components::{Collider, PowerUp, PowerUpIndicator, PowerUpType};
 use crate::core::resources::{ActivePowerUps, GameAssets, PowerUpSpawner};
 use crate::core::utils::despawn_entities;
-use crate::states::{app_state::AppState, game_state::GameState};
+use crate::plugins::courses::is_course_run;
+use crate::plugins::hitboxes::Hitboxes;
+use crate::plugins::rng::GameRng;
+use crate::plugins::simulation::InterpolatedTransform;
+use crate::states::{
+    app_state::AppState,
+    game_state::{GameState, PreGameSet, SimulationSet},
+};
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -27,20 +34,32 @@ impl Plugin for PowerUpsPlugin {
         app.init_resource::<PowerUpSpawner>()
             .init_resource::<ActivePowerUps>()
             .add_event::<PowerUpCollectedEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    spawn_powerups
+                        .in_set(SimulationSet::Spawn)
+                        .run_if(not(is_course_run)),
+                    update_powerups.in_set(SimulationSet::Movement),
+                    check_powerup_collection.in_set(SimulationSet::Collision),
+                    despawn_offscreen_powerups.in_set(SimulationSet::Scoring),
+                    update_power_up_timers.in_set(SimulationSet::Timers),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
             .add_systems(
                 Update,
+                (update_active_effects_state, update_power_up_ui)
+                    .run_if(in_state(AppState::Loaded).and(in_state(GameState::Playing))),
+            )
+            .add_systems(
+                OnEnter(GameState::PreGame),
                 (
-                    spawn_powerups.run_if(in_state(GameState::Playing)),
-                    update_powerups,
-                    check_powerup_collection.run_if(in_state(GameState::Playing)),
-                    update_power_up_timers.run_if(in_state(GameState::Playing)),
-                    update_active_effects_state
-                        .run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
-                    update_power_up_ui
-                        .run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
-                    despawn_offscreen_powerups,
+                    despawn_entities::<PowerUp>,
+                    reset_powerup_spawner,
+                    reset_active_powerups,
                 )
-                    .run_if(in_state(AppState::Loaded)),
+                    .in_set(PreGameSet::Cleanup),
             )
             .add_systems(OnExit(GameState::Playing), (despawn_entities::<PowerUp>,))
             .add_systems(
@@ -59,33 +78,55 @@ fn spawn_powerups(
     mut commands: Commands,
     time: Res<Time>,
     assets: Res<GameAssets>,
+    hitboxes: Res<Hitboxes>,
     mut spawner: ResMut<PowerUpSpawner>,
+    mut rng: ResMut<GameRng>,
 ) {
     spawner.timer.tick(time.delta());
 
     if spawner.timer.finished() {
-        let mut rng = rand::rng();
-
         // Выбираем случайный тип PowerUp
-        let power_type = match rng.random_range(0..3) {
+        let power_type = match rng.gameplay().random_range(0..3) {
             0 => PowerUpType::Shield,
             1 => PowerUpType::DoubleScore,
             _ => PowerUpType::SlowMotion,
         };
 
-        spawn_powerup(&mut commands, &assets, power_type);
+        spawn_powerup(&mut commands, &assets, &hitboxes, &mut rng, power_type);
 
         spawner.timer.reset();
     }
 }
 
+/// Сброс таймера появления Power-ups перед новым забегом
+fn reset_powerup_spawner(mut spawner: ResMut<PowerUpSpawner>) {
+    spawner.timer.reset();
+}
+
 /// Создание одного Power-up
-fn spawn_powerup(commands: &mut Commands, _assets: &GameAssets, power_type: PowerUpType) {
-    let (color, effect_duration) = match power_type {
-        PowerUpType::Shield => (Color::srgb(0.0, 0.8, 1.0), SHIELD_DURATION),
-        PowerUpType::DoubleScore => (Color::srgb(1.0, 0.8, 0.0), DOUBLE_SCORE_DURATION),
-        PowerUpType::SlowMotion => (Color::srgb(0.8, 0.4, 1.0), SLOW_MOTION_DURATION),
-    };
+fn spawn_powerup(
+    commands: &mut Commands,
+    _assets: &GameAssets,
+    hitboxes: &Hitboxes,
+    rng: &mut GameRng,
+    power_type: PowerUpType,
+) {
+    let position = Vec2::new(
+        450.0, // Начальная позиция за правым краем экрана
+        rng.gameplay().random_range(-200.0..200.0),
+    );
+    spawn_powerup_at(commands, hitboxes, power_type, position);
+}
+
+/// Создание Power-up в заданной точке
+pub fn spawn_powerup_at(
+    commands: &mut Commands,
+    hitboxes: &Hitboxes,
+    power_type: PowerUpType,
+    position: Vec2,
+) {
+    let color = power_up_color(power_type);
+    let effect_duration = power_up_duration(power_type);
 
     commands.spawn((
         PowerUp {
@@ -97,17 +138,30 @@ fn spawn_powerup(commands: &mut Commands, _assets: &GameAssets, power_type: Powe
             custom_size: Some(Vec2::splat(POWERUP_SIZE)),
             ..default()
         },
-        Transform::from_translation(Vec3::new(
-            450.0, // Начальная позиция за правым краем экрана
-            rand::rng().random_range(-200.0..200.0),
-            5.0, // Z-позиция между трубами и UI
-        )),
-        Collider {
-            size: Vec2::splat(POWERUP_SIZE),
-        },
+        InterpolatedTransform::default(),
+        Transform::from_translation(position.extend(5.0)), // Z-позиция между трубами и UI
+        Collider::new(hitboxes.power_up),
     ));
 }
 
+/// Сколько длится эффект Power-up
+pub fn power_up_duration(power_type: PowerUpType) -> f32 {
+    match power_type {
+        PowerUpType::Shield => SHIELD_DURATION,
+        PowerUpType::DoubleScore => DOUBLE_SCORE_DURATION,
+        PowerUpType::SlowMotion => SLOW_MOTION_DURATION,
+    }
+}
+
+/// Цвет Power-up на поле
+pub fn power_up_color(power_type: PowerUpType) -> Color {
+    match power_type {
+        PowerUpType::Shield => Color::srgb(0.0, 0.8, 1.0),
+        PowerUpType::DoubleScore => Color::srgb(1.0, 0.8, 0.0),
+        PowerUpType::SlowMotion => Color::srgb(0.8, 0.4, 1.0),
+    }
+}
+
 /// Обновление движения Power-ups
 fn update_powerups(
     time: Res<Time>,
@@ -128,17 +182,15 @@ fn update_powerups(
 /// Проверка сбора Power-ups
 fn check_powerup_collection(
     mut commands: Commands,
-    bird_query: Query<(Entity, &Transform), With<crate::core::components::Bird>>,
-    powerup_query: Query<(Entity, &Transform, &PowerUp), With<PowerUp>>,
+    bird_query: Query<(Entity, &Transform, &Collider), With<crate::core::components::Bird>>,
+    powerup_query: Query<(Entity, &Transform, &Collider, &PowerUp)>,
     assets: Res<GameAssets>,
     active_effects: Res<ActivePowerUps>,
     mut powerup_events: EventWriter<PowerUpCollectedEvent>,
 ) {
-    if let Ok((bird_entity, bird_transform)) = bird_query.get_single() {
-        for (powerup_entity, powerup_transform, powerup) in &powerup_query {
-            // Простая AABB коллизия
-            let distance = (bird_transform.translation - powerup_transform.translation).length();
-            if distance < (POWERUP_SIZE + 30.0) / 2.0 {
+    if let Ok((bird_entity, bird_transform, bird_collider)) = bird_query.get_single() {
+        for (powerup_entity, powerup_transform, powerup_collider, powerup) in &powerup_query {
+            if bird_collider.intersects(bird_transform, powerup_collider, powerup_transform) {
                 // Проверяем, не активен ли уже такой же эффект
                 let can_collect = match powerup.power_type {
                     PowerUpType::Shield => !active_effects.shield_active,
@@ -170,7 +222,7 @@ fn check_powerup_collection(
 }
 
 /// Активация визуального эффекта Power-up на птице
-fn activate_powerup_effect(
+pub(crate) fn activate_powerup_effect(
     commands: &mut Commands,
     assets: &GameAssets,
     power_type: PowerUpType,
@@ -305,6 +357,7 @@ fn update_active_effects_state(
 }
 
 /// Обновление UI индикаторов Power-ups
+#[allow(clippy::type_complexity)]
 fn update_power_up_ui(
     mut queries: ParamSet<(
         Query<(&Transform, &Children), With<crate::core::components::Bird>>,
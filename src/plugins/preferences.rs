@@ -0,0 +1,202 @@
+//! Сохранение настроек между запусками
+//!
+//! Сложность, последний режим, громкость, режим окна и призрак хранятся в
+//! `settings.json` в каталоге сохранений. Файл читается при запуске и записывается,
+//! когда закрывается экран настроек или выбран режим. Отсутствующие в файле поля
+//! получают значения по умолчанию, поэтому старые файлы продолжают читаться.
+
+use crate::core::difficulty_types::{DifficultyLevel, DifficultySettings};
+use crate::core::resources::{GameMode, GameModeSettings};
+use crate::core::save::{self, SaveData, SaveDir};
+use crate::plugins::audio::AudioSettings;
+use crate::plugins::daily::is_daily_run;
+use crate::plugins::game_modes::apply_game_mode;
+use crate::plugins::ghost::GhostSettings;
+use crate::plugins::profiles::ProfileSwitched;
+use crate::states::game_state::{GameState, SettingsOpen};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+/// Настройки окна
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisplaySettings {
+    pub fullscreen: bool,
+}
+
+/// Содержимое файла настроек
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSettings {
+    pub difficulty: DifficultyLevel,
+    pub game_mode: GameMode,
+    pub master_volume: f32,
+    pub effects_volume: f32,
+    pub fullscreen: bool,
+    pub ghost_enabled: bool,
+}
+
+impl Default for SavedSettings {
+    fn default() -> Self {
+        let audio = AudioSettings::default();
+        Self {
+            difficulty: DifficultyLevel::default(),
+            game_mode: GameMode::default(),
+            master_volume: audio.master_volume,
+            effects_volume: audio.effects_volume,
+            fullscreen: DisplaySettings::default().fullscreen,
+            ghost_enabled: GhostSettings::default().enabled,
+        }
+    }
+}
+
+impl SaveData for SavedSettings {
+    const FILE_NAME: &'static str = "settings.json";
+    const VERSION: u32 = 1;
+}
+
+/// Ресурсы, из которых собирается файл настроек
+#[derive(SystemParam)]
+pub struct SettingsResources<'w> {
+    difficulty: ResMut<'w, DifficultySettings>,
+    mode_settings: ResMut<'w, GameModeSettings>,
+    audio: ResMut<'w, AudioSettings>,
+    display: ResMut<'w, DisplaySettings>,
+    ghost: ResMut<'w, GhostSettings>,
+}
+
+impl SettingsResources<'_> {
+    pub fn capture(&self) -> SavedSettings {
+        SavedSettings {
+            difficulty: self.difficulty.current_level,
+            game_mode: self.mode_settings.current_mode,
+            master_volume: self.audio.master_volume,
+            effects_volume: self.audio.effects_volume,
+            fullscreen: self.display.fullscreen,
+            ghost_enabled: self.ghost.enabled,
+        }
+    }
+
+    pub fn apply(&mut self, saved: &SavedSettings) {
+        self.difficulty.set_level(saved.difficulty);
+        // Выбранная трасса не сохраняется, поэтому после перезапуска — классика
+        let game_mode = match saved.game_mode {
+            GameMode::Course => GameMode::Classic,
+            mode => mode,
+        };
+        apply_game_mode(&mut self.mode_settings, game_mode);
+        self.audio.master_volume = saved.master_volume.clamp(0.0, 1.0);
+        self.audio.effects_volume = saved.effects_volume.clamp(0.0, 1.0);
+        self.display.fullscreen = saved.fullscreen;
+        self.ghost.enabled = saved.ghost_enabled;
+    }
+}
+
+/// Плагин сохранения настроек
+pub struct PreferencesPlugin;
+
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DisplaySettings>()
+            .add_systems(Startup, load_settings)
+            .add_systems(Update, load_settings.run_if(on_event::<ProfileSwitched>))
+            // Режим и сложность испытания дня не должны попасть в настройки игрока
+            .add_systems(
+                OnExit(SettingsOpen),
+                save_settings.run_if(not(is_daily_run)),
+            )
+            .add_systems(
+                OnExit(GameState::GameModeSelection),
+                save_settings.run_if(not(is_daily_run)),
+            )
+            .add_systems(
+                Update,
+                apply_display_mode.run_if(resource_changed::<DisplaySettings>),
+            );
+    }
+}
+
+/// Загружает настройки активного профиля при запуске и при смене профиля
+fn load_settings(save_dir: Res<SaveDir>, mut resources: SettingsResources) {
+    let saved = save::load::<SavedSettings>(&save_dir).unwrap_or_default();
+    resources.apply(&saved);
+}
+
+/// Сохраняет текущие настройки на диск
+fn save_settings(save_dir: Res<SaveDir>, resources: SettingsResources) {
+    save::save(&save_dir, &resources.capture());
+}
+
+fn apply_display_mode(
+    display: Res<DisplaySettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let mode = if display.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    for mut window in &mut windows {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::HeadlessGameBuilder;
+    use crate::core::resources::StartStyle;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_missing_fields_fall_back_to_defaults() {
+        let saved: SavedSettings =
+            serde_json::from_str(r#"{"difficulty": "Easy", "unknown": 1}"#).unwrap();
+
+        assert_eq!(saved.difficulty, DifficultyLevel::Easy);
+        assert_eq!(
+            saved,
+            SavedSettings {
+                difficulty: DifficultyLevel::Easy,
+                ..SavedSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_saved_settings_restore_resources() {
+        let saved = SavedSettings {
+            difficulty: DifficultyLevel::Normal,
+            game_mode: GameMode::Survival,
+            master_volume: 0.5,
+            effects_volume: 0.25,
+            fullscreen: true,
+            ghost_enabled: false,
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app
+            .world_mut()
+            .run_system_once(move |mut resources: SettingsResources| {
+                let loaded: SavedSettings = serde_json::from_str(&json).unwrap();
+                resources.apply(&loaded);
+                assert_eq!(resources.capture(), loaded);
+            })
+            .unwrap();
+
+        // Режим применяется целиком, а не только его название
+        let mode_settings = game.resource::<GameModeSettings>();
+        assert_eq!(mode_settings.lives, Some(1));
+        assert_eq!(mode_settings.start_style, StartStyle::Countdown(3));
+        assert_eq!(
+            game.resource::<DifficultySettings>()
+                .current_params
+                .pipe_gap,
+            DifficultyLevel::Normal.get_params().pipe_gap
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Подготовка к забегу
+//!
+//! После входа в `GameState::PreGame` птица парит на месте, а трубы уже видны,
+//! но стоят. Забег начинается с первого прыжка или после обратного отсчёта —
+//! это задаётся `StartStyle` текущего режима.
+
+use crate::{
+    core::{
+        resources::{GameAssets, GameModeSettings, StartStyle},
+        utils::despawn_entities,
+    },
+    plugins::{
+        bird::{Bird, FlapBuffer},
+        input_map::{ActionState, GameAction},
+        replay::is_replay_playing,
+    },
+    states::game_state::{GameState, PreGameSet},
+};
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, PositionType, Val};
+
+/// Амплитуда покачивания птицы в ожидании старта
+const HOVER_AMPLITUDE: f32 = 10.0;
+/// Частота покачивания, радиан в секунду
+const HOVER_SPEED: f32 = 4.0;
+
+/// Плагин подготовки к забегу
+pub struct PreGamePlugin;
+
+impl Plugin for PreGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StartCountdown>()
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (
+                    reset_countdown,
+                    spawn_start_prompt.run_if(not(is_replay_playing)),
+                )
+                    .after(PreGameSet::Spawn),
+            )
+            .add_systems(
+                Update,
+                (
+                    start_on_flap,
+                    tick_countdown,
+                    update_start_prompt,
+                    hover_bird,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::PreGame).and(not(is_replay_playing))),
+            )
+            .add_systems(
+                Update,
+                // Повтор начинается сразу: первый прыжок уже записан в нём
+                start_replay_run.run_if(in_state(GameState::PreGame).and(is_replay_playing)),
+            )
+            .add_systems(
+                OnExit(GameState::PreGame),
+                despawn_entities::<OnStartPrompt>,
+            );
+    }
+}
+
+/// Оставшееся время обратного отсчёта; `None` — ждём прыжка
+#[derive(Resource, Default)]
+pub struct StartCountdown {
+    remaining: Option<f32>,
+}
+
+/// Маркер-компонент для подсказки перед стартом
+#[derive(Component)]
+pub struct OnStartPrompt;
+
+/// Текст подсказки или отсчёта
+#[derive(Component)]
+pub struct StartPromptText;
+
+fn reset_countdown(mode_settings: Res<GameModeSettings>, mut countdown: ResMut<StartCountdown>) {
+    countdown.remaining = match mode_settings.start_style {
+        StartStyle::TapToStart => None,
+        StartStyle::Countdown(seconds) => Some(seconds as f32),
+    };
+}
+
+fn spawn_start_prompt(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnStartPrompt,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                StartPromptText,
+            ));
+        });
+}
+
+/// Первый прыжок начинает забег и сразу же срабатывает
+fn start_on_flap(
+    actions: Res<ActionState>,
+    countdown: Res<StartCountdown>,
+    mut flap: ResMut<FlapBuffer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if countdown.remaining.is_none() && actions.just_pressed(GameAction::Flap) {
+        flap.pending = true;
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn tick_countdown(
+    time: Res<Time>,
+    mut countdown: ResMut<StartCountdown>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(remaining) = countdown.remaining.as_mut() {
+        *remaining -= time.delta_secs();
+        if *remaining <= 0.0 {
+            next_state.set(GameState::Playing);
+        }
+    }
+}
+
+fn update_start_prompt(
+    countdown: Res<StartCountdown>,
+    mut query: Query<&mut Text, With<StartPromptText>>,
+) {
+    let label = match countdown.remaining {
+        None => "Нажмите, чтобы начать".to_string(),
+        Some(remaining) => format!("{}", remaining.ceil().max(1.0) as u32),
+    };
+    for mut text in &mut query {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+/// Покачивает птицу только для отрисовки: перед шагами симуляции
+/// `InterpolatedTransform` возвращает её на стартовую высоту
+fn hover_bird(time: Res<Time>, mut query: Query<&mut Transform, With<Bird>>) {
+    for mut transform in &mut query {
+        transform.translation.y = (time.elapsed_secs() * HOVER_SPEED).sin() * HOVER_AMPLITUDE;
+    }
+}
+
+fn start_replay_run(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::StartButton;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::core::resources::GameMode;
+    use crate::plugins::pipes::Pipe;
+    use crate::plugins::simulation::RunClock;
+
+    fn enter_pregame(mode: GameMode) -> HeadlessGame {
+        let mut game = HeadlessGameBuilder::default().build();
+        crate::plugins::game_modes::apply_game_mode(
+            &mut game.resource_mut::<GameModeSettings>(),
+            mode,
+        );
+        game.click_button::<StartButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        game
+    }
+
+    fn pipe_positions(game: &mut HeadlessGame) -> Vec<f32> {
+        let world = game.app.world_mut();
+        world
+            .query_filtered::<&Transform, With<Pipe>>()
+            .iter(world)
+            .map(|transform| transform.translation.x)
+            .collect()
+    }
+
+    #[test]
+    fn test_tap_to_start_waits_for_first_flap() {
+        let mut game = enter_pregame(GameMode::Classic);
+        let pipes = pipe_positions(&mut game);
+        game.step_frames(120);
+
+        assert_eq!(game.game_state(), GameState::PreGame);
+        assert_eq!(game.count::<Bird>(), 1);
+        assert_eq!(game.count::<OnStartPrompt>(), 1);
+        assert_eq!(pipe_positions(&mut game), pipes);
+        assert_eq!(game.resource::<RunClock>().tick, 0);
+
+        game.tap_key(KeyCode::Space);
+        game.step();
+        assert_eq!(game.game_state(), GameState::Playing);
+        assert_eq!(game.count::<OnStartPrompt>(), 0);
+
+        // Стартовый прыжок сработал: птица поднимается, а не падает
+        let world = game.app.world_mut();
+        let bird = world.query::<&Bird>().single(world);
+        assert!(bird.velocity > 0.0);
+    }
+
+    #[test]
+    fn test_countdown_starts_run_on_its_own() {
+        let mut game = enter_pregame(GameMode::TimeAttack);
+        game.step_frames(120);
+        assert_eq!(game.game_state(), GameState::PreGame);
+
+        // Прыжок не сокращает отсчёт
+        game.tap_key(KeyCode::Space);
+        assert_eq!(game.game_state(), GameState::PreGame);
+
+        game.step_frames(70);
+        assert_eq!(game.game_state(), GameState::Playing);
+    }
+}
@@ -0,0 +1,731 @@
+//! Профили игроков
+//!
+//! У каждого профиля свои рекорды, статистика, настройки, привязки и повторы:
+//! они лежат в `profiles/<id>` каталога сохранений, а список профилей — в
+//! `profiles.json` в корне. Данные, сохранённые до появления профилей,
+//! переносятся в профиль по умолчанию при первом запуске.
+//!
+//! При смене профиля отправляется `ProfileSwitched`, и плагины перечитывают
+//! свои файлы из каталога нового профиля.
+
+use crate::{
+    core::{
+        resources::{GameAssets, HighScores},
+        save::{self, SaveData, SaveDir},
+        utils::despawn_entities,
+    },
+    plugins::{
+        input_map::{ActionState, InputBindings},
+        preferences::SavedSettings,
+        settings_ui::spawn_menu_button,
+        statistics::GameStatistics,
+    },
+    states::game_state::GameState,
+};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, UiRect, Val};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Профиль, в который попадают данные, сохранённые до появления профилей
+pub const DEFAULT_PROFILE_ID: &str = "default";
+/// Максимальная длина имени профиля
+const MAX_NAME_LEN: usize = 16;
+
+/// Профиль игрока
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Имя каталога профиля
+    pub id: String,
+    pub name: String,
+}
+
+/// Список профилей и активный профиль
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profiles {
+    profiles: Vec<Profile>,
+    active: String,
+    /// Номер для id следующего профиля
+    next_id: u32,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Игрок".to_string(),
+            }],
+            active: DEFAULT_PROFILE_ID.to_string(),
+            next_id: 1,
+        }
+    }
+}
+
+impl SaveData for Profiles {
+    const FILE_NAME: &'static str = "profiles.json";
+    const VERSION: u32 = 1;
+}
+
+impl Profiles {
+    pub fn list(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    /// Активный профиль
+    pub fn active(&self) -> &Profile {
+        self.get(&self.active)
+            .or(self.profiles.first())
+            .expect("список профилей не бывает пустым")
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    /// Создаёт профиль и возвращает его id
+    pub fn create(&mut self, name: &str) -> String {
+        let id = format!("profile_{}", self.next_id);
+        self.next_id += 1;
+        self.profiles.push(Profile {
+            id: id.clone(),
+            name: clean_name(name).unwrap_or_else(|| "Игрок".to_string()),
+        });
+        id
+    }
+
+    /// Переименовывает профиль. Пустое имя не принимается
+    pub fn rename(&mut self, id: &str, name: &str) -> bool {
+        let Some(name) = clean_name(name) else {
+            return false;
+        };
+        match self.profiles.iter_mut().find(|profile| profile.id == id) {
+            Some(profile) => {
+                profile.name = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Проверяет, можно ли удалить профиль. Активный профиль удалить нельзя
+    pub fn can_remove(&self, id: &str) -> Result<(), &'static str> {
+        if id == self.active().id {
+            return Err("Нельзя удалить активный профиль");
+        }
+        if self.get(id).is_none() {
+            return Err("Профиль не найден");
+        }
+        Ok(())
+    }
+
+    /// Удаляет профиль
+    pub fn remove(&mut self, id: &str) -> Result<(), &'static str> {
+        self.can_remove(id)?;
+        self.profiles.retain(|profile| profile.id != id);
+        Ok(())
+    }
+
+    /// Делает профиль активным
+    pub fn select(&mut self, id: &str) -> bool {
+        let exists = self.get(id).is_some();
+        if exists {
+            self.active = id.to_string();
+        }
+        exists
+    }
+}
+
+fn clean_name(name: &str) -> Option<String> {
+    let name: String = name.trim().chars().take(MAX_NAME_LEN).collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Активный профиль сменился, данные нужно перечитать
+#[derive(Event)]
+pub struct ProfileSwitched;
+
+/// Состояние экрана профилей
+#[derive(Resource, Default)]
+pub struct ProfileEditor {
+    /// Профиль, имя которого сейчас набирается
+    renaming: Option<String>,
+    buffer: String,
+    message: Option<String>,
+    /// Профиль, удаление которого ждёт повторного нажатия
+    confirm_delete: Option<String>,
+}
+
+/// Плагин профилей
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profiles>()
+            .init_resource::<ProfileEditor>()
+            .add_event::<ProfileSwitched>()
+            // Раньше всех загрузок: они читают каталог активного профиля
+            .add_systems(PreStartup, load_profiles)
+            .add_systems(
+                Update,
+                switch_profile
+                    .run_if(resource_changed::<Profiles>.and(not(resource_added::<Profiles>))),
+            )
+            .add_systems(OnEnter(GameState::Profiles), spawn_profiles_screen)
+            .add_systems(
+                Update,
+                (
+                    type_profile_name,
+                    handle_profile_buttons,
+                    rebuild_profile_rows
+                        .run_if(resource_changed::<Profiles>.or(resource_changed::<ProfileEditor>)),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Profiles)),
+            )
+            .add_systems(
+                OnExit(GameState::Profiles),
+                (despawn_entities::<OnProfilesScreen>, stop_renaming),
+            );
+    }
+}
+
+/// Читает список профилей; при первом запуске переносит старые сохранения
+/// в профиль по умолчанию
+fn load_profiles(mut save_dir: ResMut<SaveDir>, mut profiles: ResMut<Profiles>) {
+    if let Some(path) = save_dir.shared_path(Profiles::FILE_NAME) {
+        if path.exists() {
+            if let Some(loaded) = save::load_from::<Profiles>(&path) {
+                *profiles = loaded;
+            }
+        } else {
+            migrate_legacy_layout(&save_dir);
+            save::save_to(&path, &*profiles);
+        }
+    }
+    save_dir.profile = Some(profiles.active().id.clone());
+}
+
+/// Переносит файлы из корня каталога сохранений в профиль по умолчанию
+fn migrate_legacy_layout(save_dir: &SaveDir) {
+    let names = [
+        HighScores::FILE_NAME,
+        GameStatistics::FILE_NAME,
+        SavedSettings::FILE_NAME,
+        InputBindings::FILE_NAME,
+        "replays",
+    ];
+    for name in names {
+        let (Some(from), Some(to)) = (
+            save_dir.shared_path(name),
+            save_dir.profile_path(DEFAULT_PROFILE_ID, name),
+        ) else {
+            continue;
+        };
+        if !from.exists() || to.exists() {
+            continue;
+        }
+        let moved = to
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::rename(&from, &to));
+        match moved {
+            Ok(()) => println!("Перенесено в профиль по умолчанию: {}", name),
+            Err(e) => eprintln!("Ошибка переноса {}: {}", from.display(), e),
+        }
+    }
+}
+
+/// Сохраняет список профилей и переключает каталог, если сменился активный профиль
+fn switch_profile(
+    profiles: Res<Profiles>,
+    mut save_dir: ResMut<SaveDir>,
+    mut switched: EventWriter<ProfileSwitched>,
+) {
+    if let Some(path) = save_dir.shared_path(Profiles::FILE_NAME) {
+        save::save_to(&path, &*profiles);
+    }
+
+    let active = &profiles.active().id;
+    if save_dir.profile.as_ref() != Some(active) {
+        save_dir.profile = Some(active.clone());
+        switched.send(ProfileSwitched);
+    }
+}
+
+#[derive(Component)]
+pub struct OnProfilesScreen;
+
+/// Маркер контейнера со строками профилей
+#[derive(Component)]
+pub struct ProfileRows;
+
+/// Кнопка выбора профиля
+#[derive(Component)]
+pub struct SelectProfileButton(pub String);
+
+/// Кнопка переименования профиля
+#[derive(Component)]
+pub struct RenameProfileButton(pub String);
+
+/// Кнопка удаления профиля
+#[derive(Component)]
+pub struct DeleteProfileButton(pub String);
+
+/// Кнопка создания профиля
+#[derive(Component)]
+pub struct CreateProfileButton;
+
+/// Кнопка возврата в главное меню
+#[derive(Component)]
+pub struct ProfilesBackButton;
+
+/// Строка подсказок под списком
+#[derive(Component)]
+pub struct ProfilesStatusText;
+
+fn spawn_profiles_screen(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnProfilesScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("👤 Профили"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            parent.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ProfileRows,
+            ));
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                Node {
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..default()
+                },
+                ProfilesStatusText,
+            ));
+
+            spawn_menu_button(parent, &assets, "➕ Новый профиль", CreateProfileButton);
+            spawn_menu_button(parent, &assets, "🔙 Назад в меню", ProfilesBackButton);
+        });
+}
+
+/// Пересоздаёт строки профилей и подсказку после любых изменений
+fn rebuild_profile_rows(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    profiles: Res<Profiles>,
+    editor: Res<ProfileEditor>,
+    rows_query: Query<Entity, With<ProfileRows>>,
+    mut status_query: Query<&mut Text, With<ProfilesStatusText>>,
+) {
+    let text_font = TextFont {
+        font: assets.font.clone(),
+        font_size: 22.0,
+        ..default()
+    };
+
+    for rows in &rows_query {
+        commands.entity(rows).despawn_descendants();
+        commands.entity(rows).with_children(|parent| {
+            for profile in profiles.list() {
+                let is_active = profile.id == profiles.active().id;
+                let name = if editor.renaming.as_ref() == Some(&profile.id) {
+                    format!("{}_", editor.buffer)
+                } else if is_active {
+                    format!("• {}", profile.name)
+                } else {
+                    profile.name.clone()
+                };
+
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::vertical(Val::Px(4.0)),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        let color = if is_active {
+                            Color::srgb(0.2, 0.6, 0.2)
+                        } else {
+                            Color::srgb(0.2, 0.2, 0.2)
+                        };
+                        spawn_row_button(
+                            row,
+                            text_font.clone(),
+                            &name,
+                            260.0,
+                            color,
+                            SelectProfileButton(profile.id.clone()),
+                        );
+                        spawn_row_button(
+                            row,
+                            text_font.clone(),
+                            "✎",
+                            40.0,
+                            Color::srgb(0.2, 0.2, 0.2),
+                            RenameProfileButton(profile.id.clone()),
+                        );
+                        // Первое нажатие только спрашивает подтверждение
+                        let (label, width, color) =
+                            if editor.confirm_delete.as_ref() == Some(&profile.id) {
+                                ("Удалить?", 120.0, Color::srgb(0.7, 0.15, 0.15))
+                            } else {
+                                ("✕", 40.0, Color::srgb(0.2, 0.2, 0.2))
+                            };
+                        spawn_row_button(
+                            row,
+                            text_font.clone(),
+                            label,
+                            width,
+                            color,
+                            DeleteProfileButton(profile.id.clone()),
+                        );
+                    });
+            }
+        });
+    }
+
+    let status = if editor.renaming.is_some() {
+        "Введите имя и нажмите Enter (Esc — отмена)".to_string()
+    } else {
+        editor.message.clone().unwrap_or_default()
+    };
+    for mut text in &mut status_query {
+        if **text != status {
+            **text = status.clone();
+        }
+    }
+}
+
+fn spawn_row_button(
+    parent: &mut ChildBuilder,
+    text_font: TextFont,
+    text: &str,
+    width: f32,
+    color: Color,
+    button_component: impl Component,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(40.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                margin: UiRect::left(Val::Px(8.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(color),
+            button_component,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(text), text_font, TextColor(Color::WHITE)));
+        });
+}
+
+fn start_renaming(editor: &mut ProfileEditor, actions: &mut ActionState, profile: &Profile) {
+    editor.renaming = Some(profile.id.clone());
+    editor.buffer = profile.name.clone();
+    editor.message = None;
+    // Пока набирается имя, пробел и Esc — это текст, а не действия
+    actions.suspended = true;
+}
+
+/// Набор имени профиля: символы, Backspace, Enter — готово, Esc — отмена
+fn type_profile_name(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut editor: ResMut<ProfileEditor>,
+    mut actions: ResMut<ActionState>,
+    mut profiles: ResMut<Profiles>,
+) {
+    let Some(id) = editor.renaming.clone() else {
+        keyboard_events.clear();
+        return;
+    };
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match (&event.key_code, &event.logical_key) {
+            (KeyCode::Enter | KeyCode::NumpadEnter, _) => {
+                if profiles.rename(&id, &editor.buffer) {
+                    editor.renaming = None;
+                    actions.suspended = false;
+                } else {
+                    editor.message = Some("Имя не может быть пустым".to_string());
+                }
+                return;
+            }
+            (KeyCode::Escape, _) => {
+                editor.renaming = None;
+                actions.suspended = false;
+                return;
+            }
+            (KeyCode::Backspace, _) => {
+                editor.buffer.pop();
+            }
+            (_, Key::Character(text)) if editor.buffer.chars().count() < MAX_NAME_LEN => {
+                editor.buffer.push_str(text);
+            }
+            (_, Key::Space) if editor.buffer.chars().count() < MAX_NAME_LEN => {
+                editor.buffer.push(' ');
+            }
+            _ => {}
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_profile_buttons(
+    select_query: Query<(&Interaction, &SelectProfileButton), Changed<Interaction>>,
+    rename_query: Query<(&Interaction, &RenameProfileButton), Changed<Interaction>>,
+    delete_query: Query<(&Interaction, &DeleteProfileButton), Changed<Interaction>>,
+    create_query: Query<&Interaction, (Changed<Interaction>, With<CreateProfileButton>)>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<ProfilesBackButton>)>,
+    save_dir: Res<SaveDir>,
+    mut profiles: ResMut<Profiles>,
+    mut editor: ResMut<ProfileEditor>,
+    mut actions: ResMut<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+
+    // Любая другая кнопка отменяет ожидающее подтверждение удаления
+    let other_pressed = select_query.iter().any(|(i, _)| pressed(i))
+        || rename_query.iter().any(|(i, _)| pressed(i))
+        || create_query.iter().any(pressed)
+        || back_query.iter().any(pressed);
+    if other_pressed && editor.confirm_delete.is_some() {
+        editor.confirm_delete = None;
+        editor.message = None;
+    }
+
+    for (_, SelectProfileButton(id)) in select_query.iter().filter(|(i, _)| pressed(i)) {
+        if *id != profiles.active().id {
+            profiles.select(id);
+            editor.message = None;
+        }
+    }
+
+    for (_, RenameProfileButton(id)) in rename_query.iter().filter(|(i, _)| pressed(i)) {
+        if let Some(profile) = profiles.get(id).cloned() {
+            start_renaming(&mut editor, &mut actions, &profile);
+        }
+    }
+
+    for (_, DeleteProfileButton(id)) in delete_query.iter().filter(|(i, _)| pressed(i)) {
+        if let Err(message) = profiles.can_remove(id) {
+            editor.message = Some(message.to_string());
+            continue;
+        }
+        if editor.confirm_delete.as_ref() != Some(id) {
+            let name = profiles.get(id).map_or("", |profile| profile.name.as_str());
+            editor.message = Some(format!(
+                "Рекорды, статистика и покупки «{}» будут удалены. Нажмите «Удалить?» ещё раз",
+                name
+            ));
+            editor.confirm_delete = Some(id.clone());
+            continue;
+        }
+
+        editor.confirm_delete = None;
+        match profiles.remove(id) {
+            Ok(()) => {
+                editor.message = None;
+                if let Some(dir) = save_dir.profile_path(id, "") {
+                    if dir.exists() {
+                        if let Err(e) = fs::remove_dir_all(&dir) {
+                            eprintln!("Ошибка удаления профиля {}: {}", dir.display(), e);
+                        }
+                    }
+                }
+            }
+            Err(message) => editor.message = Some(message.to_string()),
+        }
+    }
+
+    if create_query.iter().any(pressed) {
+        let name = format!("Игрок {}", profiles.list().len() + 1);
+        let id = profiles.create(&name);
+        if let Some(profile) = profiles.get(&id).cloned() {
+            start_renaming(&mut editor, &mut actions, &profile);
+        }
+    }
+
+    if back_query.iter().any(pressed) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+fn stop_renaming(mut editor: ResMut<ProfileEditor>, mut actions: ResMut<ActionState>) {
+    *editor = ProfileEditor::default();
+    actions.suspended = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder, TempSaveDir};
+
+    #[test]
+    fn test_profiles_create_rename_remove() {
+        let mut profiles = Profiles::default();
+        let id = profiles.create("  Аня  ");
+        assert_eq!(profiles.get(&id).unwrap().name, "Аня");
+        assert!(!profiles.rename(&id, "   "));
+        assert!(profiles.rename(&id, "Борис"));
+
+        assert!(profiles.remove(DEFAULT_PROFILE_ID).is_err());
+        assert!(profiles.select(&id));
+        assert!(profiles.remove(DEFAULT_PROFILE_ID).is_ok());
+        assert_eq!(profiles.list().len(), 1);
+        assert_eq!(profiles.active().name, "Борис");
+    }
+
+    #[test]
+    fn test_existing_saves_move_to_default_profile() {
+        let temp = TempSaveDir::new("profiles_migrate");
+        let root = temp.path();
+        fs::write(root.join("high_scores.json"), "{}").unwrap();
+        fs::create_dir_all(root.join("replays")).unwrap();
+        fs::write(root.join("replays").join("replay_1.json"), "{}").unwrap();
+
+        migrate_legacy_layout(&temp.save_dir());
+        let default_dir = root.join("profiles").join(DEFAULT_PROFILE_ID);
+        let moved_scores = default_dir.join("high_scores.json").exists();
+        let moved_replay = default_dir.join("replays").join("replay_1.json").exists();
+        let left_in_root = root.join("high_scores.json").exists();
+
+        assert!(moved_scores);
+        assert!(moved_replay);
+        assert!(!left_in_root);
+    }
+
+    fn total_games(game: &HeadlessGame) -> u32 {
+        game.resource::<GameStatistics>().total_games
+    }
+
+    #[test]
+    fn test_switching_profile_reloads_player_data() {
+        let temp = TempSaveDir::new("profiles_switch");
+        let mut game = HeadlessGameBuilder::default().build();
+        let mut save_dir = temp.save_dir();
+        save_dir.profile = Some(DEFAULT_PROFILE_ID.to_string());
+        game.resource_mut::<GameStatistics>().total_games = 5;
+        save::save(&save_dir, game.resource::<GameStatistics>());
+        game.app.insert_resource(save_dir);
+
+        let second = game.resource_mut::<Profiles>().create("Второй");
+        game.resource_mut::<Profiles>().select(&second);
+        game.step_frames(2);
+        let second_games = total_games(&game);
+
+        game.resource_mut::<Profiles>().select(DEFAULT_PROFILE_ID);
+        game.step_frames(2);
+        let default_games = total_games(&game);
+        let list_saved = temp.path().join("profiles.json").exists();
+
+        assert_eq!(second_games, 0);
+        assert_eq!(default_games, 5);
+        assert!(list_saved);
+    }
+
+    #[test]
+    fn test_create_and_name_profile_from_menu() {
+        let mut game = HeadlessGameBuilder::default().build();
+        game.click_button::<crate::core::components::ProfilesButton>();
+        assert!(game.step_until_state(GameState::Profiles));
+
+        game.click_button::<CreateProfileButton>();
+        game.step();
+        game.tap_key(KeyCode::Backspace);
+        game.type_text("Ян");
+        game.tap_key(KeyCode::Enter);
+        game.step();
+
+        let profiles = game.resource::<Profiles>();
+        assert_eq!(profiles.list().len(), 2);
+        assert_eq!(profiles.list()[1].name, "Игрок Ян");
+        // Новый профиль не становится активным сам по себе
+        assert_eq!(profiles.active().id, DEFAULT_PROFILE_ID);
+        assert_eq!(game.game_state(), GameState::Profiles);
+    }
+
+    fn press_delete(game: &mut HeadlessGame, id: &str) {
+        let world = game.app.world_mut();
+        let mut buttons = world.query::<(&mut Interaction, &DeleteProfileButton)>();
+        for (mut interaction, button) in buttons.iter_mut(world) {
+            if button.0 == id {
+                *interaction = Interaction::Pressed;
+            }
+        }
+        game.step();
+        game.step();
+    }
+
+    #[test]
+    fn test_delete_profile_needs_confirmation() {
+        let temp = TempSaveDir::new("profiles_delete");
+        let mut game = HeadlessGameBuilder::default().build();
+        let mut save_dir = temp.save_dir();
+        save_dir.profile = Some(DEFAULT_PROFILE_ID.to_string());
+        game.app.insert_resource(save_dir.clone());
+        let second = game.resource_mut::<Profiles>().create("Второй");
+        let second_dir = save_dir.profile_path(&second, "").unwrap();
+        fs::create_dir_all(&second_dir).unwrap();
+
+        game.click_button::<crate::core::components::ProfilesButton>();
+        assert!(game.step_until_state(GameState::Profiles));
+        game.step();
+
+        press_delete(&mut game, &second);
+        let kept_after_first_click = game.resource::<Profiles>().get(&second).is_some();
+        let dir_kept = second_dir.exists();
+        press_delete(&mut game, &second);
+        let removed = game.resource::<Profiles>().get(&second).is_none();
+        let dir_removed = !second_dir.exists();
+
+        assert!(kept_after_first_click);
+        assert!(dir_kept);
+        assert!(removed);
+        assert!(dir_removed);
+    }
+}
@@ -4,8 +4,9 @@
 /// в режиме ProgressiveDifficulty, где параметры игры
 /// постепенно усложняются каждые 10 очков.
 use crate::{
-    core::difficulty_types::DifficultySettings, core::resources::GameScore,
-    states::game_state::GameState,
+    core::difficulty_types::DifficultySettings,
+    core::resources::GameScore,
+    states::game_state::{GameState, PreGameSet, SimulationSet},
 };
 use bevy::prelude::*;
 
@@ -15,12 +16,24 @@ pub struct ProgressiveDifficultyPlugin;
 impl Plugin for ProgressiveDifficultyPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
-            update_progressive_difficulty.run_if(in_state(GameState::Playing)),
+            OnEnter(GameState::PreGame),
+            reset_difficulty_params.in_set(PreGameSet::Cleanup),
+        )
+        .add_systems(
+            FixedUpdate,
+            update_progressive_difficulty
+                .in_set(SimulationSet::Timers)
+                .run_if(in_state(GameState::Playing)),
         );
     }
 }
 
+/// Возвращает параметры сложности к начальным перед новым забегом
+fn reset_difficulty_params(mut difficulty_settings: ResMut<DifficultySettings>) {
+    let level = difficulty_settings.current_level;
+    difficulty_settings.set_level(level);
+}
+
 /// Система обновления прогрессивной сложности
 fn update_progressive_difficulty(
     mut difficulty_settings: ResMut<DifficultySettings>,
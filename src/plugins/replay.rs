@@ -0,0 +1,707 @@
+//! Запись и воспроизведение забегов
+//!
+//! Каждый забег записывается как seed, режим, сложность и список шагов симуляции,
+//! на которых птица прыгала. Повтор проигрывается через те же системы, что и
+//! обычная игра: подменяется только источник прыжков. Поэтому повтор помнит
+//! трассу и хитбоксы, с которыми шёл забег, и с другими не запускается.
+
+use crate::core::components::WatchReplayButton;
+use crate::core::difficulty_types::{DifficultyLevel, DifficultySettings};
+use crate::core::resources::{is_cheated_run, GameAssets, GameMode, GameModeSettings, GameScore};
+use crate::core::save::{write_atomic, SaveDir};
+use crate::core::utils::arg_value;
+use crate::plugins::audio::JumpEvent;
+use crate::plugins::bird::FlapBuffer;
+use crate::plugins::courses::{find_course, ActiveCourse, Course, CourseLibrary, SelectedCourse};
+use crate::plugins::editor::is_editor_playtest;
+use crate::plugins::game_modes::apply_game_mode;
+use crate::plugins::hitboxes::{Hitboxes, NextRunHitboxes};
+use crate::plugins::input_map::{ActionState, GameAction};
+use crate::plugins::rng::GameRng;
+use crate::plugins::simulation::{RunClock, SIMULATION_HZ};
+use crate::states::{
+    app_state::AppState,
+    game_state::{GameOverSet, GameState, PreGameSet, SimulationSet},
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Версия формата файла повтора. Поднимается и тогда, когда формат прежний,
+/// но старые записи уже не воспроизводятся: 4 — хитбоксы стали задаваться в данных,
+/// 5 — у каждой пары труб и препятствия свой генератор
+pub const REPLAY_VERSION: u32 = 5;
+/// Шаг перемотки стрелками
+const REPLAY_SEEK_SECONDS: f64 = 5.0;
+/// Скорость времени во время перемотки
+const REPLAY_SEEK_SPEED: f32 = 8.0;
+/// Клавиши выбора скорости воспроизведения
+const REPLAY_SPEEDS: [(KeyCode, f32); 3] = [
+    (KeyCode::Digit1, 0.5),
+    (KeyCode::Digit2, 1.0),
+    (KeyCode::Digit3, 2.0),
+];
+
+/// Запись одного забега
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub mode: GameMode,
+    pub difficulty: DifficultyLevel,
+    /// Хэш раскладки трассы, если забег шёл в режиме «Трасса»
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub course: Option<u64>,
+    /// Хэш хитбоксов, с которыми шёл забег
+    #[serde(default)]
+    pub hitboxes: u64,
+    pub date: String,
+    pub score: u32,
+    /// Шаг симуляции, на котором забег закончился
+    pub final_tick: u32,
+    /// Шаги симуляции, на которых был прыжок
+    pub flaps: Vec<u32>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, mode: GameMode, difficulty: DifficultyLevel) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            mode,
+            difficulty,
+            course: None,
+            hitboxes: Hitboxes::default().content_hash(),
+            date: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
+            score: 0,
+            final_tick: 0,
+            flaps: Vec::new(),
+        }
+    }
+
+    /// Длительность забега в секундах
+    pub fn duration_secs(&self) -> f32 {
+        (self.final_tick as f64 / SIMULATION_HZ) as f32
+    }
+
+    /// Читает повтор из файла
+    pub fn load(path: &Path) -> Result<Self, ReplayLoadError> {
+        let content = fs::read_to_string(path)?;
+        let replay: Replay = serde_json::from_str(&content)
+            .map_err(|error| ReplayLoadError::Parse(error.to_string()))?;
+        if replay.version > REPLAY_VERSION {
+            return Err(ReplayLoadError::Unsupported(replay.version));
+        }
+        // Версии 2 добавили подвижные трубы, версии 3 — препятствия: тот же seed
+        // в старых версиях давал другую раскладку
+        if replay.version < REPLAY_VERSION {
+            return Err(ReplayLoadError::Outdated(replay.version));
+        }
+        Ok(replay)
+    }
+
+    /// Записывает повтор в файл
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        write_atomic(path, json.as_bytes())
+    }
+}
+
+/// Ошибка чтения файла повтора
+#[derive(Debug)]
+pub enum ReplayLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    /// Записан более новой версией игры
+    Unsupported(u32),
+    /// Записан старой версией игры и уже не воспроизводится
+    Outdated(u32),
+}
+
+impl fmt::Display for ReplayLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayLoadError::Io(error) => write!(f, "не удалось прочитать повтор: {}", error),
+            ReplayLoadError::Parse(error) => write!(f, "ошибка в файле повтора: {}", error),
+            ReplayLoadError::Unsupported(version) => {
+                write!(f, "неподдерживаемая версия повтора {}", version)
+            }
+            ReplayLoadError::Outdated(version) => {
+                write!(f, "повтор версии {} записан старой версией игры", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayLoadError {}
+
+impl From<std::io::Error> for ReplayLoadError {
+    fn from(error: std::io::Error) -> Self {
+        ReplayLoadError::Io(error)
+    }
+}
+
+/// Запись текущего забега
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    current: Option<Replay>,
+    /// Повтор последнего завершённого забега
+    pub last_replay: Option<Replay>,
+    /// Имя файла последнего сохранённого повтора
+    pub last_file: Option<String>,
+}
+
+/// Воспроизведение повтора
+#[derive(Resource, Default)]
+pub struct ReplayPlayback {
+    /// Повтор, ожидающий запуска из меню или экрана Game Over
+    pub queued: Option<Replay>,
+    active: Option<ActivePlayback>,
+}
+
+impl ReplayPlayback {
+    /// Воспроизведение с повтором, который запустится при входе в меню
+    pub fn with_queued(queued: Option<Replay>) -> Self {
+        Self {
+            queued,
+            active: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+}
+
+struct ActivePlayback {
+    replay: Replay,
+    next_flap: usize,
+    speed: f32,
+    seek_target: Option<u32>,
+    // Настройки игрока, которые вернутся после просмотра
+    restore_mode: GameModeSettings,
+    restore_difficulty: DifficultyLevel,
+    restore_course: Option<Handle<Course>>,
+}
+
+/// Условие запуска: сейчас проигрывается повтор
+pub fn is_replay_playing(playback: Res<ReplayPlayback>) -> bool {
+    playback.is_active()
+}
+
+#[derive(Component)]
+pub struct OnReplayHud;
+
+#[derive(Component)]
+pub struct ReplayHudText;
+
+/// Плагин записи и воспроизведения повторов
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .init_resource::<ReplayPlayback>()
+            .add_systems(
+                OnEnter(GameState::PreGame),
+                (
+                    prepare_replay_run
+                        .in_set(PreGameSet::Cleanup)
+                        .run_if(is_replay_playing),
+                    start_recording
+                        .in_set(PreGameSet::Spawn)
+                        .run_if(not(is_replay_playing)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    inject_replay_flaps
+                        .in_set(SimulationSet::Spawn)
+                        .run_if(is_replay_playing),
+                    record_flaps
+                        .in_set(SimulationSet::Scoring)
+                        .run_if(not(is_replay_playing)),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                finish_recording.in_set(GameOverSet::SaveReplay).run_if(
+                    not(is_replay_playing)
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_watch_replay_button.run_if(in_state(GameState::GameOver)),
+                    start_queued_replay
+                        .run_if(in_state(GameState::MainMenu).or(in_state(GameState::GameOver))),
+                    (handle_replay_controls, drive_replay_seek, update_replay_hud)
+                        .chain()
+                        .run_if(is_replay_playing),
+                )
+                    .run_if(in_state(AppState::Loaded)),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), stop_replay);
+    }
+}
+
+/// Каталог файлов повторов
+pub fn replays_dir(save_dir: &SaveDir) -> Option<PathBuf> {
+    save_dir.path("replays")
+}
+
+/// Удаляет записанные игрой повторы, кроме перечисленных в `keep`
+pub fn prune_replays(save_dir: &SaveDir, keep: &[&str]) {
+    let Some(dir) = replays_dir(save_dir) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let recorded = name.starts_with("replay_") && name.ends_with(".json");
+        if recorded && !keep.contains(&name) {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                eprintln!("Ошибка удаления повтора {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Загружает повтор по пути или по имени файла из каталога повторов
+pub fn load_replay_file(name: &str, save_dir: &SaveDir) -> Result<Replay, ReplayLoadError> {
+    let mut path = PathBuf::from(name);
+    if !path.exists() {
+        if let Some(dir) = replays_dir(save_dir) {
+            path = dir.join(name);
+        }
+    }
+
+    let result = Replay::load(&path);
+    if let Err(e) = &result {
+        eprintln!("Ошибка загрузки повтора {}: {}", path.display(), e);
+    }
+    result
+}
+
+/// Разбирает `--replay <файл>` из аргументов командной строки
+pub fn replay_from_args(
+    args: impl IntoIterator<Item = String>,
+    save_dir: &SaveDir,
+) -> Option<Replay> {
+    arg_value(args, "--replay").and_then(|name| load_replay_file(&name, save_dir).ok())
+}
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    rng: Res<GameRng>,
+    mode_settings: Res<GameModeSettings>,
+    difficulty: Res<DifficultySettings>,
+    course: ActiveCourse,
+    hitboxes: Res<Hitboxes>,
+) {
+    // Незаписанный забег не должен показывать повтор предыдущего
+    recorder.last_replay = None;
+    recorder.last_file = None;
+    let mut replay = Replay::new(
+        rng.seed(),
+        mode_settings.current_mode,
+        difficulty.current_level,
+    );
+    replay.course = course.content_hash();
+    replay.hitboxes = hitboxes.content_hash();
+    recorder.current = Some(replay);
+}
+
+fn record_flaps(
+    mut recorder: ResMut<ReplayRecorder>,
+    clock: Res<RunClock>,
+    mut jump_events: EventReader<JumpEvent>,
+) {
+    for _event in jump_events.read() {
+        if let Some(replay) = recorder.current.as_mut() {
+            replay.flaps.push(clock.tick);
+        }
+    }
+}
+
+/// Завершает запись и сохраняет файл повтора
+fn finish_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    save_dir: Res<SaveDir>,
+    clock: Res<RunClock>,
+    score: Res<GameScore>,
+) {
+    let Some(mut replay) = recorder.current.take() else {
+        return;
+    };
+    replay.score = score.0;
+    replay.final_tick = clock.tick;
+
+    recorder.last_file = None;
+    if let Some(dir) = replays_dir(&save_dir) {
+        let file_name = format!(
+            "replay_{}.json",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+        );
+        match replay.save(&dir.join(&file_name)) {
+            Ok(()) => recorder.last_file = Some(file_name),
+            Err(e) => eprintln!("Ошибка сохранения повтора: {}", e),
+        }
+    }
+    recorder.last_replay = Some(replay);
+}
+
+fn handle_watch_replay_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<WatchReplayButton>)>,
+    recorder: Res<ReplayRecorder>,
+    mut playback: ResMut<ReplayPlayback>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            playback.queued = recorder.last_replay.clone();
+        }
+    }
+}
+
+/// Запускает повтор из очереди: подставляет режим, сложность и трассу записи
+#[allow(clippy::too_many_arguments)]
+fn start_queued_replay(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut difficulty: ResMut<DifficultySettings>,
+    mut selected_course: ResMut<SelectedCourse>,
+    mut courses: ResMut<Assets<Course>>,
+    library: Option<Res<CourseLibrary>>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+    hud_query: Query<(), With<OnReplayHud>>,
+    assets: Res<GameAssets>,
+    hitboxes: NextRunHitboxes,
+) {
+    let Some(queued) = playback.queued.as_ref() else {
+        return;
+    };
+
+    // С другими хитбоксами те же прыжки приводят к другому забегу
+    if queued.hitboxes != hitboxes.get().content_hash() {
+        eprintln!("Повтор записан с другими хитбоксами, повтор не запущен");
+        playback.queued = None;
+        return;
+    }
+
+    // Забег по трассе воспроизводится только на той же трассе
+    let course = match (queued.mode, queued.course) {
+        (_, Some(hash)) => match find_course(&mut courses, hash) {
+            Some(handle) => Some(handle),
+            // Из командной строки повтор запускается раньше, чем загрузятся трассы
+            None if library.is_some_and(|library| library.is_loading(&asset_server)) => return,
+            None => {
+                eprintln!("Трасса повтора не найдена, повтор не запущен");
+                playback.queued = None;
+                return;
+            }
+        },
+        (GameMode::Course, None) => {
+            eprintln!("В повторе не указана трасса, повтор не запущен");
+            playback.queued = None;
+            return;
+        }
+        _ => None,
+    };
+    let Some(replay) = playback.queued.take() else {
+        return;
+    };
+
+    // При повторном запуске сохраняем исходные настройки игрока
+    let (restore_mode, restore_difficulty, restore_course) = match playback.active.take() {
+        Some(active) => (
+            active.restore_mode,
+            active.restore_difficulty,
+            active.restore_course,
+        ),
+        None => (
+            mode_settings.clone(),
+            difficulty.current_level,
+            selected_course.0.clone(),
+        ),
+    };
+
+    apply_game_mode(&mut mode_settings, replay.mode);
+    difficulty.set_level(replay.difficulty);
+    if course.is_some() {
+        selected_course.0 = course;
+    }
+
+    playback.active = Some(ActivePlayback {
+        replay,
+        next_flap: 0,
+        speed: 1.0,
+        seek_target: None,
+        restore_mode,
+        restore_difficulty,
+        restore_course,
+    });
+
+    if hud_query.is_empty() {
+        spawn_replay_hud(&mut commands, &assets);
+    }
+    next_state.set(GameState::PreGame);
+}
+
+/// Перед каждым забегом повтора подставляет seed записи
+fn prepare_replay_run(mut playback: ResMut<ReplayPlayback>, mut rng: ResMut<GameRng>) {
+    if let Some(active) = playback.active.as_mut() {
+        rng.next_run_seed = Some(active.replay.seed);
+        active.next_flap = 0;
+    }
+}
+
+/// Подаёт записанные прыжки на тех же шагах симуляции
+fn inject_replay_flaps(
+    mut playback: ResMut<ReplayPlayback>,
+    clock: Res<RunClock>,
+    mut flap: ResMut<FlapBuffer>,
+) {
+    let Some(active) = playback.active.as_mut() else {
+        return;
+    };
+
+    while let Some(&tick) = active.replay.flaps.get(active.next_flap) {
+        if tick > clock.tick {
+            break;
+        }
+        if tick == clock.tick {
+            flap.pending = true;
+        }
+        active.next_flap += 1;
+    }
+}
+
+/// Управление просмотром: пауза, скорость, перемотка, выход
+fn handle_replay_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time: ResMut<Time<Virtual>>,
+    clock: Res<RunClock>,
+    game_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(active) = playback.active.as_mut() else {
+        return;
+    };
+
+    if actions.just_pressed(GameAction::Back) {
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+
+    if actions.just_pressed(GameAction::Pause) {
+        if time.is_paused() {
+            time.unpause();
+        } else {
+            time.pause();
+        }
+    }
+
+    for (key, speed) in REPLAY_SPEEDS {
+        if keys.just_pressed(key) {
+            active.speed = speed;
+            time.set_relative_speed(speed);
+        }
+    }
+
+    if *game_state.get() != GameState::Playing {
+        return;
+    }
+
+    let seek_ticks = (REPLAY_SEEK_SECONDS * SIMULATION_HZ) as u32;
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        active.seek_target = Some(clock.tick + seek_ticks);
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        // Назад можно только пересимулировать забег с начала
+        active.seek_target = Some(clock.tick.saturating_sub(seek_ticks));
+        next_state.set(GameState::PreGame);
+    }
+}
+
+/// Перематывает забег ускоренным временем до нужного шага
+fn drive_replay_seek(
+    mut playback: ResMut<ReplayPlayback>,
+    mut time: ResMut<Time<Virtual>>,
+    clock: Res<RunClock>,
+    game_state: Res<State<GameState>>,
+) {
+    let Some(active) = playback.active.as_mut() else {
+        return;
+    };
+    let Some(target) = active.seek_target else {
+        return;
+    };
+
+    let in_run = matches!(game_state.get(), GameState::PreGame | GameState::Playing);
+    if in_run && clock.tick < target.min(active.replay.final_tick) {
+        time.set_relative_speed(REPLAY_SEEK_SPEED);
+    } else {
+        active.seek_target = None;
+        time.set_relative_speed(active.speed);
+    }
+}
+
+fn update_replay_hud(
+    playback: Res<ReplayPlayback>,
+    time: Res<Time<Virtual>>,
+    clock: Res<RunClock>,
+    mut text_query: Query<&mut Text, With<ReplayHudText>>,
+) {
+    let Some(active) = playback.active.as_ref() else {
+        return;
+    };
+
+    let status = if time.is_paused() {
+        "⏸".to_string()
+    } else if active.seek_target.is_some() {
+        "⏩".to_string()
+    } else {
+        format!("▶ {:.1}x", active.speed)
+    };
+
+    for mut text in &mut text_query {
+        **text = format!(
+            "Повтор {}  {:.1} / {:.1} с   [1/2/3] скорость  [←/→] перемотка",
+            status,
+            clock.elapsed_secs(),
+            active.replay.duration_secs()
+        );
+    }
+}
+
+fn spawn_replay_hud(commands: &mut Commands, assets: &GameAssets) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnReplayHud,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Повтор"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.9, 0.4)),
+                ReplayHudText,
+            ));
+        });
+}
+
+/// Завершает просмотр и возвращает настройки игрока
+pub fn stop_replay(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut mode_settings: ResMut<GameModeSettings>,
+    mut difficulty: ResMut<DifficultySettings>,
+    mut selected_course: ResMut<SelectedCourse>,
+    mut time: ResMut<Time<Virtual>>,
+    hud_query: Query<Entity, With<OnReplayHud>>,
+) {
+    let Some(active) = playback.active.take() else {
+        return;
+    };
+
+    *mode_settings = active.restore_mode;
+    difficulty.set_level(active.restore_difficulty);
+    selected_course.0 = active.restore_course;
+    time.unpause();
+    time.set_relative_speed(1.0);
+
+    for entity in &hud_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGameBuilder, TempSaveDir};
+
+    #[test]
+    fn test_replay_roundtrip() {
+        let mut replay = Replay::new(42, GameMode::Survival, DifficultyLevel::Normal);
+        replay.flaps = vec![0, 30, 61];
+        replay.final_tick = 128;
+        replay.score = 3;
+
+        let temp = TempSaveDir::new("replay_test");
+        let path = temp.path().join("replay.json");
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+
+        assert_eq!(loaded, replay);
+        assert_eq!(loaded.duration_secs(), 2.0);
+    }
+
+    #[test]
+    fn test_replay_reproduces_recorded_run() {
+        let mut game = HeadlessGameBuilder::default().with_seed(7).build();
+        game.start_run();
+        for _ in 0..4 {
+            game.tap_key(KeyCode::Space);
+            game.step_frames(20);
+        }
+        game.step_frames(120);
+        assert_eq!(game.game_state(), GameState::GameOver);
+
+        let replay = game
+            .resource::<ReplayRecorder>()
+            .last_replay
+            .clone()
+            .expect("забег должен быть записан");
+        assert_eq!(replay.flaps.len(), 4);
+
+        // Другой seed приложения: повтор обязан взять seed из записи
+        let mut viewer = HeadlessGameBuilder::default().with_seed(1).build();
+        viewer.resource_mut::<ReplayPlayback>().queued = Some(replay.clone());
+        viewer.step_frames(300);
+
+        assert_eq!(viewer.game_state(), GameState::GameOver);
+        assert!(viewer.resource::<ReplayPlayback>().is_active());
+        assert_eq!(viewer.resource::<RunClock>().tick, replay.final_tick);
+        assert_eq!(viewer.score(), replay.score);
+        assert!(viewer.resource::<ReplayRecorder>().last_replay.is_none());
+    }
+    #[test]
+    fn test_replay_with_other_hitboxes_is_not_started() {
+        let mut replay = Replay::new(7, GameMode::Classic, DifficultyLevel::Normal);
+        replay.hitboxes ^= 1;
+
+        let mut viewer = HeadlessGameBuilder::default().build();
+        viewer.resource_mut::<ReplayPlayback>().queued = Some(replay);
+        viewer.step_frames(5);
+
+        let playback = viewer.resource::<ReplayPlayback>();
+        assert!(playback.queued.is_none());
+        assert!(!playback.is_active());
+        assert_eq!(viewer.game_state(), GameState::MainMenu);
+    }
+}
@@ -0,0 +1,153 @@
+//! Детерминированный генератор случайных чисел
+//!
+//! Вся случайность игры идёт через ресурс `GameRng`. Каждый забег пересевается
+//! от своего seed, поэтому забег можно воспроизвести, зная seed.
+//! Геймплей и косметика используют разные потоки: частицы и тряска камеры
+//! не сдвигают раскладку труб и power-ups. Трубы и препятствия берут числа
+//! из собственных генераторов по номеру, чтобы раскладка не зависела от счёта.
+
+use crate::core::utils::arg_value;
+use crate::states::game_state::{GameState, PreGameSet};
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Соль для косметического потока, чтобы он не совпадал с геймплейным
+const COSMETIC_STREAM_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+/// Соль для генераторов отдельных объектов
+const SPAWN_STREAM_SALT: u64 = 0xD1B5_4A32_D192_ED03;
+/// Поток пар труб в `spawn_stream`
+pub const PIPE_STREAM: u8 = 0;
+/// Первый из потоков препятствий, по одному на вид
+pub const OBSTACLE_STREAM: u8 = 1;
+
+/// Ресурс с генераторами случайных чисел текущего забега
+#[derive(Resource)]
+pub struct GameRng {
+    /// Seed, заданный при запуске (`--seed`); если `None`, каждый забег получает новый
+    pub fixed_seed: Option<u64>,
+    /// Seed только для следующего забега (повторы); важнее `fixed_seed`
+    pub next_run_seed: Option<u64>,
+    seed: u64,
+    gameplay: StdRng,
+    cosmetic: StdRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        let seed = fixed_seed.unwrap_or_else(rand::random);
+        Self {
+            fixed_seed,
+            next_run_seed: None,
+            seed,
+            gameplay: StdRng::seed_from_u64(seed),
+            cosmetic: StdRng::seed_from_u64(seed ^ COSMETIC_STREAM_SALT),
+        }
+    }
+
+    /// Seed текущего забега
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Пересевает оба потока от заданного seed
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.gameplay = StdRng::seed_from_u64(seed);
+        self.cosmetic = StdRng::seed_from_u64(seed ^ COSMETIC_STREAM_SALT);
+    }
+
+    /// Поток для всего, что влияет на игровой процесс
+    pub fn gameplay(&mut self) -> &mut StdRng {
+        &mut self.gameplay
+    }
+
+    /// Поток для визуальных эффектов
+    pub fn cosmetic(&mut self) -> &mut StdRng {
+        &mut self.cosmetic
+    }
+
+    /// Генератор `index`-го объекта потока `stream` (например, пары труб).
+    /// Сколько чисел вытянул объект, не сдвигает следующие: у игроков с разным
+    /// счётом на одном seed раскладка совпадает
+    pub fn spawn_stream(&self, stream: u8, index: u64) -> StdRng {
+        let stream = u64::from(stream) << 56;
+        StdRng::seed_from_u64(self.seed ^ SPAWN_STREAM_SALT ^ stream ^ index)
+    }
+}
+
+/// Разбирает `--seed N` или `--seed=N` из аргументов командной строки
+pub fn seed_from_args(args: impl IntoIterator<Item = String>) -> Option<u64> {
+    arg_value(args, "--seed").and_then(|value| value.parse().ok())
+}
+
+/// Плагин детерминированной случайности
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>().add_systems(
+            OnEnter(GameState::PreGame),
+            reseed_for_new_run.in_set(PreGameSet::Seed),
+        );
+    }
+}
+
+/// Выбирает seed нового забега и пересевает генераторы
+fn reseed_for_new_run(mut rng: ResMut<GameRng>) {
+    let seed = rng
+        .next_run_seed
+        .take()
+        .or(rng.fixed_seed)
+        .unwrap_or_else(rand::random);
+    rng.reseed(seed);
+    info!("Seed забега: {}", seed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_seed_from_args() {
+        assert_eq!(
+            seed_from_args(args(&["game", "--seed", "1234"])),
+            Some(1234)
+        );
+        assert_eq!(seed_from_args(args(&["game", "--seed=42"])), Some(42));
+        assert_eq!(seed_from_args(args(&["game"])), None);
+        assert_eq!(seed_from_args(args(&["game", "--seed", "abc"])), None);
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_sequence() {
+        let mut a = GameRng::new(Some(7));
+        let mut b = GameRng::new(Some(7));
+
+        let seq_a: Vec<u32> = (0..10).map(|_| a.gameplay().random()).collect();
+        let seq_b: Vec<u32> = (0..10).map(|_| b.gameplay().random()).collect();
+        assert_eq!(seq_a, seq_b);
+    }
+
+    #[test]
+    fn test_cosmetic_stream_does_not_shift_gameplay() {
+        let mut a = GameRng::new(Some(7));
+        let mut b = GameRng::new(Some(7));
+
+        for _ in 0..100 {
+            let _: f32 = b.cosmetic().random();
+        }
+        assert_eq!(a.gameplay().random::<u64>(), b.gameplay().random::<u64>());
+    }
+}
@@ -6,8 +6,13 @@ use crate::{
         resources::GameAssets,
         utils::despawn_entities,
     },
-    states::game_state::GameState,
+    plugins::audio::{next_volume_step, AudioSettings},
+    plugins::ghost::GhostSettings,
+    plugins::pause::PAUSE_BACKDROP,
+    plugins::preferences::DisplaySettings,
+    states::game_state::{GameState, PauseState, SettingsOpen, SettingsPage},
 };
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, UiRect, Val};
 
@@ -16,18 +21,22 @@ pub struct SettingsUIPlugin;
 
 impl Plugin for SettingsUIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Settings), spawn_settings_ui)
+        app.add_computed_state::<SettingsOpen>()
+            .add_sub_state::<SettingsPage>()
+            .add_systems(OnEnter(SettingsPage::General), spawn_settings_ui)
             .add_systems(
                 Update,
                 (
                     handle_settings_button_clicks,
                     handle_difficulty_selection,
                     update_difficulty_buttons,
+                    handle_setting_toggles,
+                    handle_controls_button,
                 )
-                    .run_if(in_state(GameState::Settings)),
+                    .run_if(in_state(SettingsPage::General)),
             )
             .add_systems(
-                OnExit(GameState::Settings),
+                OnExit(SettingsPage::General),
                 despawn_entities::<OnSettingsScreen>,
             );
     }
@@ -38,7 +47,16 @@ fn spawn_settings_ui(
     mut commands: Commands,
     assets: Res<GameAssets>,
     difficulty: Res<DifficultySettings>,
+    toggles: ToggleValues,
+    pause_state: Option<Res<State<PauseState>>>,
 ) {
+    // Из паузы настройки открываются поверх забега
+    let background = if pause_state.is_some() {
+        PAUSE_BACKDROP
+    } else {
+        Color::NONE
+    };
+
     commands
         .spawn((
             Node {
@@ -49,6 +67,7 @@ fn spawn_settings_ui(
                 justify_content: JustifyContent::Center,
                 ..default()
             },
+            BackgroundColor(background),
             OnSettingsScreen,
         ))
         .with_children(|parent| {
@@ -62,7 +81,7 @@ fn spawn_settings_ui(
                 },
                 TextColor(Color::srgb(1.0, 1.0, 1.0)),
                 Node {
-                    margin: UiRect::bottom(Val::Px(40.0)),
+                    margin: UiRect::bottom(Val::Px(20.0)),
                     ..default()
                 },
             ));
@@ -72,7 +91,7 @@ fn spawn_settings_ui(
                 .spawn((Node {
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
-                    margin: UiRect::bottom(Val::Px(30.0)),
+                    margin: UiRect::bottom(Val::Px(10.0)),
                     ..default()
                 },))
                 .with_children(|parent| {
@@ -85,29 +104,55 @@ fn spawn_settings_ui(
                         },
                         TextColor(Color::srgb(0.9, 0.9, 0.9)),
                         Node {
-                            margin: UiRect::bottom(Val::Px(20.0)),
+                            margin: UiRect::bottom(Val::Px(10.0)),
                             ..default()
                         },
                     ));
 
-                    // Кнопки выбора сложности
+                    // Кнопки выбора сложности, по две в ряд
                     let difficulties = [
-                        DifficultyLevel::Easy,
-                        DifficultyLevel::Normal,
-                        DifficultyLevel::Hard,
-                        DifficultyLevel::Progressive,
+                        [DifficultyLevel::Easy, DifficultyLevel::Normal],
+                        [DifficultyLevel::Hard, DifficultyLevel::Progressive],
                     ];
 
-                    for level in difficulties {
-                        spawn_difficulty_button(
-                            parent,
-                            &assets,
-                            level,
-                            difficulty.current_level == level,
-                        );
+                    for row in difficulties {
+                        parent
+                            .spawn(Node {
+                                flex_direction: FlexDirection::Row,
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for level in row {
+                                    spawn_difficulty_button(
+                                        parent,
+                                        &assets,
+                                        level,
+                                        difficulty.current_level == level,
+                                    );
+                                }
+                            });
                     }
                 });
 
+            // Громкость, окно и призрак
+            for row in [
+                [SettingToggle::MasterVolume, SettingToggle::EffectsVolume],
+                [SettingToggle::Fullscreen, SettingToggle::Ghost],
+            ] {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for toggle in row {
+                            spawn_toggle_button(parent, &assets, toggle, &toggles);
+                        }
+                    });
+            }
+
+            spawn_menu_button(parent, &assets, "🎮 Управление", ControlsButton);
+
             // Кнопка возврата
             spawn_menu_button(parent, &assets, "🔙 Назад в меню", BackButton);
         });
@@ -134,7 +179,7 @@ fn spawn_difficulty_button(
                 height: Val::Px(50.0),
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
-                margin: UiRect::vertical(Val::Px(5.0)),
+                margin: UiRect::all(Val::Px(5.0)),
                 ..default()
             },
             BorderRadius::all(Val::Px(8.0)),
@@ -189,13 +234,107 @@ pub fn spawn_menu_button(
         });
 }
 
+/// Компонент кнопки экрана управления
+#[derive(Component)]
+pub struct ControlsButton;
+
+/// Кнопка-переключатель настройки
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum SettingToggle {
+    MasterVolume,
+    EffectsVolume,
+    Fullscreen,
+    Ghost,
+}
+
+/// Текст кнопки-переключателя
+#[derive(Component)]
+pub struct SettingToggleText(SettingToggle);
+
+/// Настройки, которые меняются кнопками-переключателями
+#[derive(SystemParam)]
+pub struct ToggleValues<'w> {
+    audio: ResMut<'w, AudioSettings>,
+    display: ResMut<'w, DisplaySettings>,
+    ghost: ResMut<'w, GhostSettings>,
+}
+
+impl ToggleValues<'_> {
+    fn label(&self, toggle: SettingToggle) -> String {
+        let on_off = |enabled: bool| if enabled { "вкл" } else { "выкл" };
+        match toggle {
+            SettingToggle::MasterVolume => {
+                format!("🔊 Громкость: {:.0}%", self.audio.master_volume * 100.0)
+            }
+            SettingToggle::EffectsVolume => {
+                format!("🔔 Эффекты: {:.0}%", self.audio.effects_volume * 100.0)
+            }
+            SettingToggle::Fullscreen => {
+                format!("🖥 Полный экран: {}", on_off(self.display.fullscreen))
+            }
+            SettingToggle::Ghost => format!("👻 Призрак: {}", on_off(self.ghost.enabled)),
+        }
+    }
+
+    fn toggle(&mut self, toggle: SettingToggle) {
+        match toggle {
+            SettingToggle::MasterVolume => {
+                self.audio.master_volume = next_volume_step(self.audio.master_volume);
+            }
+            SettingToggle::EffectsVolume => {
+                self.audio.effects_volume = next_volume_step(self.audio.effects_volume);
+            }
+            SettingToggle::Fullscreen => self.display.fullscreen = !self.display.fullscreen,
+            SettingToggle::Ghost => self.ghost.enabled = !self.ghost.enabled,
+        }
+    }
+}
+
+fn spawn_toggle_button(
+    parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    toggle: SettingToggle,
+    values: &ToggleValues,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(250.0),
+                height: Val::Px(50.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                margin: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(8.0)),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            toggle,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(values.label(toggle)),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                SettingToggleText(toggle),
+            ));
+        });
+}
+
 /// Компонент кнопки возврата
 #[derive(Component)]
 pub struct BackButton;
 
 /// Обрабатывает клики по кнопкам настроек
+#[allow(clippy::type_complexity)]
 fn handle_settings_button_clicks(
     mut next_state: ResMut<NextState<GameState>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &BackButton),
         (Changed<Interaction>, With<Button>),
@@ -205,7 +344,11 @@ fn handle_settings_button_clicks(
         match *interaction {
             Interaction::Pressed => {
                 *color = Color::srgb(0.4, 0.4, 0.4).into();
-                next_state.set(GameState::MainMenu);
+                if pause_state.is_some() {
+                    next_pause_state.set(PauseState::Paused);
+                } else {
+                    next_state.set(GameState::MainMenu);
+                }
             }
             Interaction::Hovered => {
                 *color = Color::srgb(0.3, 0.3, 0.3).into();
@@ -218,6 +361,7 @@ fn handle_settings_button_clicks(
 }
 
 /// Обрабатывает выбор сложности
+#[allow(clippy::type_complexity)]
 fn handle_difficulty_selection(
     mut difficulty_settings: ResMut<DifficultySettings>,
     mut interaction_query: Query<
@@ -266,3 +410,33 @@ fn update_difficulty_buttons(
         }
     }
 }
+
+/// Переключает громкость, полноэкранный режим и призрака
+fn handle_setting_toggles(
+    mut values: ToggleValues,
+    interaction_query: Query<(&Interaction, &SettingToggle), Changed<Interaction>>,
+    mut text_query: Query<(&mut Text, &SettingToggleText)>,
+) {
+    for (interaction, toggle) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            values.toggle(*toggle);
+            for (mut text, SettingToggleText(text_toggle)) in &mut text_query {
+                if text_toggle == toggle {
+                    **text = values.label(*toggle);
+                }
+            }
+        }
+    }
+}
+
+/// Открывает экран управления
+fn handle_controls_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+    mut next_page: ResMut<NextState<SettingsPage>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_page.set(SettingsPage::Controls);
+        }
+    }
+}
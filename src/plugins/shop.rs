@@ -0,0 +1,783 @@
+//! Магазин
+//!
+//! Каталог товаров лежит в `assets/shop/catalogue.shop.ron` и загружается как
+//! ассет, поэтому новые товары добавляются без правки кода. Монеты и покупки
+//! хранятся в `shop.json` профиля. Купленный облик птицы и тема фона
+//! надеваются сразу. Сами облики описаны в каталоге обликов (`skins`), товар
+//! лишь открывает облик по ключу.
+
+use crate::{
+    core::{
+        resources::{is_cheated_run, GameAssets},
+        save::{self, is_app_exiting, SaveData, SaveDir},
+        utils::despawn_entities,
+    },
+    plugins::{
+        background::BackgroundLayer, coins::CoinCollectedEvent, editor::is_editor_playtest,
+        profiles::ProfileSwitched, replay::is_replay_playing, settings_ui::spawn_menu_button,
+    },
+    states::game_state::GameState,
+};
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, JustifyContent, Node, Overflow, UiRect, Val};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+/// Файл каталога внутри `assets`
+const CATALOGUE_PATH: &str = "shop/catalogue.shop.ron";
+
+/// Каталог магазина из файла
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Deserialize)]
+pub struct ShopCatalogue {
+    pub items: Vec<ShopItem>,
+}
+
+/// Товар магазина
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ShopItem {
+    /// Ключ в файле сохранения; не меняется между версиями
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub price: u32,
+    pub unlock: Unlock,
+}
+
+/// Что открывает покупка
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Unlock {
+    /// Облик птицы по ключу из каталога обликов
+    BirdSkin(String),
+    /// Тема фона: оттенок слоёв параллакса
+    Background { tint: [f32; 3] },
+}
+
+/// Ячейка, в которой может быть надет только один товар
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Slot {
+    BirdSkin,
+    Background,
+}
+
+impl Unlock {
+    pub fn slot(&self) -> Slot {
+        match self {
+            Unlock::BirdSkin(_) => Slot::BirdSkin,
+            Unlock::Background { .. } => Slot::Background,
+        }
+    }
+
+    fn tint(&self) -> Option<Color> {
+        match *self {
+            Unlock::Background { tint: [r, g, b] } => Some(Color::srgb(r, g, b)),
+            Unlock::BirdSkin(_) => None,
+        }
+    }
+}
+
+impl ShopItem {
+    /// Что записывается в ячейку: ключ облика или ключ самого товара
+    fn equip_key(&self) -> &str {
+        match &self.unlock {
+            Unlock::BirdSkin(skin) => skin,
+            _ => &self.id,
+        }
+    }
+}
+
+impl ShopCatalogue {
+    /// Проверяет, что ключи товаров уникальны, а облики и цвета заданы
+    pub fn validate(&self) -> Result<(), ShopLoadError> {
+        let mut ids = HashSet::new();
+        for item in &self.items {
+            if item.id.is_empty() || !ids.insert(item.id.as_str()) {
+                return Err(ShopLoadError::Invalid(format!(
+                    "ключ товара «{}» пустой или повторяется",
+                    item.id
+                )));
+            }
+            let unlock_ok = match &item.unlock {
+                Unlock::BirdSkin(skin) => !skin.is_empty(),
+                Unlock::Background { tint } => tint.iter().all(|c| (0.0..=1.0).contains(c)),
+            };
+            if !unlock_ok {
+                return Err(ShopLoadError::Invalid(format!(
+                    "товар «{}»: пустой облик или оттенок вне 0..1",
+                    item.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn item(&self, id: &str) -> Option<&ShopItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+}
+
+/// Ошибка загрузки каталога
+#[derive(Debug)]
+pub enum ShopLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for ShopLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShopLoadError::Io(error) => write!(f, "не удалось прочитать каталог: {}", error),
+            ShopLoadError::Parse(error) => write!(f, "ошибка в каталоге магазина: {}", error),
+            ShopLoadError::Invalid(error) => write!(f, "некорректный каталог: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ShopLoadError {}
+
+impl From<std::io::Error> for ShopLoadError {
+    fn from(error: std::io::Error) -> Self {
+        ShopLoadError::Io(error)
+    }
+}
+
+/// Разбирает каталог из RON
+pub fn parse_catalogue(bytes: &[u8]) -> Result<ShopCatalogue, ShopLoadError> {
+    let catalogue: ShopCatalogue =
+        ron::de::from_bytes(bytes).map_err(|error| ShopLoadError::Parse(error.to_string()))?;
+    catalogue.validate()?;
+    Ok(catalogue)
+}
+
+/// Загрузчик `*.shop.ron`
+#[derive(Default)]
+pub struct ShopCatalogueLoader;
+
+impl AssetLoader for ShopCatalogueLoader {
+    type Asset = ShopCatalogue;
+    type Settings = ();
+    type Error = ShopLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<ShopCatalogue, ShopLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        parse_catalogue(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["shop.ron"]
+    }
+}
+
+/// Загруженный каталог
+#[derive(Resource, Default)]
+pub struct ShopCatalogueHandle(pub Handle<ShopCatalogue>);
+
+/// Почему покупка не состоялась
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseError {
+    AlreadyOwned,
+    NotEnoughCoins,
+}
+
+/// Почему облик нельзя выбрать
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkinLock {
+    /// Продаётся в магазине и ещё не куплен
+    Price(u32),
+    /// Каталог не загружен или не разобрался: неизвестно, платный ли облик
+    Unavailable,
+}
+
+/// Монеты и покупки игрока
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Wallet {
+    pub coins: u64,
+    owned: BTreeSet<String>,
+    equipped: BTreeMap<Slot, String>,
+}
+
+impl SaveData for Wallet {
+    const FILE_NAME: &'static str = "shop.json";
+    const VERSION: u32 = 1;
+}
+
+impl Wallet {
+    pub fn owns(&self, item: &ShopItem) -> bool {
+        self.owned.contains(&item.id)
+    }
+
+    pub fn is_equipped(&self, item: &ShopItem) -> bool {
+        self.equipped(item.unlock.slot()) == Some(item.equip_key())
+    }
+
+    /// Покупает товар; облик и тему сразу надевает
+    pub fn buy(&mut self, item: &ShopItem) -> Result<(), PurchaseError> {
+        if self.owns(item) {
+            return Err(PurchaseError::AlreadyOwned);
+        }
+        let price = u64::from(item.price);
+        if self.coins < price {
+            return Err(PurchaseError::NotEnoughCoins);
+        }
+        self.coins -= price;
+        self.owned.insert(item.id.clone());
+        self.toggle_equipped(item);
+        Ok(())
+    }
+
+    /// Надевает купленный товар или снимает уже надетый
+    pub fn toggle_equipped(&mut self, item: &ShopItem) {
+        let slot = item.unlock.slot();
+        if !self.owns(item) {
+            return;
+        }
+        if self.is_equipped(item) {
+            self.equipped.remove(&slot);
+        } else {
+            self.equip(slot, item.equip_key());
+        }
+    }
+
+    /// Ключ надетого в ячейку облика или товара
+    pub fn equipped(&self, slot: Slot) -> Option<&str> {
+        self.equipped.get(&slot).map(String::as_str)
+    }
+
+    /// Надевает по ключу; что это открыто, проверяет вызывающий
+    pub fn equip(&mut self, slot: Slot, key: &str) {
+        self.equipped.insert(slot, key.to_string());
+    }
+
+    /// Цена того, что открывает `unlock`, если оно продаётся и ещё не куплено
+    pub fn locked_price(&self, catalogue: &ShopCatalogue, unlock: &Unlock) -> Option<u32> {
+        catalogue
+            .items
+            .iter()
+            .find(|item| item.unlock == *unlock && !self.owns(item))
+            .map(|item| item.price)
+    }
+
+    /// Цена облика, если магазин его продаёт и он ещё не куплен
+    pub fn locked_skin_price(&self, catalogue: &ShopCatalogue, skin: &str) -> Option<u32> {
+        self.locked_price(catalogue, &Unlock::BirdSkin(skin.to_string()))
+    }
+
+    /// Почему облик закрыт. Без каталога закрыто всё: иначе одна ошибка в файле
+    /// открыла бы платные облики, а выбор сохранился бы в профиле
+    pub fn skin_lock(&self, catalogue: Option<&ShopCatalogue>, skin: &str) -> Option<SkinLock> {
+        let Some(catalogue) = catalogue else {
+            return Some(SkinLock::Unavailable);
+        };
+        self.locked_skin_price(catalogue, skin).map(SkinLock::Price)
+    }
+
+    /// Оттенок надетого в ячейку товара
+    pub fn equipped_tint(&self, catalogue: &ShopCatalogue, slot: Slot) -> Option<Color> {
+        let id = self.equipped.get(&slot)?;
+        catalogue.item(id)?.unlock.tint()
+    }
+}
+
+/// Каталог вместе с покупками игрока, для систем вне магазина
+#[derive(SystemParam)]
+pub struct Shop<'w> {
+    catalogues: Res<'w, Assets<ShopCatalogue>>,
+    handle: Res<'w, ShopCatalogueHandle>,
+    wallet: Res<'w, Wallet>,
+}
+
+impl Shop<'_> {
+    pub fn catalogue(&self) -> Option<&ShopCatalogue> {
+        self.catalogues.get(&self.handle.0)
+    }
+
+    pub fn wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    pub fn equipped(&self, slot: Slot) -> Option<&str> {
+        self.wallet.equipped(slot)
+    }
+
+    fn equipped_tint(&self, slot: Slot) -> Option<Color> {
+        let catalogue = self.catalogues.get(&self.handle.0)?;
+        self.wallet.equipped_tint(catalogue, slot)
+    }
+}
+
+#[derive(Component)]
+pub struct OnShopScreen;
+
+/// Список товаров на экране магазина
+#[derive(Component)]
+struct ShopList;
+
+/// Кнопка товара
+#[derive(Component)]
+pub struct ShopItemButton(pub String);
+
+/// Баланс и сообщение о последней покупке
+#[derive(Component)]
+struct ShopStatusText;
+
+#[derive(Component)]
+pub struct ShopBackButton;
+
+/// Переход к выбору облика птицы
+#[derive(Component)]
+pub struct ShopCustomizeButton;
+
+/// Плагин магазина
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ShopCatalogue>()
+            .init_asset_loader::<ShopCatalogueLoader>()
+            .init_resource::<ShopCatalogueHandle>()
+            .init_resource::<Wallet>()
+            .add_systems(Startup, (load_catalogue, load_wallet))
+            .add_systems(Update, load_wallet.run_if(on_event::<ProfileSwitched>))
+            .add_systems(
+                Update,
+                credit_coins.run_if(
+                    not(is_replay_playing)
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(Update, apply_background_theme)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                save_wallet.run_if(
+                    not(is_replay_playing)
+                        .and(not(is_editor_playtest))
+                        .and(not(is_cheated_run)),
+                ),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), save_wallet)
+            .add_systems(Last, save_wallet.run_if(is_app_exiting))
+            .add_systems(OnEnter(GameState::Shop), spawn_shop_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_shop_buttons,
+                    refresh_shop_list.run_if(
+                        resource_changed::<Wallet>.or(on_event::<AssetEvent<ShopCatalogue>>),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Shop)),
+            )
+            .add_systems(OnExit(GameState::Shop), despawn_entities::<OnShopScreen>);
+    }
+}
+
+fn load_catalogue(mut handle: ResMut<ShopCatalogueHandle>, asset_server: Res<AssetServer>) {
+    handle.0 = asset_server.load(CATALOGUE_PATH);
+}
+
+fn load_wallet(save_dir: Res<SaveDir>, mut wallet: ResMut<Wallet>) {
+    *wallet = save::load::<Wallet>(&save_dir).unwrap_or_default();
+}
+
+fn save_wallet(save_dir: Res<SaveDir>, wallet: Res<Wallet>) {
+    save::save(&save_dir, &*wallet);
+}
+
+/// Зачисляет подобранные монеты на счёт
+fn credit_coins(mut wallet: ResMut<Wallet>, mut coin_events: EventReader<CoinCollectedEvent>) {
+    for event in coin_events.read() {
+        wallet.coins += u64::from(event.value);
+    }
+}
+
+fn apply_background_theme(shop: Shop, mut query: Query<&mut Sprite, With<BackgroundLayer>>) {
+    let tint = shop.equipped_tint(Slot::Background).unwrap_or(Color::WHITE);
+    for mut sprite in &mut query {
+        // Пишем только при смене, чтобы не трогать отслеживание изменений
+        if sprite.color != tint {
+            sprite.color = tint;
+        }
+    }
+}
+
+fn spawn_shop_screen(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    catalogues: Res<Assets<ShopCatalogue>>,
+    handle: Res<ShopCatalogueHandle>,
+    wallet: Res<Wallet>,
+) {
+    let catalogue = catalogues.get(&handle.0);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnShopScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("🛒 Магазин"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent.spawn((
+                Text::new(balance_text(&wallet)),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.84, 0.0)),
+                Node {
+                    margin: UiRect::vertical(Val::Px(8.0)),
+                    ..default()
+                },
+                ShopStatusText,
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(520.0),
+                        height: Val::Px(320.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    ShopList,
+                ))
+                .with_children(|list| spawn_shop_rows(list, &assets, catalogue, &wallet));
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|buttons| {
+                    spawn_menu_button(buttons, &assets, "🎨 Облик птицы", ShopCustomizeButton);
+                    spawn_menu_button(buttons, &assets, "🔙 Назад в меню", ShopBackButton);
+                });
+        });
+}
+
+fn balance_text(wallet: &Wallet) -> String {
+    format!("Монеты: {}", wallet.coins)
+}
+
+fn spawn_shop_rows(
+    parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    catalogue: Option<&ShopCatalogue>,
+    wallet: &Wallet,
+) {
+    let Some(catalogue) = catalogue else {
+        parent.spawn((
+            Text::new(format!("Каталог не загружен: assets/{}", CATALOGUE_PATH)),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        ));
+        return;
+    };
+
+    for item in &catalogue.items {
+        let (status, color) = if wallet.is_equipped(item) {
+            ("✔ Надето".to_string(), Color::srgb(0.2, 0.45, 0.3))
+        } else if wallet.owns(item) {
+            ("Надеть".to_string(), Color::srgb(0.2, 0.3, 0.4))
+        } else {
+            (format!("{} монет", item.price), Color::srgb(0.1, 0.1, 0.2))
+        };
+        let swatch = item.unlock.tint().unwrap_or(Color::NONE);
+
+        parent
+            .spawn((
+                Button,
+                Node {
+                    width: Val::Px(500.0),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    margin: UiRect::bottom(Val::Px(8.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(8.0)),
+                BackgroundColor(color),
+                ShopItemButton(item.id.clone()),
+            ))
+            .with_children(|row| {
+                row.spawn((
+                    Node {
+                        width: Val::Px(24.0),
+                        height: Val::Px(24.0),
+                        margin: UiRect::right(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(12.0)),
+                    BackgroundColor(swatch),
+                ));
+                row.spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.0,
+                    ..default()
+                })
+                .with_children(|text| {
+                    text.spawn((
+                        Text::new(item.name.clone()),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                    text.spawn((
+                        Text::new(item.description.clone()),
+                        TextFont {
+                            font: assets.font.clone(),
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                    ));
+                });
+                row.spawn((
+                    Text::new(status),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.84, 0.0)),
+                ));
+            });
+    }
+}
+
+/// Покупка, надевание, выбор облика и возврат в меню
+#[allow(clippy::too_many_arguments)]
+fn handle_shop_buttons(
+    item_query: Query<(&Interaction, &ShopItemButton), Changed<Interaction>>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<ShopBackButton>)>,
+    customize_query: Query<&Interaction, (Changed<Interaction>, With<ShopCustomizeButton>)>,
+    catalogues: Res<Assets<ShopCatalogue>>,
+    handle: Res<ShopCatalogueHandle>,
+    save_dir: Res<SaveDir>,
+    mut wallet: ResMut<Wallet>,
+    mut status_query: Query<&mut Text, With<ShopStatusText>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if back_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+    if customize_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::Customization);
+        return;
+    }
+
+    let Some(catalogue) = catalogues.get(&handle.0) else {
+        return;
+    };
+    for (interaction, button) in &item_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(item) = catalogue.item(&button.0) else {
+            continue;
+        };
+
+        let message = if wallet.owns(item) {
+            wallet.toggle_equipped(item);
+            balance_text(&wallet)
+        } else {
+            match wallet.buy(item) {
+                Ok(()) => format!("Куплено: {}. {}", item.name, balance_text(&wallet)),
+                Err(_) => format!(
+                    "Не хватает монет: нужно {}, есть {}",
+                    item.price, wallet.coins
+                ),
+            }
+        };
+        // Покупки сохраняются сразу, чтобы не потерять их при сбое
+        save::save(&save_dir, &*wallet);
+        for mut text in &mut status_query {
+            text.0 = message.clone();
+        }
+    }
+}
+
+/// Пересобирает список после покупки или изменения файла каталога
+fn refresh_shop_list(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    catalogues: Res<Assets<ShopCatalogue>>,
+    handle: Res<ShopCatalogueHandle>,
+    wallet: Res<Wallet>,
+    list_query: Query<Entity, With<ShopList>>,
+) {
+    for list in &list_query {
+        commands
+            .entity(list)
+            .despawn_descendants()
+            .with_children(|list| {
+                spawn_shop_rows(list, &assets, catalogues.get(&handle.0), &wallet)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::ShopButton;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder, TempSaveDir};
+
+    const CATALOGUE: &[u8] = br#"(
+        items: [
+            (id: "red", name: "Red", price: 10, unlock: BirdSkin("red_skin")),
+            (id: "blue", name: "Blue", price: 10, unlock: BirdSkin("blue_skin")),
+            (id: "night", name: "Night", description: "Dark", price: 20,
+                unlock: Background(tint: (0.3, 0.3, 0.6))),
+        ],
+    )"#;
+
+    fn catalogue() -> ShopCatalogue {
+        parse_catalogue(CATALOGUE).unwrap()
+    }
+
+    /// Подменяет каталог; ручку нужно держать, пока идёт тест
+    fn install_catalogue(game: &mut HeadlessGame) -> Handle<ShopCatalogue> {
+        let handle = game
+            .app
+            .world_mut()
+            .resource_mut::<Assets<ShopCatalogue>>()
+            .add(catalogue());
+        game.resource_mut::<ShopCatalogueHandle>().0 = handle.clone();
+        handle
+    }
+
+    #[test]
+    fn test_catalogue_rejects_duplicate_ids_and_bad_tints() {
+        assert_eq!(catalogue().items.len(), 3);
+        let shipped = include_bytes!("../../assets/shop/catalogue.shop.ron");
+        assert!(parse_catalogue(shipped).is_ok());
+        let duplicate = br#"(items: [
+            (id: "a", name: "A", price: 1, unlock: BirdSkin("a")),
+            (id: "a", name: "B", price: 1, unlock: BirdSkin("b")),
+        ])"#;
+        assert!(matches!(
+            parse_catalogue(duplicate),
+            Err(ShopLoadError::Invalid(_))
+        ));
+        let bright = br#"(items: [(id: "a", name: "A", price: 1, unlock: Background(tint: (2.0, 0.0, 0.0)))])"#;
+        assert!(parse_catalogue(bright).is_err());
+        // Режимы игры не продаются
+        let mode = br#"(items: [(id: "a", name: "A", price: 1, unlock: Mode(Zen))])"#;
+        assert!(matches!(
+            parse_catalogue(mode),
+            Err(ShopLoadError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_buy_and_equip() {
+        let catalogue = catalogue();
+        let red = catalogue.item("red").unwrap();
+        let blue = catalogue.item("blue").unwrap();
+        let mut wallet = Wallet {
+            coins: 25,
+            ..default()
+        };
+
+        assert_eq!(wallet.buy(red), Ok(()));
+        assert_eq!(wallet.buy(red), Err(PurchaseError::AlreadyOwned));
+        assert!(wallet.is_equipped(red));
+        assert_eq!(wallet.buy(blue), Ok(()));
+        // В ячейке облика остаётся только последний
+        assert!(wallet.is_equipped(blue) && !wallet.is_equipped(red));
+        assert_eq!(
+            wallet.buy(catalogue.item("night").unwrap()),
+            Err(PurchaseError::NotEnoughCoins)
+        );
+
+        assert_eq!(wallet.coins, 5);
+
+        // В ячейку облика записывается ключ облика, а не товара
+        assert_eq!(wallet.equipped(Slot::BirdSkin), Some("blue_skin"));
+        wallet.toggle_equipped(blue);
+        assert_eq!(wallet.equipped(Slot::BirdSkin), None);
+    }
+
+    #[test]
+    fn test_coins_credited_and_purchase_saved() {
+        let temp = TempSaveDir::new("shop_test");
+        let save_dir = temp.save_dir();
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app.insert_resource(save_dir.clone());
+        let _catalogue = install_catalogue(&mut game);
+
+        game.app
+            .world_mut()
+            .send_event(CoinCollectedEvent { value: 12 });
+        game.step();
+        assert_eq!(game.resource::<Wallet>().coins, 12);
+
+        game.click_button::<ShopButton>();
+        assert!(game.step_until_state(GameState::Shop));
+        game.step();
+        let world = game.app.world_mut();
+        let red = world
+            .query::<(Entity, &ShopItemButton)>()
+            .iter(world)
+            .find(|(_, button)| button.0 == "red")
+            .map(|(entity, _)| entity)
+            .unwrap();
+        world.entity_mut(red).insert(Interaction::Pressed);
+        game.step();
+
+        let saved = save::load::<Wallet>(&save_dir).unwrap();
+        assert_eq!(saved.coins, 2);
+        assert!(saved.owned.contains("red"));
+        assert_eq!(
+            saved.equipped.get(&Slot::BirdSkin),
+            Some(&"red_skin".to_string())
+        );
+    }
+}
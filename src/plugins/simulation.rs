@@ -0,0 +1,121 @@
+//! Фиксированный шаг симуляции
+//!
+//! Физика птицы, трубы, power-ups и таймеры режимов работают в `FixedUpdate`
+//! с постоянным шагом, поэтому результат забега не зависит от FPS.
+//! Отрисовка при этом интерполируется между двумя последними шагами симуляции.
+
+use crate::states::game_state::{GameState, SimulationSet};
+use bevy::app::{RunFixedMainLoop, RunFixedMainLoopSystem};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Частота шагов симуляции. 64 Гц дают точный шаг 15.625 мс без накопления ошибки
+pub const SIMULATION_HZ: f64 = 64.0;
+
+/// Номер шага симуляции с начала текущего забега
+#[derive(Resource, Default, Debug)]
+pub struct RunClock {
+    pub tick: u32,
+}
+
+impl RunClock {
+    /// Время забега в секундах
+    pub fn elapsed_secs(&self) -> f32 {
+        (self.tick as f64 / SIMULATION_HZ) as f32
+    }
+}
+
+/// Интерполяция положения сущности, которую двигает симуляция.
+///
+/// Внутри `FixedUpdate` `Transform` хранит состояние симуляции, а между шагами —
+/// интерполированное положение для отрисовки.
+#[derive(Component, Default)]
+pub struct InterpolatedTransform {
+    previous: Option<(Vec3, Quat)>,
+    current: Option<(Vec3, Quat)>,
+}
+
+/// Плагин фиксированного шага симуляции
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ))
+            .init_resource::<RunClock>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    SimulationSet::Spawn,
+                    SimulationSet::Movement,
+                    SimulationSet::Collision,
+                    SimulationSet::Scoring,
+                    SimulationSet::Timers,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                restore_simulated_transforms.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+            )
+            .add_systems(FixedFirst, remember_previous_transforms)
+            .add_systems(OnEnter(GameState::PreGame), reset_run_clock)
+            .add_systems(OnEnter(GameState::Playing), align_fixed_steps)
+            .add_systems(
+                FixedUpdate,
+                advance_run_clock
+                    .after(SimulationSet::Timers)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                interpolate_transforms.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            );
+    }
+}
+
+fn reset_run_clock(mut clock: ResMut<RunClock>) {
+    clock.tick = 0;
+}
+
+/// Отсчитывает шаги симуляции от первого кадра забега, а не от времени подготовки
+fn align_fixed_steps(mut fixed_time: ResMut<Time<Fixed>>) {
+    fixed_time.discard_overstep(Duration::MAX);
+}
+
+fn advance_run_clock(mut clock: ResMut<RunClock>) {
+    clock.tick += 1;
+}
+
+/// Возвращает сущностям положение из симуляции перед новыми шагами
+fn restore_simulated_transforms(mut query: Query<(&mut Transform, &InterpolatedTransform)>) {
+    for (mut transform, interpolated) in &mut query {
+        if let Some((translation, rotation)) = interpolated.current {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+/// Запоминает состояние перед каждым шагом симуляции
+fn remember_previous_transforms(mut query: Query<(&Transform, &mut InterpolatedTransform)>) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.previous = Some((transform.translation, transform.rotation));
+    }
+}
+
+/// Сохраняет результат симуляции и выставляет интерполированное положение для отрисовки
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut InterpolatedTransform)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+
+    for (mut transform, mut interpolated) in &mut query {
+        interpolated.current = Some((transform.translation, transform.rotation));
+
+        if let Some((previous_translation, previous_rotation)) = interpolated.previous {
+            transform.translation = previous_translation.lerp(transform.translation, alpha);
+            transform.rotation = previous_rotation.slerp(transform.rotation, alpha);
+        }
+    }
+}
@@ -0,0 +1,888 @@
+//! Облики птицы
+//!
+//! Облики описаны в `assets/skins/catalogue.skins.ron`: лист спрайтов с клипами
+//! анимации, оттенок и эффект гибели. Новый облик добавляется записью в файле и
+//! картинками, без правки кода. Первый облик открыт всем; облики, которые продаёт магазин,
+//! открываются покупкой. Выбранный облик хранится в `shop.json` вместе с
+//! остальными покупками, выбирается на экране «Облик птицы».
+
+use crate::{
+    core::{
+        resources::GameAssets,
+        save::{self, SaveDir},
+        utils::despawn_entities,
+    },
+    plugins::{
+        animation::{validate_clips, AnimationClips, ClipPlayer},
+        audio::GameOverEvent,
+        bird::{Bird, BirdAnimationState},
+        effects::spawn_particle_explosion,
+        rng::GameRng,
+        settings_ui::spawn_menu_button,
+        shop::{Shop, ShopCatalogue, ShopCatalogueHandle, SkinLock, Slot, Wallet},
+    },
+    states::game_state::GameState,
+};
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::{AlignItems, FlexDirection, FlexWrap, JustifyContent, Node, UiRect, Val};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Файл каталога внутри `assets`
+pub const CATALOGUE_PATH: &str = "skins/catalogue.skins.ron";
+
+/// Каталог обликов с загруженными кадрами
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SkinCatalogue {
+    pub skins: Vec<Skin>,
+}
+
+/// Облик птицы
+#[derive(Debug, Clone)]
+pub struct Skin {
+    /// Ключ в файле сохранения; не меняется между версиями
+    pub id: String,
+    pub name: String,
+    pub animation: SkinAnimation,
+    pub tint: Color,
+    pub death_effect: DeathEffect,
+}
+
+/// Лист спрайтов облика вместе с клипами
+#[derive(Debug, Clone)]
+pub struct SkinAnimation {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: AnimationClips,
+    pub states: StateClips,
+}
+
+impl Skin {
+    /// Спрайт облика в первом кадре клипа покоя
+    pub fn sprite(&self) -> Sprite {
+        let animation = &self.animation;
+        let index = animation
+            .clips
+            .get(&animation.states.idle)
+            .and_then(|clip| clip.frames.first())
+            .map_or(0, |frame| frame.index);
+        Sprite {
+            image: animation.image.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: animation.layout.clone(),
+                index,
+            }),
+            color: self.tint,
+            ..default()
+        }
+    }
+}
+
+impl SkinCatalogue {
+    pub fn skin(&self, id: &str) -> Option<&Skin> {
+        self.skins.iter().find(|skin| skin.id == id)
+    }
+
+    /// Облик по умолчанию — первый в файле
+    pub fn default_skin(&self) -> Option<&Skin> {
+        self.skins.first()
+    }
+}
+
+/// Взрыв частиц на месте гибели птицы
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DeathEffect {
+    pub color: [f32; 3],
+    pub particles: u32,
+    pub size: f32,
+    pub speed: f32,
+}
+
+impl Default for DeathEffect {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 0.5, 0.0],
+            particles: 100,
+            size: 5.0,
+            speed: 180.0,
+        }
+    }
+}
+
+/// Клипы, которые запускаются в состояниях птицы
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StateClips {
+    pub idle: String,
+    pub flapping: String,
+    pub falling: String,
+    pub dead: String,
+}
+
+impl StateClips {
+    pub fn clip(&self, state: BirdAnimationState) -> &str {
+        match state {
+            BirdAnimationState::Idle => &self.idle,
+            BirdAnimationState::Flapping => &self.flapping,
+            BirdAnimationState::Falling => &self.falling,
+            BirdAnimationState::Dead => &self.dead,
+        }
+    }
+
+    fn all(&self) -> [&str; 4] {
+        [&self.idle, &self.flapping, &self.falling, &self.dead]
+    }
+}
+
+/// Лист спрайтов в файле: картинка внутри `assets`, нарезанная сеткой
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SheetDefinition {
+    pub sheet: String,
+    pub tile: [u32; 2],
+    pub columns: u32,
+    pub rows: u32,
+    pub clips: AnimationClips,
+    pub states: StateClips,
+}
+
+/// Запись облика в файле; лист спрайтов указан по имени
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SkinDefinition {
+    pub id: String,
+    pub name: String,
+    pub animation: String,
+    #[serde(default = "white")]
+    pub tint: [f32; 3],
+    #[serde(default)]
+    pub death_effect: DeathEffect,
+}
+
+fn white() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+/// Содержимое файла обликов
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SkinFile {
+    pub animations: HashMap<String, SheetDefinition>,
+    pub skins: Vec<SkinDefinition>,
+}
+
+impl SkinFile {
+    /// Собирает каталог; `load_sheet` выдаёт картинку и сетку для листа по имени
+    fn into_catalogue(
+        self,
+        mut load_sheet: impl FnMut(
+            &str,
+            &SheetDefinition,
+        ) -> (Handle<Image>, Handle<TextureAtlasLayout>),
+    ) -> SkinCatalogue {
+        let animations: HashMap<_, _> = self
+            .animations
+            .into_iter()
+            .map(|(name, sheet)| {
+                let (image, layout) = load_sheet(&name, &sheet);
+                let animation = SkinAnimation {
+                    image,
+                    layout,
+                    clips: sheet.clips,
+                    states: sheet.states,
+                };
+                (name, animation)
+            })
+            .collect();
+        let skins = self
+            .skins
+            .into_iter()
+            .map(|definition| Skin {
+                // Ссылки на листы проверены в `parse_skins`
+                animation: animations[&definition.animation].clone(),
+                tint: srgb(definition.tint),
+                death_effect: definition.death_effect,
+                id: definition.id,
+                name: definition.name,
+            })
+            .collect();
+        SkinCatalogue { skins }
+    }
+}
+
+/// Ошибка загрузки каталога обликов
+#[derive(Debug)]
+pub enum SkinLoadError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for SkinLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkinLoadError::Io(error) => write!(f, "не удалось прочитать облики: {}", error),
+            SkinLoadError::Parse(error) => write!(f, "ошибка в каталоге обликов: {}", error),
+            SkinLoadError::Invalid(error) => write!(f, "некорректный каталог обликов: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SkinLoadError {}
+
+impl From<std::io::Error> for SkinLoadError {
+    fn from(error: std::io::Error) -> Self {
+        SkinLoadError::Io(error)
+    }
+}
+
+/// Разбирает и проверяет облики и листы спрайтов из RON
+pub fn parse_skins(bytes: &[u8]) -> Result<SkinFile, SkinLoadError> {
+    let file: SkinFile =
+        ron::de::from_bytes(bytes).map_err(|error| SkinLoadError::Parse(error.to_string()))?;
+    if file.skins.is_empty() {
+        return Err(SkinLoadError::Invalid("нет ни одного облика".to_string()));
+    }
+
+    for (name, sheet) in &file.animations {
+        let cells = (sheet.columns * sheet.rows) as usize;
+        if cells == 0 || sheet.tile.contains(&0) {
+            return Err(SkinLoadError::Invalid(format!(
+                "лист «{}»: пустая сетка",
+                name
+            )));
+        }
+        validate_clips(&sheet.clips, cells)
+            .map_err(|error| SkinLoadError::Invalid(format!("лист «{}»: {}", name, error)))?;
+        if let Some(missing) = sheet
+            .states
+            .all()
+            .into_iter()
+            .find(|clip| !sheet.clips.contains_key(*clip))
+        {
+            return Err(SkinLoadError::Invalid(format!(
+                "лист «{}»: нет клипа «{}» для состояния птицы",
+                name, missing
+            )));
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for skin in &file.skins {
+        if skin.id.is_empty() || !ids.insert(skin.id.as_str()) {
+            return Err(SkinLoadError::Invalid(format!(
+                "ключ облика «{}» пустой или повторяется",
+                skin.id
+            )));
+        }
+        if !file.animations.contains_key(&skin.animation) {
+            return Err(SkinLoadError::Invalid(format!(
+                "облик «{}» ссылается на неизвестный лист «{}»",
+                skin.id, skin.animation
+            )));
+        }
+        let in_range = |color: &[f32; 3]| color.iter().all(|c| (0.0..=1.0).contains(c));
+        if !in_range(&skin.tint) || !in_range(&skin.death_effect.color) {
+            return Err(SkinLoadError::Invalid(format!(
+                "облик «{}»: цвет вне 0..1",
+                skin.id
+            )));
+        }
+    }
+    Ok(file)
+}
+
+fn srgb([r, g, b]: [f32; 3]) -> Color {
+    Color::srgb(r, g, b)
+}
+
+/// Загрузчик `*.skins.ron`; листы спрайтов становятся зависимостями каталога
+#[derive(Default)]
+pub struct SkinCatalogueLoader;
+
+impl AssetLoader for SkinCatalogueLoader {
+    type Asset = SkinCatalogue;
+    type Settings = ();
+    type Error = SkinLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<SkinCatalogue, SkinLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let catalogue = parse_skins(&bytes)?.into_catalogue(|name, sheet| {
+            let layout = TextureAtlasLayout::from_grid(
+                UVec2::from(sheet.tile),
+                sheet.columns,
+                sheet.rows,
+                None,
+                None,
+            );
+            (
+                load_context.load(&sheet.sheet),
+                load_context.add_labeled_asset(format!("layout/{}", name), layout),
+            )
+        });
+        Ok(catalogue)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["skins.ron"]
+    }
+}
+
+/// Каталог обликов вместе с выбором игрока
+#[derive(SystemParam)]
+pub struct Skins<'w> {
+    catalogues: Res<'w, Assets<SkinCatalogue>>,
+    assets: Res<'w, GameAssets>,
+    shop: Shop<'w>,
+}
+
+impl Skins<'_> {
+    pub fn catalogue(&self) -> Option<&SkinCatalogue> {
+        self.catalogues.get(&self.assets.skins)
+    }
+
+    /// Выбранный облик; закрытый или удалённый из файла заменяется обликом по умолчанию
+    pub fn selected(&self) -> Option<&Skin> {
+        let catalogue = self.catalogue()?;
+        self.shop
+            .equipped(Slot::BirdSkin)
+            .and_then(|id| catalogue.skin(id))
+            .filter(|skin| self.is_unlocked(skin))
+            .or_else(|| catalogue.default_skin())
+    }
+
+    pub fn default_skin(&self) -> Option<&Skin> {
+        self.catalogue()?.default_skin()
+    }
+
+    /// Почему облик нельзя выбрать; `None` — облик открыт
+    pub fn lock(&self, skin: &Skin) -> Option<SkinLock> {
+        let catalogue = self.catalogue()?;
+        skin_lock(catalogue, self.shop.catalogue(), self.shop.wallet(), skin)
+    }
+
+    fn is_unlocked(&self, skin: &Skin) -> bool {
+        self.lock(skin).is_none()
+    }
+}
+
+/// Облик по умолчанию открыт всегда, остальные — если магазин их не продаёт
+/// или они куплены
+fn skin_lock(
+    skins: &SkinCatalogue,
+    shop: Option<&ShopCatalogue>,
+    wallet: &Wallet,
+    skin: &Skin,
+) -> Option<SkinLock> {
+    if skins
+        .default_skin()
+        .is_some_and(|default| default.id == skin.id)
+    {
+        return None;
+    }
+    wallet.skin_lock(shop, &skin.id)
+}
+
+#[derive(Component)]
+pub struct OnCustomizationScreen;
+
+/// Ряд карточек обликов
+#[derive(Component)]
+struct SkinList;
+
+/// Карточка облика
+#[derive(Component)]
+pub struct SkinButton(pub String);
+
+/// Превью облика, которое без конца машет крыльями
+#[derive(Component)]
+struct SkinPreview {
+    clips: AnimationClips,
+    flap: String,
+    player: ClipPlayer,
+}
+
+/// Подсказка под списком
+#[derive(Component)]
+struct CustomizationStatusText;
+
+#[derive(Component)]
+pub struct CustomizationBackButton;
+
+/// Плагин обликов птицы
+pub struct SkinsPlugin;
+
+impl Plugin for SkinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SkinCatalogue>()
+            .init_asset_loader::<SkinCatalogueLoader>()
+            .add_systems(Update, play_death_effect.run_if(on_event::<GameOverEvent>))
+            .add_systems(
+                OnEnter(GameState::Customization),
+                spawn_customization_screen,
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_customization_buttons,
+                    refresh_skin_list.run_if(
+                        resource_changed::<Wallet>.or(on_event::<AssetEvent<SkinCatalogue>>),
+                    ),
+                    animate_previews,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Customization)),
+            )
+            .add_systems(
+                OnExit(GameState::Customization),
+                despawn_entities::<OnCustomizationScreen>,
+            );
+    }
+}
+
+/// Взрыв частиц выбранного облика на месте гибели птицы
+fn play_death_effect(
+    mut commands: Commands,
+    mut game_over_events: EventReader<GameOverEvent>,
+    mut rng: ResMut<GameRng>,
+    skins: Skins,
+    bird_query: Query<&Transform, With<Bird>>,
+) {
+    game_over_events.clear();
+    let (Some(skin), Ok(transform)) = (skins.selected(), bird_query.get_single()) else {
+        return;
+    };
+    let effect = skin.death_effect;
+    spawn_particle_explosion(
+        &mut commands,
+        &mut rng,
+        transform.translation.truncate().extend(1.0),
+        srgb(effect.color),
+        effect.particles,
+        effect.size,
+        effect.speed,
+    );
+}
+
+fn spawn_customization_screen(mut commands: Commands, assets: Res<GameAssets>, skins: Skins) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnCustomizationScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("🎨 Облик птицы"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(720.0),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::vertical(Val::Px(16.0)),
+                        ..default()
+                    },
+                    SkinList,
+                ))
+                .with_children(|list| spawn_skin_cards(list, &assets, &skins));
+
+            parent.spawn((
+                Text::new("Новые облики открываются в магазине"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                CustomizationStatusText,
+            ));
+
+            spawn_menu_button(parent, &assets, "🔙 В магазин", CustomizationBackButton);
+        });
+}
+
+fn spawn_skin_cards(parent: &mut ChildBuilder, assets: &GameAssets, skins: &Skins) {
+    let Some(catalogue) = skins.catalogue() else {
+        parent.spawn((
+            Text::new(format!("Облики не загружены: assets/{}", CATALOGUE_PATH)),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        ));
+        return;
+    };
+    let selected = skins.selected().map(|skin| skin.id.as_str());
+
+    for skin in &catalogue.skins {
+        let lock = skins.lock(skin);
+        let (status, color) = match lock {
+            Some(SkinLock::Price(price)) => {
+                (format!("🔒 {} монет", price), Color::srgb(0.1, 0.1, 0.15))
+            }
+            Some(SkinLock::Unavailable) => {
+                ("🔒 Недоступен".to_string(), Color::srgb(0.1, 0.1, 0.15))
+            }
+            None if selected == Some(skin.id.as_str()) => {
+                ("✔ Выбран".to_string(), Color::srgb(0.2, 0.45, 0.3))
+            }
+            None => ("Выбрать".to_string(), Color::srgb(0.2, 0.3, 0.4)),
+        };
+        // Закрытый облик показываем тёмным силуэтом
+        let preview_tint = if lock.is_some() {
+            skin.tint.darker(0.5)
+        } else {
+            skin.tint
+        };
+
+        let sprite = skin.sprite();
+        let flap = skin.animation.states.flapping.clone();
+        let mut player = ClipPlayer::default();
+        player.play(&flap);
+
+        parent
+            .spawn((
+                Button,
+                Node {
+                    width: Val::Px(160.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    margin: UiRect::all(Val::Px(8.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(8.0)),
+                BackgroundColor(color),
+                SkinButton(skin.id.clone()),
+            ))
+            .with_children(|card| {
+                card.spawn((
+                    ImageNode {
+                        image: sprite.image,
+                        texture_atlas: sprite.texture_atlas,
+                        color: preview_tint,
+                        ..default()
+                    },
+                    Node {
+                        width: Val::Px(64.0),
+                        height: Val::Px(64.0),
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                    SkinPreview {
+                        clips: skin.animation.clips.clone(),
+                        flap,
+                        player,
+                    },
+                ));
+                card.spawn((
+                    Text::new(skin.name.clone()),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                card.spawn((
+                    Text::new(status),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.84, 0.0)),
+                ));
+            });
+    }
+}
+
+/// Выбор облика и возврат в магазин
+#[allow(clippy::too_many_arguments)]
+fn handle_customization_buttons(
+    skin_query: Query<(&Interaction, &SkinButton), Changed<Interaction>>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<CustomizationBackButton>)>,
+    skin_catalogues: Res<Assets<SkinCatalogue>>,
+    assets: Res<GameAssets>,
+    shop_catalogues: Res<Assets<ShopCatalogue>>,
+    shop_handle: Res<ShopCatalogueHandle>,
+    save_dir: Res<SaveDir>,
+    mut wallet: ResMut<Wallet>,
+    mut status_query: Query<&mut Text, With<CustomizationStatusText>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if back_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::Shop);
+        return;
+    }
+
+    let Some(catalogue) = skin_catalogues.get(&assets.skins) else {
+        return;
+    };
+    let shop = shop_catalogues.get(&shop_handle.0);
+    for (interaction, button) in &skin_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(skin) = catalogue.skin(&button.0) else {
+            continue;
+        };
+
+        let message = match skin_lock(catalogue, shop, &wallet, skin) {
+            Some(SkinLock::Price(price)) => {
+                format!("«{}» продаётся в магазине за {} монет", skin.name, price)
+            }
+            Some(SkinLock::Unavailable) => {
+                format!("«{}» недоступен: каталог магазина не загружен", skin.name)
+            }
+            None => {
+                wallet.equip(Slot::BirdSkin, &skin.id);
+                save::save(&save_dir, &*wallet);
+                format!("Выбран облик «{}»", skin.name)
+            }
+        };
+        for mut text in &mut status_query {
+            text.0 = message.clone();
+        }
+    }
+}
+
+/// Пересобирает карточки после выбора или изменения файла обликов
+fn refresh_skin_list(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    skins: Skins,
+    list_query: Query<Entity, With<SkinList>>,
+) {
+    for list in &list_query {
+        commands
+            .entity(list)
+            .despawn_descendants()
+            .with_children(|list| spawn_skin_cards(list, &assets, &skins));
+    }
+}
+
+fn animate_previews(time: Res<Time>, mut query: Query<(&mut SkinPreview, &mut ImageNode)>) {
+    for (mut preview, mut image) in &mut query {
+        let preview = &mut *preview;
+        preview.player.tick(&preview.clips, time.delta_secs());
+        // Взмах переходит к планированию, а в превью он повторяется
+        if preview.player.clip() != preview.flap {
+            preview.player.play(&preview.flap);
+        }
+        let index = preview.player.current(&preview.clips);
+        if let (Some(index), Some(atlas)) = (index, image.texture_atlas.as_mut()) {
+            atlas.index = index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessGame, HeadlessGameBuilder};
+    use crate::plugins::bird::BirdAnimation;
+    use crate::plugins::effects::Particle;
+    use crate::plugins::shop::parse_catalogue;
+
+    const SKINS: &[u8] = br#"(
+        animations: {
+            "sheet": (
+                sheet: "sheet.png",
+                tile: (12, 8),
+                columns: 3,
+                rows: 1,
+                clips: {
+                    "flap": (frames: [(index: 0, seconds: 0.1)], end: Next("glide")),
+                    "glide": (frames: [(index: 1, seconds: 0.5)]),
+                    "fall": (frames: [(index: 2, seconds: 0.5)]),
+                },
+                states: (idle: "glide", flapping: "flap", falling: "fall", dead: "fall"),
+            ),
+        },
+        skins: [
+            (id: "plain", name: "Plain", animation: "sheet"),
+            (id: "red", name: "Red", animation: "sheet", tint: (1.0, 0.2, 0.2),
+                death_effect: (particles: 7)),
+        ],
+    )"#;
+
+    /// Каталог с пустыми листами вместо картинок с диска
+    fn catalogue() -> SkinCatalogue {
+        parse_skins(SKINS)
+            .unwrap()
+            .into_catalogue(|_, _| (Handle::default(), Handle::default()))
+    }
+
+    /// Подменяет каталоги обликов и магазина; ручки нужно держать, пока идёт тест
+    fn install(game: &mut HeadlessGame) -> (Handle<SkinCatalogue>, Handle<ShopCatalogue>) {
+        let world = game.app.world_mut();
+        let skins = world
+            .resource_mut::<Assets<SkinCatalogue>>()
+            .add(catalogue());
+        let shop = parse_catalogue(
+            br#"(items: [(id: "red_item", name: "Red", price: 10, unlock: BirdSkin("red"))])"#,
+        )
+        .unwrap();
+        let shop = world.resource_mut::<Assets<ShopCatalogue>>().add(shop);
+        world.resource_mut::<GameAssets>().skins = skins.clone();
+        world.resource_mut::<ShopCatalogueHandle>().0 = shop.clone();
+        (skins, shop)
+    }
+
+    fn bird_color(game: &mut HeadlessGame) -> Color {
+        let world = game.app.world_mut();
+        world
+            .query_filtered::<&Sprite, With<Bird>>()
+            .single(world)
+            .color
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_skins() {
+        let shipped = include_bytes!("../../assets/skins/catalogue.skins.ron");
+        let shipped = parse_skins(shipped).unwrap();
+        assert_eq!(shipped.skins[0].id, "classic");
+        assert_eq!(shipped.skins[0].tint, white());
+
+        assert!(matches!(
+            parse_skins(br#"(animations: {}, skins: [])"#),
+            Err(SkinLoadError::Invalid(_))
+        ));
+        let unknown_sheet = br#"(animations: {}, skins: [(id: "a", name: "A", animation: "x")])"#;
+        assert!(parse_skins(unknown_sheet).is_err());
+        let text = std::str::from_utf8(SKINS).unwrap();
+        let duplicate = text.replace(r#"id: "red""#, r#"id: "plain""#);
+        assert!(parse_skins(duplicate.as_bytes()).is_err());
+        let missing_state = text.replace(r#"dead: "fall""#, r#"dead: "tumble""#);
+        assert!(parse_skins(missing_state.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_locked_skin_falls_back_to_default() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let _handles = install(&mut game);
+
+        // Облик выбран, но не куплен — птица остаётся обычной
+        game.resource_mut::<Wallet>().equip(Slot::BirdSkin, "red");
+        game.start_run();
+        assert_eq!(bird_color(&mut game), Color::srgb(1.0, 1.0, 1.0));
+
+        game.app.world_mut().send_event(GameOverEvent);
+        game.step();
+        assert_eq!(game.count::<Particle>(), 100);
+    }
+
+    #[test]
+    fn test_missing_shop_catalogue_locks_paid_skins() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let _handles = install(&mut game);
+        // Каталог магазина не разобрался: неизвестно, какие облики платные
+        game.resource_mut::<ShopCatalogueHandle>().0 = Handle::default();
+        game.resource_mut::<NextState<GameState>>()
+            .set(GameState::Customization);
+        assert!(game.step_until_state(GameState::Customization));
+
+        let world = game.app.world_mut();
+        let red = world
+            .query::<(Entity, &SkinButton)>()
+            .iter(world)
+            .find(|(_, button)| button.0 == "red")
+            .map(|(entity, _)| entity)
+            .unwrap();
+        world.entity_mut(red).insert(Interaction::Pressed);
+        game.step();
+        assert_eq!(game.resource::<Wallet>().equipped(Slot::BirdSkin), None);
+
+        // Облик по умолчанию выбрать можно всегда
+        let world = game.app.world_mut();
+        let plain = world
+            .query::<(Entity, &SkinButton)>()
+            .iter(world)
+            .find(|(_, button)| button.0 == "plain")
+            .map(|(entity, _)| entity)
+            .unwrap();
+        world.entity_mut(plain).insert(Interaction::Pressed);
+        game.step();
+        assert_eq!(
+            game.resource::<Wallet>().equipped(Slot::BirdSkin),
+            Some("plain")
+        );
+    }
+
+    #[test]
+    fn test_selected_skin_tints_bird_and_sets_death_effect() {
+        let mut game = HeadlessGameBuilder::default().build();
+        let _handles = install(&mut game);
+        game.resource_mut::<Wallet>().coins = 10;
+        game.app
+            .world_mut()
+            .resource_scope(|world, mut wallet: Mut<Wallet>| {
+                let catalogue = world.resource::<Assets<ShopCatalogue>>();
+                let handle = &world.resource::<ShopCatalogueHandle>().0;
+                let item = catalogue.get(handle).unwrap().item("red_item").unwrap();
+                wallet.buy(item).unwrap();
+            });
+
+        game.start_run();
+        assert_eq!(bird_color(&mut game), Color::srgb(1.0, 0.2, 0.2));
+
+        game.app.world_mut().send_event(GameOverEvent);
+        game.step();
+        assert_eq!(game.count::<Particle>(), 7);
+    }
+
+    #[test]
+    fn test_bird_plays_clip_for_its_state() {
+        fn clip(game: &mut HeadlessGame) -> (String, Option<usize>) {
+            let world = game.app.world_mut();
+            let (animation, sprite) = world
+                .query_filtered::<(&BirdAnimation, &Sprite), With<Bird>>()
+                .single(world);
+            let index = sprite.texture_atlas.as_ref().map(|atlas| atlas.index);
+            (animation.player.clip().to_string(), index)
+        }
+
+        let mut game = HeadlessGameBuilder::default().build();
+        let _handles = install(&mut game);
+        game.start_run();
+        assert_eq!(clip(&mut game), ("glide".to_string(), Some(1)));
+
+        game.tap_key(KeyCode::Space);
+        game.step();
+        assert_eq!(clip(&mut game), ("flap".to_string(), Some(0)));
+        // Взмах разовый: через 0.1 с птица ещё летит вверх, но уже планирует
+        game.step_frames(7);
+        assert_eq!(clip(&mut game), ("glide".to_string(), Some(1)));
+
+        game.step_frames(20);
+        assert_eq!(clip(&mut game), ("fall".to_string(), Some(2)));
+    }
+}
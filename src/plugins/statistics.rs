@@ -1,15 +1,22 @@
 use crate::core::{
     components::*,
-    resources::{GameMode, GameScore},
+    resources::{is_cheated_run, GameMode, GameScore},
+    save::{self, is_app_exiting, SaveData, SaveDir},
     utils::despawn_entities
 };
 use crate::plugins::audio::{JumpEvent, ScoreEvent};
 use crate::plugins::powerups::PowerUpCollectedEvent;
+use crate::plugins::profiles::{ProfileSwitched, DEFAULT_PROFILE_ID};
+use crate::plugins::editor::is_editor_playtest;
+use crate::plugins::obstacles::ObstacleDodgedEvent;
+use crate::plugins::replay::is_replay_playing;
 use crate::plugins::settings_ui::spawn_menu_button;
 use crate::states::{app_state::AppState, game_state::GameState};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Плагин статистики
 pub struct StatisticsPlugin;
@@ -18,15 +25,24 @@ impl Plugin for StatisticsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GameStatistics>()
             .add_systems(Startup, load_statistics)
+            .add_systems(Update, load_statistics.run_if(on_event::<ProfileSwitched>))
             .add_systems(
                 Update,
                 (
                     track_game_events,
-                    track_bird_jumps,
-                    track_powerup_collection,
-                    track_pipe_passing,
+                    (
+                        track_bird_jumps,
+                        track_powerup_collection,
+                        track_pipe_passing,
+                        track_obstacles_dodged,
+                    )
+                        .run_if(not(is_cheated_run)),
                 )
-                    .run_if(in_state(AppState::Loaded)),
+                    .run_if(
+                        in_state(AppState::Loaded)
+                            .and(not(is_replay_playing))
+                            .and(not(is_editor_playtest)),
+                    ),
             )
             .add_systems(
                 OnEnter(GameState::Statistics),
@@ -36,7 +52,16 @@ impl Plugin for StatisticsPlugin {
                 OnExit(GameState::Statistics),
                 despawn_entities::<OnStatisticsScreen>,
             )
-            .add_systems(OnExit(AppState::Loaded), save_statistics);
+            // Статистика пишется на диск в конце каждого забега, при выходе в меню
+            // и при закрытии игры
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (is_cheated_run.pipe(record_run_end), save_statistics)
+                    .chain()
+                    .run_if(not(is_replay_playing).and(not(is_editor_playtest))),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), save_statistics)
+            .add_systems(Last, save_statistics.run_if(is_app_exiting));
     }
 }
 
@@ -54,6 +79,7 @@ pub struct GameStatistics {
     pub jumps_made: u64,
     pub pipes_passed: u64,
     pub powerups_collected: u64,
+    pub obstacles_dodged: u64,
 
     // Статистика по режимам игры
     pub games_by_mode: HashMap<String, u32>,
@@ -88,6 +114,7 @@ impl Default for GameStatistics {
             jumps_made: 0,
             pipes_passed: 0,
             powerups_collected: 0,
+            obstacles_dodged: 0,
             games_by_mode: HashMap::new(),
             best_score_by_mode: HashMap::new(),
             total_time_by_mode: HashMap::new(),
@@ -141,6 +168,14 @@ impl GameStatistics {
         *self.games_by_mode.entry(mode_str.clone()).or_insert(0) += 1;
     }
 
+    /// Отменяет учёт начатой игры, если её итог не засчитывается
+    pub fn discard_game(&mut self, mode: &GameMode) {
+        self.total_games = self.total_games.saturating_sub(1);
+        if let Some(games) = self.games_by_mode.get_mut(&format!("{:?}", mode)) {
+            *games = games.saturating_sub(1);
+        }
+    }
+
     /// Регистрирует окончание игры
     pub fn end_game(&mut self, score: u32, mode: &GameMode, was_perfect: bool) {
         self.total_score += score as u64;
@@ -195,6 +230,11 @@ impl GameStatistics {
         }
     }
 
+    /// Регистрирует пройденное препятствие
+    pub fn register_obstacle_dodged(&mut self) {
+        self.obstacles_dodged += 1;
+    }
+
     /// Регистрирует смерть
     pub fn register_death(&mut self) {
         self.total_deaths += 1;
@@ -202,14 +242,12 @@ impl GameStatistics {
 }
 
 /// Система отслеживания игровых событий
+#[allow(clippy::collapsible_match)]
 pub fn track_game_events(
     time: Res<Time>,
     mut statistics: ResMut<GameStatistics>,
     game_state: Res<State<GameState>>,
-    score: Res<GameScore>,
     mode_settings: Res<crate::core::resources::GameModeSettings>,
-    bird_query: Query<Entity, With<crate::core::components::Bird>>,
-    powerup_query: Query<&PowerUp>,
 ) {
     let current_time = time.elapsed_secs();
 
@@ -226,24 +264,36 @@ pub fn track_game_events(
                 statistics.start_game(&mode_settings.current_mode);
             }
         }
-        GameState::GameOver => {
-            if statistics.current_session_start.is_some() {
-                statistics.end_game(
-                    score.0,
-                    &mode_settings.current_mode,
-                    powerup_query.is_empty(),
-                );
-                statistics.end_session(current_time);
-            }
-        }
         _ => {}
     }
+}
 
-    // Регистрируем смерть
-    // когда мы заходим в GameOver то птица все равно остается на экране, надо по другому признаку смотреть
-    if *game_state.get() == GameState::GameOver && bird_query.is_empty() {
-        statistics.register_death();
+/// Подводит итог забега при входе в Game Over. Забег, изменённый из консоли,
+/// не попадает в статистику, но сессия всё равно закрывается
+pub fn record_run_end(
+    In(cheated): In<bool>,
+    time: Res<Time>,
+    mut statistics: ResMut<GameStatistics>,
+    score: Res<GameScore>,
+    mode_settings: Res<crate::core::resources::GameModeSettings>,
+    powerup_query: Query<&PowerUp>,
+) {
+    if statistics.current_session_start.is_none() {
+        return;
+    }
+    if cheated {
+        statistics.discard_game(&mode_settings.current_mode);
+        statistics.end_session(time.elapsed_secs());
+        return;
     }
+
+    statistics.end_game(
+        score.0,
+        &mode_settings.current_mode,
+        powerup_query.is_empty(),
+    );
+    statistics.end_session(time.elapsed_secs());
+    statistics.register_death();
 }
 
 /// Система отслеживания прыжков птицы
@@ -276,6 +326,16 @@ pub fn track_pipe_passing(
     }
 }
 
+/// Система отслеживания пройденных препятствий
+pub fn track_obstacles_dodged(
+    mut statistics: ResMut<GameStatistics>,
+    mut dodged_events: EventReader<ObstacleDodgedEvent>,
+) {
+    for _event in dodged_events.read() {
+        statistics.register_obstacle_dodged();
+    }
+}
+
 /// Система отображения экрана статистики
 pub fn spawn_statistics_screen(
     mut commands: Commands,
@@ -387,6 +447,10 @@ pub fn spawn_statistics_screen(
                                         "Power-ups собрано: {}",
                                         statistics.powerups_collected
                                     ),
+                                    &format!(
+                                        "Препятствий пройдено: {}",
+                                        statistics.obstacles_dodged
+                                    ),
                                     &format!("Смертей: {}", statistics.total_deaths),
                                 ],
                             );
@@ -465,30 +529,118 @@ fn spawn_statistics_section(
         });
 }
 
-/// Система сохранения статистики
-pub fn save_statistics(statistics: Res<GameStatistics>) {
-    // Сохраняем статистику в JSON файл
-    if let Ok(json) = serde_json::to_string_pretty(&*statistics) {
-        if let Err(e) = std::fs::write("flappy_bird_statistics.json", json) {
-            eprintln!("Ошибка сохранения статистики: {}", e);
-        } else {
-            println!("Статистика успешно сохранена в flappy_bird_statistics.json");
+/// Файл статистики старых версий, которые сохраняли её в рабочий каталог
+const LEGACY_STATISTICS_FILE: &str = "flappy_bird_statistics.json";
+
+impl SaveData for GameStatistics {
+    const FILE_NAME: &'static str = "statistics.json";
+    const VERSION: u32 = 2;
+
+    fn migrate(version: u32, mut data: Value) -> Result<Value, String> {
+        match version {
+            0 => Ok(data),
+            // В версии 2 появился счётчик пройденных препятствий
+            1 => {
+                data["obstacles_dodged"] = Value::from(0);
+                Ok(data)
+            }
+            _ => Err(format!("нет миграции с версии {}", version)),
         }
     }
 }
 
-/// Система загрузки статистики
-pub fn load_statistics(mut commands: Commands) {
-    if let Ok(json) = std::fs::read_to_string("flappy_bird_statistics.json") {
-        if let Ok(statistics) = serde_json::from_str::<GameStatistics>(&json) {
-            commands.insert_resource(statistics);
-            println!("Статистика успешно загружена из файла");
-        } else {
-            eprintln!("Ошибка парсинга файла статистики, используются значения по умолчанию");
+/// Система сохранения статистики
+pub fn save_statistics(save_dir: Res<SaveDir>, statistics: Res<GameStatistics>) {
+    save::save(&save_dir, &*statistics);
+}
+
+/// Загружает статистику активного профиля при запуске и при смене профиля
+pub fn load_statistics(save_dir: Res<SaveDir>, mut statistics: ResMut<GameStatistics>) {
+    // Файл старой версии принадлежит профилю по умолчанию
+    let loaded = match save_dir.profile.as_deref() {
+        None | Some(DEFAULT_PROFILE_ID) => {
+            load_or_import_statistics(&save_dir, Path::new(LEGACY_STATISTICS_FILE))
         }
-    } else {
-        println!("Файл статистики не найден, создается новая статистика");
+        Some(_) => save::load(&save_dir),
+    };
+    *statistics = loaded.unwrap_or_default();
+}
+
+/// Читает статистику из каталога сохранений. Если её там ещё нет, один раз
+/// переносит файл старой версии и сразу сохраняет его в новом формате
+fn load_or_import_statistics(save_dir: &SaveDir, legacy_path: &Path) -> Option<GameStatistics> {
+    let path = save_dir.path(GameStatistics::FILE_NAME)?;
+    if path.exists() {
+        return save::load_from(&path);
     }
+
+    let imported = save::load_from::<GameStatistics>(legacy_path)?;
+    save::save(save_dir, &imported);
+    println!(
+        "Статистика перенесена из {} в {}",
+        legacy_path.display(),
+        path.display()
+    );
+    Some(imported)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::RestartButton;
+    use crate::core::headless::{HeadlessGameBuilder, TempSaveDir};
+    use std::fs;
+
+    #[test]
+    fn test_statistics_saved_after_each_run_and_on_exit() {
+        let temp = TempSaveDir::new("statistics_runs");
+        let save_dir = temp.save_dir();
+        let mut game = HeadlessGameBuilder::default().build();
+        game.app.insert_resource(save_dir.clone());
+
+        game.start_run();
+        game.step_frames(60);
+        assert_eq!(game.game_state(), GameState::GameOver);
+        let after_first = save::load::<GameStatistics>(&save_dir).map(|stats| stats.total_games);
+
+        // Рестарт прыжком: счётчики продолжают копиться, а не начинаются заново
+        game.click_button::<RestartButton>();
+        assert!(game.step_until_state(GameState::PreGame));
+        game.tap_key(KeyCode::Space);
+        game.step_frames(90);
+        assert_eq!(game.game_state(), GameState::GameOver);
+        let after_second = save::load::<GameStatistics>(&save_dir);
+
+        game.resource_mut::<GameStatistics>().jumps_made += 100;
+        game.app.world_mut().send_event(AppExit::Success);
+        game.step();
+        let after_exit = save::load::<GameStatistics>(&save_dir);
+
+        assert_eq!(after_first, Some(1));
+        let after_second = after_second.unwrap();
+        assert_eq!(after_second.total_games, 2);
+        assert_eq!(after_second.total_deaths, 2);
+        assert_eq!(after_second.jumps_made, 1);
+        assert_eq!(after_exit.map(|stats| stats.jumps_made), Some(101));
+    }
 
+    #[test]
+    fn test_legacy_statistics_are_imported_once() {
+        let temp = TempSaveDir::new("statistics_test");
+        let save_dir = SaveDir::new(Some(temp.path().join("saves")));
+        let legacy_path = temp.path().join(LEGACY_STATISTICS_FILE);
+        let legacy = GameStatistics {
+            total_games: 12,
+            ..default()
+        };
+        fs::write(&legacy_path, serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let imported = load_or_import_statistics(&save_dir, &legacy_path);
+        // Старый файл больше не читается: новая статистика уже в каталоге сохранений
+        fs::write(&legacy_path, "{}").unwrap();
+        let reloaded = load_or_import_statistics(&save_dir, &legacy_path);
+
+        assert_eq!(imported.map(|stats| stats.total_games), Some(12));
+        assert_eq!(reloaded.map(|stats| stats.total_games), Some(12));
+    }
+}
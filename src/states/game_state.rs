@@ -5,20 +5,79 @@ pub enum GameState {
     #[default]
     MainMenu,
     GameModeSelection,
+    CourseSelection,
+    Editor,
     Settings,
     Statistics,
+    Profiles,
+    Achievements,
+    Shop,
+    Customization,
     PreGame,
     Playing,
-    Paused,
     GameOver,
 }
 
+/// Пауза внутри забега.
+///
+/// На паузе `GameState` остаётся `Playing`: `OnExit(GameState::Playing)` не срабатывает,
+/// поэтому таймеры режимов, жизни и power-ups переживают паузу.
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(GameState = GameState::Playing)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    /// Настройки, открытые из меню паузы
+    Settings,
+}
+
+/// Открыт экран настроек: из главного меню или из меню паузы
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SettingsOpen;
+
+impl ComputedStates for SettingsOpen {
+    type SourceStates = (GameState, Option<PauseState>);
+
+    fn compute((game_state, pause_state): (GameState, Option<PauseState>)) -> Option<Self> {
+        let open = game_state == GameState::Settings || pause_state == Some(PauseState::Settings);
+        open.then_some(SettingsOpen)
+    }
+}
+
+/// Страница экрана настроек
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(SettingsOpen = SettingsOpen)]
+pub enum SettingsPage {
+    #[default]
+    General,
+    Controls,
+}
+
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum GameOverSet {
+    SaveReplay,
     UpdateScores,
     SpawnUi,
 }
 
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum PreGameSet {
+    Cleanup,
+    Seed,
+    Spawn,
+}
+
+/// Порядок шагов симуляции внутри `FixedUpdate`
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum SimulationSet {
+    Spawn,
+    Movement,
+    Collision,
+    Scoring,
+    Timers,
+}
+
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum EffectsSet {
     SpawnCam,